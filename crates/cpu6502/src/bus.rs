@@ -0,0 +1,28 @@
+pub trait BusReader {
+    fn bus_read(&mut self, addr: u16, read_only: bool) -> u8;
+}
+
+pub trait BusWriter {
+    fn bus_write(&mut self, addr: u16, data: u8);
+}
+
+/// Anything the cpu can be plugged into
+pub trait CpuBus: BusReader + BusWriter {}
+
+impl<T: BusReader + BusWriter> CpuBus for T {}
+
+/// A bus that can't be built, for when all that's needed out of the
+/// instruction table are the names and addressing modes
+pub enum NoBus {}
+
+impl BusReader for NoBus {
+    fn bus_read(&mut self, _addr: u16, _read_only: bool) -> u8 {
+        match *self {}
+    }
+}
+
+impl BusWriter for NoBus {
+    fn bus_write(&mut self, _addr: u16, _data: u8) {
+        match *self {}
+    }
+}
@@ -0,0 +1,325 @@
+use crate::bus::{BusReader, BusWriter, CpuBus};
+use crate::instructions::{
+    instruction::{
+        AddressingMode, 
+        Instruction,
+        InstructionSet,
+        Operation,
+    },
+    instruction_cycles::instruction_cycle,
+};
+use bitflags::bitflags;
+use std::{
+    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign},
+};
+
+bitflags! {
+    pub struct CPUFlags: u8 {
+        const C = 1 << 0; // 0x01 // Carry Bit
+        const Z = 1 << 1; // 0x02 // Zero
+        const I = 1 << 2; // 0x04 // Disable Interupts
+        const D = 1 << 3; // 0x08 // Decimal Mode
+        const B = 1 << 4; // 0x10 // Break
+        const U = 1 << 5; // 0x20 // Unused
+        const V = 1 << 6; // 0x40 // Overflow
+        const N = 1 << 7; // 0x80 // Negative
+    }
+}
+
+impl BitAnd<CPUFlags> for u8 {
+    type Output = u8;
+    fn bitand(self, rhs: CPUFlags) -> Self::Output {
+        self & rhs.bits
+    }
+}
+
+impl BitAndAssign<CPUFlags> for u8 {
+    fn bitand_assign(&mut self, rhs: CPUFlags) {
+        *self = *self & rhs.bits
+    }
+}
+
+impl BitOr<CPUFlags> for u8 {
+    type Output = u8;
+    fn bitor(self, rhs: CPUFlags) -> Self::Output {
+        self | rhs.bits
+    }
+}
+
+impl BitOrAssign<CPUFlags> for u8 {
+    fn bitor_assign(&mut self, rhs: CPUFlags) {
+        *self = *self | rhs.bits
+    }
+}
+
+impl BitXor<CPUFlags> for u8 {
+    type Output = u8;
+    fn bitxor(self, rhs: CPUFlags) -> Self::Output {
+        self ^ rhs.bits
+    }
+}
+
+impl BitXorAssign<CPUFlags> for u8 {
+    fn bitxor_assign(&mut self, rhs: CPUFlags) {
+        *self = *self ^ rhs.bits
+    }
+}
+
+/// The nes plugs it into its `Bus`, the tests into a flat 64k of ram
+pub struct Cpu6502<B> {
+    pub acc: u8,
+    pub x_reg: u8,
+    pub y_reg: u8,
+    pub stack_pointer: u8,
+    pub pc: u16,
+    pub status: u8,
+
+    pub fetched: u8,
+    pub(crate) temp: u16,
+    pub(crate) addr_abs: u16,
+    pub(crate) addr_rel: u16,
+    pub(crate) addressing_mode: AddressingMode,
+    pub(crate) operation: Operation,
+    /// Set once the operand has been read, read-modify-writes and implied
+    /// instructions have it before the instruction runs
+    pub(crate) fetched_ready: bool,
+    pub(crate) branch_taken: bool,
+    /// Idle cycles left over from a reset
+    pub(crate) cycles: u8,
+    /// Cycle of the current instruction, 0 when the next clock fetches an opcode
+    pub(crate) step: u8,
+    pub(crate) opcode: u8,
+    pub clock_count: u32,
+    pub instruction_count: usize,
+    pub instruction: Instruction<B>,
+    pub instruction_complete: bool,
+    /// The interrupt pins, driven by whoever owns the cpu. Nmi fires on the
+    /// line going high, irq for as long as it's held high.
+    pub nmi_line: bool,
+    pub irq_line: bool,
+    prev_nmi_line: bool,
+    /// The opcode fetch was swapped out for an irq or nmi, BRK runs without
+    /// moving pc and pushes status with B clear
+    pub(crate) hardware_interrupt: bool,
+    /// Latched by the nmi edge detector until the interrupt sequence takes it
+    pub(crate) need_nmi: bool,
+    pub(crate) prev_need_nmi: bool,
+    /// Keeps an nmi that came in during a taken branch's operand fetch from
+    /// being polled at the end of the branch's next cycle
+    pub(crate) skip_nmi_poll: bool,
+    /// The irq line is asserted and the I flag is clear
+    pub(crate) run_irq: bool,
+    pub(crate) prev_run_irq: bool,
+    /// Set by the JAM opcodes, the cpu stays stuck on the same instruction until a reset
+    pub(crate) jammed: bool,
+    /// The 2A03 had the decimal mode circuitry cut, a stock 6502 still has it.
+    /// Off by default, set it for anything that isn't a nes.
+    pub decimal_enabled: bool,
+
+    pub bus: B,
+}
+
+impl<B: CpuBus> Cpu6502<B> {
+    pub(crate) fn read_bus(&mut self, addr: u16) -> u8 {
+        self.bus_read(addr, false)
+    }
+
+    pub(crate) fn write_bus(&mut self, addr: u16, data: u8) {
+        self.bus_write(addr, data)
+    }
+
+    pub(crate) fn get_flag(&self, flag: CPUFlags) -> u8 {
+        if self.status & flag > 0 {
+            1
+        } else {
+            0
+        }
+    }
+
+    pub(crate) fn read_bus_two_bytes(&mut self, addr: u16) -> u16 {
+        let lo: u16 = self.bus_read(addr, false) as u16;
+        let hi: u16 = (self.bus_read(addr + 1, false) as u16) << 8;
+        hi | lo
+    }
+
+    pub(crate) fn push(&mut self, data: u8) {
+        self.write_bus(0x0100 + self.stack_pointer as u16, data);
+        self.stack_pointer = self.stack_pointer.wrapping_sub(1);
+    }
+
+    pub(crate) fn pull(&mut self) -> u8 {
+        self.stack_pointer = self.stack_pointer.wrapping_add(1);
+        self.read_bus(0x0100 + self.stack_pointer as u16)
+    }
+
+    pub(crate) fn set_flag(&mut self, flag: CPUFlags, v: bool) {
+        if v {
+            self.status |= flag;
+        } else {
+            self.status &= !flag;
+        }
+    }
+
+    /// Runs a single cycle, every cycle does exactly one read or write
+    pub fn clock(&mut self) {
+        let mut instruction_done = false;
+        if self.cycles > 0 {
+            self.cycles -= 1;
+        } else if self.step == 0 {
+            if self.interrupt_pending() {
+                // The fetched opcode gets thrown away and BRK runs in its place
+                self.read_bus(self.pc);
+                self.opcode = 0x00;
+                self.hardware_interrupt = true;
+            } else {
+                self.opcode = self.read_bus(self.pc);
+                self.pc = self.pc.wrapping_add(1);
+                self.hardware_interrupt = false;
+            }
+
+            self.instruction = InstructionSet::<B>::LOOKUP[self.opcode as usize];
+            self.addressing_mode = self.instruction.addr_mode;
+            self.operation = self.instruction.operation();
+            self.fetched_ready = false;
+            self.branch_taken = false;
+            self.step = 1;
+        } else if instruction_cycle(self) {
+            // Page crossings and taken branches can only make it longer
+            debug_assert!(self.step + 1 >= self.instruction.clock_cycles);
+            // make suuuuuuure its set
+            self.set_flag(CPUFlags::U, true);
+            self.step = 0;
+            self.instruction_count += 1;
+            self.instruction_complete = true;
+            instruction_done = true;
+        } else {
+            self.step += 1;
+        }
+        self.poll_interrupts();
+        if instruction_done && self.opcode == 0x00 {
+            // Nothing is polled on the last cycle of BRK or an interrupt,
+            // the handler always gets to run its first instruction
+            self.prev_need_nmi = false;
+        }
+        self.clock_count += 1;
+    }
+
+    // https://www.nesdev.org/wiki/CPU_interrupts
+    // The lines get sampled at the end of every cycle, but what the cpu acts on
+    // is how they looked at the end of the second to last cycle of an
+    // instruction. That's why CLI, SEI and PLP only change things one
+    // instruction late while RTI is immediate.
+    fn poll_interrupts(&mut self) {
+        self.prev_need_nmi = self.need_nmi && !std::mem::take(&mut self.skip_nmi_poll);
+        if self.nmi_line && !self.prev_nmi_line {
+            self.need_nmi = true;
+        }
+        self.prev_nmi_line = self.nmi_line;
+        self.prev_run_irq = self.run_irq;
+        self.run_irq = self.irq_line && self.get_flag(CPUFlags::I) == 0;
+    }
+
+    /// True if the next instruction will be an interrupt instead
+    pub fn interrupt_pending(&self) -> bool {
+        (self.prev_need_nmi || self.prev_run_irq) && !self.jammed
+    }
+
+    /// True when the next clock starts a new instruction
+    pub fn between_instructions(&self) -> bool {
+        self.cycles == 0 && self.step == 0
+    }
+
+    pub fn reset(&mut self, reset_vector: Option<u16>) {
+        self.addr_abs = 0xFFFC;
+        match reset_vector {
+            Some(x) => self.pc = x,
+            None => self.pc = self.read_bus_two_bytes(self.addr_abs),
+        };
+        self.acc = 0;
+        self.x_reg = 0;
+        self.y_reg = 0;
+
+        // self.stack_pointer = self.stack_pointer.wrapping_sub(3);
+
+        // Reset masks interrupts, the same as any other interrupt sequence
+        self.status = 0x00 | CPUFlags::U | CPUFlags::I;
+
+        self.addr_rel = 0x0000;
+        self.addr_abs = 0x0000;
+        self.fetched = 0x0000;
+        self.instruction_count = 0;
+        self.jammed = false;
+
+        self.clock_count = 0;
+        self.step = 0;
+        self.need_nmi = false;
+        self.prev_need_nmi = false;
+        self.skip_nmi_poll = false;
+        self.run_irq = false;
+        self.prev_run_irq = false;
+
+        self.cycles = 7;
+    }
+
+    pub fn is_jammed(&self) -> bool {
+        self.jammed
+    }
+
+    pub(crate) fn fetch(&mut self) -> u8 {
+        if !self.fetched_ready {
+            self.fetched = self.read_bus(self.addr_abs);
+            self.fetched_ready = true;
+        }
+        self.fetched
+    }
+
+    pub fn new(b: B) -> Self {
+        Cpu6502 {
+            acc: 0x00,
+            x_reg: 0x00,
+            y_reg: 0x00,
+            stack_pointer: 0xFD,
+            pc: 0x0000,
+            status: 0x00,
+            fetched: 0x00,
+            temp: 0x0000,
+            addr_abs: 0xFFFC,
+            addr_rel: 0x00,
+            addressing_mode: AddressingMode::ABS,
+            operation: Operation::Read,
+            fetched_ready: false,
+            branch_taken: false,
+            opcode: 0x00,
+            cycles: 1,
+            step: 0,
+            clock_count: 0,
+            instruction_count: 0,
+            instruction_complete: false,
+            hardware_interrupt: false,
+            need_nmi: false,
+            prev_need_nmi: false,
+            skip_nmi_poll: false,
+            run_irq: false,
+            prev_run_irq: false,
+            nmi_line: false,
+            irq_line: false,
+            prev_nmi_line: false,
+            jammed: false,
+            decimal_enabled: false,
+            bus: b,
+            instruction: InstructionSet::<B>::LOOKUP[0xFF],
+        }
+    }
+}
+
+impl<B: CpuBus> BusReader for Cpu6502<B> {
+    fn bus_read(&mut self, addr: u16, read_only: bool) -> u8 {
+        self.bus.bus_read(addr, read_only)
+    }
+}
+
+impl<B: CpuBus> BusWriter for Cpu6502<B> {
+    fn bus_write(&mut self, addr: u16, data: u8) {
+        self.bus.bus_write(addr, data);
+    }
+}
@@ -0,0 +1,260 @@
+use crate::bus::{BusReader, BusWriter, CpuBus};
+use crate::cpu::{CPUFlags, Cpu6502};
+use std::fs;
+
+// Klaus Dormann's 6502_functional_test.bin, assembled with the default
+// settings, from https://github.com/Klaus2m5/6502_65C02_functional_tests
+const FUNCTIONAL_TEST_BIN: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../test-roms/cpu/6502_functional_test.bin"
+);
+// Where the default build of the functional test loops once everything passed
+const FUNCTIONAL_TEST_SUCCESS: u16 = 0x3469;
+
+/// 64k of plain ram with nothing mapped, what the 6502 test suites expect
+struct FlatBus {
+    ram: Vec<u8>,
+}
+
+impl BusReader for FlatBus {
+    fn bus_read(&mut self, addr: u16, _read_only: bool) -> u8 {
+        self.ram[addr as usize]
+    }
+}
+
+impl BusWriter for FlatBus {
+    fn bus_write(&mut self, addr: u16, data: u8) {
+        self.ram[addr as usize] = data;
+    }
+}
+
+// The test suites report both success and failure by jumping or branching to
+// themselves forever, returns the pc of that loop
+fn run_to_trap<B: CpuBus>(cpu: &mut Cpu6502<B>) -> u16 {
+    let mut last_pc = None;
+    loop {
+        cpu.clock();
+        while !cpu.between_instructions() && !cpu.jammed {
+            cpu.clock();
+        }
+        if cpu.jammed || last_pc == Some(cpu.pc) {
+            return cpu.pc;
+        }
+        last_pc = Some(cpu.pc);
+    }
+}
+
+#[test]
+fn test_klaus_functional() {
+    // The suite is GPL so it doesn't ship with the repo
+    let mut ram = match fs::read(FUNCTIONAL_TEST_BIN) {
+        Ok(ram) => ram,
+        Err(e) => {
+            eprintln!("skipping the functional test, can't read {}: {}", FUNCTIONAL_TEST_BIN, e);
+            return;
+        }
+    };
+    ram.resize(0x10000, 0);
+    let mut cpu = Cpu6502::new(FlatBus { ram });
+    cpu.decimal_enabled = true;
+    cpu.reset(Some(0x0400));
+
+    let trap = run_to_trap(&mut cpu);
+    assert_eq!(
+        trap, FUNCTIONAL_TEST_SUCCESS,
+        "functional test trapped at {:04X}, test number {:02X}",
+        trap,
+        cpu.bus.ram[0x0200]
+    );
+}
+
+// Bruce Clark's model of what the nmos 6502 does in decimal mode, from
+// http://www.6502.org/tutorials/decimal_mode.html (appendix B). Gives the
+// accumulator and carry, valid bcd or not.
+type DecimalModel = fn(u8, u8, bool) -> (u8, bool);
+
+fn decimal_adc(a: u8, b: u8, carry: bool) -> (u8, bool) {
+    let mut low = (a & 0x0F) as u16 + (b & 0x0F) as u16 + carry as u16;
+    if low >= 0x0A {
+        low = ((low + 0x06) & 0x0F) + 0x10;
+    }
+    let mut result = (a & 0xF0) as u16 + (b & 0xF0) as u16 + low;
+    if result >= 0xA0 {
+        result += 0x60;
+    }
+    (result as u8, result >= 0x100)
+}
+
+fn decimal_sbc(a: u8, b: u8, carry: bool) -> (u8, bool) {
+    let mut low = (a & 0x0F) as i16 - (b & 0x0F) as i16 + carry as i16 - 1;
+    if low < 0 {
+        low = ((low - 0x06) & 0x0F) - 0x10;
+    }
+    let mut result = (a & 0xF0) as i16 - (b & 0xF0) as i16 + low;
+    if result < 0 {
+        result -= 0x60;
+    }
+    // The carry is the same as in binary mode
+    let binary = a as i16 - b as i16 + carry as i16 - 1;
+    (result as u8, binary >= 0)
+}
+
+#[test]
+fn test_decimal_mode() {
+    let mut cpu = Cpu6502::new(FlatBus {
+        ram: vec![0; 0x10000],
+    });
+    cpu.decimal_enabled = true;
+
+    let models: [(u8, DecimalModel); 2] = [(0x69, decimal_adc), (0xE9, decimal_sbc)];
+    for &(opcode, model) in models.iter() {
+        for a in 0..=0xFF {
+            for b in 0..=0xFF {
+                for &carry in [false, true].iter() {
+                    // LDA #a, CLC/SEC, SED, ADC/SBC #b, PHP, JMP *
+                    let set_carry = if carry { 0x38 } else { 0x18 };
+                    let program = [0xA9, a, set_carry, 0xF8, opcode, b, 0x08, 0x4C, 0x08, 0x04];
+                    cpu.bus.ram[0x0400..0x0400 + program.len()].copy_from_slice(&program);
+                    cpu.reset(Some(0x0400));
+                    run_to_trap(&mut cpu);
+
+                    let status = cpu.bus.ram[0x0100 + cpu.stack_pointer.wrapping_add(1) as usize];
+                    let got = (cpu.acc, status & CPUFlags::C > 0);
+                    assert_eq!(
+                        got,
+                        model(a, b, carry),
+                        "opcode {:02X} with A={:02X} operand={:02X} carry={}",
+                        opcode,
+                        a,
+                        b,
+                        carry
+                    );
+                }
+            }
+        }
+    }
+}
+
+const NMI_HANDLER: u16 = 0x0600;
+const IRQ_HANDLER: u16 = 0x0700;
+
+// NOPs everywhere with `program` at $0400, run up to its first instruction
+fn program_cpu(program: &[u8]) -> Cpu6502<FlatBus> {
+    let mut ram = vec![0xEA; 0x10000];
+    ram[0x0400..0x0400 + program.len()].copy_from_slice(program);
+    ram[0xFFFA..0xFFFC].copy_from_slice(&NMI_HANDLER.to_le_bytes());
+    ram[0xFFFE..].copy_from_slice(&IRQ_HANDLER.to_le_bytes());
+    let mut cpu = Cpu6502::new(FlatBus { ram });
+    cpu.reset(Some(0x0400));
+    while !cpu.between_instructions() {
+        cpu.clock();
+    }
+    cpu
+}
+
+// Runs `count` instructions (or interrupts), gives back pc after each one
+fn run_instructions(cpu: &mut Cpu6502<FlatBus>, count: usize) -> Vec<u16> {
+    (0..count)
+        .map(|_| {
+            cpu.clock();
+            while !cpu.between_instructions() {
+                cpu.clock();
+            }
+            cpu.pc
+        })
+        .collect()
+}
+
+// Runs one instruction with an interrupt line going up on its `cycle`th cycle, 0 being
+// the opcode fetch. The nmi line is only held for that cycle, the irq line stays up.
+fn run_raising(cpu: &mut Cpu6502<FlatBus>, cycle: usize, nmi: bool) {
+    for i in 0.. {
+        if i == cycle {
+            if nmi {
+                cpu.nmi_line = true;
+            } else {
+                cpu.irq_line = true;
+            }
+        }
+        cpu.clock();
+        cpu.nmi_line = false;
+        if cpu.between_instructions() {
+            return;
+        }
+    }
+}
+
+#[test]
+fn test_interrupt_flag_latency() {
+    // CLI, the instruction after it still runs before the irq
+    let mut cpu = program_cpu(&[0x58]);
+    cpu.irq_line = true;
+    assert_eq!(run_instructions(&mut cpu, 3), [0x0401, 0x0402, IRQ_HANDLER]);
+
+    // PLP clearing I is just as late, LDA #0, PHA, PLP
+    let mut cpu = program_cpu(&[0xA9, 0x00, 0x48, 0x28]);
+    cpu.irq_line = true;
+    assert_eq!(
+        run_instructions(&mut cpu, 5),
+        [0x0402, 0x0403, 0x0404, 0x0405, IRQ_HANDLER]
+    );
+
+    // SEI, an irq that came in during it still goes off right after and pushes I set
+    let mut cpu = program_cpu(&[0x78]);
+    cpu.status &= !CPUFlags::I.bits();
+    run_raising(&mut cpu, 0, false);
+    assert_eq!(run_instructions(&mut cpu, 1), [IRQ_HANDLER]);
+    assert!(cpu.bus.ram[0x01FB] & CPUFlags::I > 0);
+}
+
+#[test]
+fn test_brk_hijacked_by_nmi() {
+    let mut cpu = program_cpu(&[0x00]);
+    // An nmi before the vector is picked sends BRK to the nmi handler
+    run_raising(&mut cpu, 2, true);
+    assert_eq!(cpu.pc, NMI_HANDLER);
+    // It still pushes BRK's return address and the B flag
+    assert_eq!(cpu.bus.ram[0x01FC..=0x01FD], [0x02, 0x04]);
+    assert!(cpu.bus.ram[0x01FB] & CPUFlags::B > 0);
+    // and the nmi was used up by it
+    assert_eq!(
+        run_instructions(&mut cpu, 2),
+        [NMI_HANDLER + 1, NMI_HANDLER + 2]
+    );
+}
+
+#[test]
+fn test_branch_delays_interrupts() {
+    for &nmi in [false, true].iter() {
+        let handler = if nmi { NMI_HANDLER } else { IRQ_HANDLER };
+        // BCC to the next instruction, taken without crossing a page
+        let cases = [
+            // Polled before the operand fetch, it goes off right after the branch
+            (0, [handler, handler + 1]),
+            // During the operand fetch, the instruction after the branch runs first
+            (1, [0x0403, handler]),
+        ];
+        for &(cycle, expected) in cases.iter() {
+            let mut cpu = program_cpu(&[0x90, 0x00]);
+            cpu.status &= !CPUFlags::I.bits();
+            run_raising(&mut cpu, cycle, nmi);
+            assert_eq!(cpu.pc, 0x0402);
+            assert_eq!(
+                run_instructions(&mut cpu, 2),
+                expected,
+                "nmi {} on cycle {}",
+                nmi,
+                cycle
+            );
+        }
+
+        // JMP $04FD, then a BCC from there to $050F that crosses a page and polls again
+        let mut cpu = program_cpu(&[0x4C, 0xFD, 0x04]);
+        cpu.bus.ram[0x04FD..0x04FF].copy_from_slice(&[0x90, 0x10]);
+        cpu.status &= !CPUFlags::I.bits();
+        run_instructions(&mut cpu, 1);
+        run_raising(&mut cpu, 1, nmi);
+        assert_eq!(cpu.pc, 0x050F);
+        assert_eq!(run_instructions(&mut cpu, 1), [handler], "nmi {}", nmi);
+    }
+}
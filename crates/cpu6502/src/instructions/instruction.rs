@@ -0,0 +1,370 @@
+use crate::bus::{CpuBus, NoBus};
+use crate::cpu::Cpu6502;
+use super::instruction_functions::*;
+
+use std::{fmt, marker::PhantomData};
+
+pub struct Instruction<B> {
+    pub name: &'static str,
+    pub addr_mode: AddressingMode,
+    pub(crate) function: fn(&mut Cpu6502<B>),
+    pub(crate) clock_cycles: u8,
+}
+
+// Derive would want the bus to be Copy too
+impl<B> Clone for Instruction<B> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<B> Copy for Instruction<B> {}
+
+impl<B> Instruction<B> {
+    /// What the instruction does with the memory its addressing mode points at,
+    /// this decides which dummy reads and writes happen along the way
+    pub(crate) fn operation(&self) -> Operation {
+        match self.name {
+            "STA" | "STX" | "STY" | "SAX" | "SHX" | "SHY" | "AHX" | "TAS" => Operation::Write,
+            "ASL" | "LSR" | "ROL" | "ROR" | "INC" | "DEC" | "SLO" | "RLA" | "SRE" | "RRA"
+            | "DCP" | "ISC" => Operation::ReadModifyWrite,
+            _ => Operation::Read,
+        }
+    }
+}
+
+impl<B: CpuBus> Default for Instruction<B> {
+    fn default() -> Self {
+        Instruction {
+            name: "???",
+            addr_mode: AddressingMode::YYY,
+            function: XXX,
+            clock_cycles: 0,
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum Operation {
+    Read,
+    Write,
+    ReadModifyWrite,
+}
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum AddressingMode {
+    IMP,
+    IMM,
+    ZP0,
+    ZPX,
+    ZPY,
+    REL,
+    ABS,
+    ABX,
+    ABY,
+    IND,
+    IZX,
+    IZY,
+    YYY,
+}
+
+impl fmt::Display for AddressingMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AddressingMode::IMP => write!(f, "IMP"),
+            AddressingMode::IMM => write!(f, "IMM"),
+            AddressingMode::ZP0 => write!(f, "ZP0"),
+            AddressingMode::ZPX => write!(f, "ZPX"),
+            AddressingMode::ZPY => write!(f, "ZPY"),
+            AddressingMode::REL => write!(f, "REL"),
+            AddressingMode::ABS => write!(f, "ABS"),
+            AddressingMode::ABX => write!(f, "ABX"),
+            AddressingMode::ABY => write!(f, "ABY"),
+            AddressingMode::IND => write!(f, "IND"),
+            AddressingMode::IZX => write!(f, "IZX"),
+            AddressingMode::IZY => write!(f, "IZY"),
+            AddressingMode::YYY => write!(f, "XXX"),
+        }
+    }
+}
+
+/// True for the opcodes that aren't part of the documented instruction set
+pub fn is_unofficial(opcode: u8) -> bool {
+    match INSTRUCTION_LOOKUP[opcode as usize].name {
+        "NOP" => opcode != 0xEA,
+        "SBC" => opcode == 0xEB,
+        "AHX" | "ALR" | "ANC" | "ARR" | "AXS" | "DCP" | "ISC" | "JAM" | "LAS" | "LAX" | "LXA"
+        | "RLA" | "RRA" | "SAX" | "SHX" | "SHY" | "SLO" | "SRE" | "TAS" | "XAA" => true,
+        _ => false,
+    }
+}
+
+/// The instruction functions are generic over the bus, so the table gets
+/// built for every kind of bus the cpu is used with
+pub struct InstructionSet<B>(PhantomData<B>);
+
+impl<B: CpuBus> InstructionSet<B> {
+    // BY THE POWER OF AUTS
+    pub const LOOKUP: [Instruction<B>; 0xFF + 1] = [
+        Instruction { name: "BRK", clock_cycles: 7, addr_mode: AddressingMode::IMP, function: BRK},
+        Instruction { name: "ORA", clock_cycles: 6, addr_mode: AddressingMode::IZX, function: ORA},
+        Instruction { name: "JAM", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: JAM}, // Unofficial
+        Instruction { name: "SLO", clock_cycles: 8, addr_mode: AddressingMode::IZX, function: SLO}, // Unofficial
+        Instruction { name: "NOP", clock_cycles: 3, addr_mode: AddressingMode::ZP0, function: NOP}, // Unofficial
+        Instruction { name: "ORA", clock_cycles: 3, addr_mode: AddressingMode::ZP0, function: ORA},
+        Instruction { name: "ASL", clock_cycles: 5, addr_mode: AddressingMode::ZP0, function: ASL},
+        Instruction { name: "SLO", clock_cycles: 5, addr_mode: AddressingMode::ZP0, function: SLO}, // Unofficial
+        Instruction { name: "PHP", clock_cycles: 3, addr_mode: AddressingMode::IMP, function: PHP},
+        Instruction { name: "ORA", clock_cycles: 2, addr_mode: AddressingMode::IMM, function: ORA},
+        Instruction { name: "ASL", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: ASL},
+        Instruction { name: "ANC", clock_cycles: 2, addr_mode: AddressingMode::IMM, function: ANC}, // Unofficial
+        Instruction { name: "NOP", clock_cycles: 4, addr_mode: AddressingMode::ABS, function: NOP}, // Unofficial
+        Instruction { name: "ORA", clock_cycles: 4, addr_mode: AddressingMode::ABS, function: ORA},
+        Instruction { name: "ASL", clock_cycles: 6, addr_mode: AddressingMode::ABS, function: ASL},
+        Instruction { name: "SLO", clock_cycles: 6, addr_mode: AddressingMode::ABS, function: SLO}, // Unofficial
+        Instruction { name: "BPL", clock_cycles: 2, addr_mode: AddressingMode::REL, function: BPL},
+        Instruction { name: "ORA", clock_cycles: 5, addr_mode: AddressingMode::IZY, function: ORA},
+        Instruction { name: "JAM", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: JAM}, // Unofficial
+        Instruction { name: "SLO", clock_cycles: 8, addr_mode: AddressingMode::IZY, function: SLO}, // Unofficial
+        Instruction { name: "NOP", clock_cycles: 4, addr_mode: AddressingMode::ZPX, function: NOP}, // Unofficial
+        Instruction { name: "ORA", clock_cycles: 4, addr_mode: AddressingMode::ZPX, function: ORA},
+        Instruction { name: "ASL", clock_cycles: 6, addr_mode: AddressingMode::ZPX, function: ASL},
+        Instruction { name: "SLO", clock_cycles: 6, addr_mode: AddressingMode::ZPX, function: SLO}, // Unofficial
+        Instruction { name: "CLC", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: CLC},
+        Instruction { name: "ORA", clock_cycles: 4, addr_mode: AddressingMode::ABY, function: ORA},
+        Instruction { name: "NOP", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: NOP}, // Unofficial
+        Instruction { name: "SLO", clock_cycles: 7, addr_mode: AddressingMode::ABY, function: SLO}, // Unofficial
+        Instruction { name: "NOP", clock_cycles: 4, addr_mode: AddressingMode::ABX, function: NOP}, // Unofficial
+        Instruction { name: "ORA", clock_cycles: 4, addr_mode: AddressingMode::ABX, function: ORA},
+        Instruction { name: "ASL", clock_cycles: 7, addr_mode: AddressingMode::ABX, function: ASL},
+        Instruction { name: "SLO", clock_cycles: 7, addr_mode: AddressingMode::ABX, function: SLO}, // Unofficial
+        Instruction { name: "JSR", clock_cycles: 6, addr_mode: AddressingMode::ABS, function: JSR},
+        Instruction { name: "AND", clock_cycles: 6, addr_mode: AddressingMode::IZX, function: AND},
+        Instruction { name: "JAM", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: JAM}, // Unofficial
+        Instruction { name: "RLA", clock_cycles: 8, addr_mode: AddressingMode::IZX, function: RLA}, // Unofficial
+        Instruction { name: "BIT", clock_cycles: 3, addr_mode: AddressingMode::ZP0, function: BIT},
+        Instruction { name: "AND", clock_cycles: 3, addr_mode: AddressingMode::ZP0, function: AND},
+        Instruction { name: "ROL", clock_cycles: 5, addr_mode: AddressingMode::ZP0, function: ROL},
+        Instruction { name: "RLA", clock_cycles: 5, addr_mode: AddressingMode::ZP0, function: RLA}, // Unofficial
+        Instruction { name: "PLP", clock_cycles: 4, addr_mode: AddressingMode::IMP, function: PLP},
+        Instruction { name: "AND", clock_cycles: 2, addr_mode: AddressingMode::IMM, function: AND},
+        Instruction { name: "ROL", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: ROL},
+        Instruction { name: "ANC", clock_cycles: 2, addr_mode: AddressingMode::IMM, function: ANC}, // Unofficial
+        Instruction { name: "BIT", clock_cycles: 4, addr_mode: AddressingMode::ABS, function: BIT},
+        Instruction { name: "AND", clock_cycles: 4, addr_mode: AddressingMode::ABS, function: AND},
+        Instruction { name: "ROL", clock_cycles: 6, addr_mode: AddressingMode::ABS, function: ROL},
+        Instruction { name: "RLA", clock_cycles: 6, addr_mode: AddressingMode::ABS, function: RLA}, // Unofficial
+        Instruction { name: "BMI", clock_cycles: 2, addr_mode: AddressingMode::REL, function: BMI},
+        Instruction { name: "AND", clock_cycles: 5, addr_mode: AddressingMode::IZY, function: AND},
+        Instruction { name: "JAM", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: JAM}, // Unofficial
+        Instruction { name: "RLA", clock_cycles: 8, addr_mode: AddressingMode::IZY, function: RLA}, // Unofficial
+        Instruction { name: "NOP", clock_cycles: 4, addr_mode: AddressingMode::ZPX, function: NOP}, // Unofficial
+        Instruction { name: "AND", clock_cycles: 4, addr_mode: AddressingMode::ZPX, function: AND},
+        Instruction { name: "ROL", clock_cycles: 6, addr_mode: AddressingMode::ZPX, function: ROL},
+        Instruction { name: "RLA", clock_cycles: 6, addr_mode: AddressingMode::ZPX, function: RLA}, // Unofficial
+        Instruction { name: "SEC", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: SEC},
+        Instruction { name: "AND", clock_cycles: 4, addr_mode: AddressingMode::ABY, function: AND},
+        Instruction { name: "NOP", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: NOP}, // Unofficial
+        Instruction { name: "RLA", clock_cycles: 7, addr_mode: AddressingMode::ABY, function: RLA}, // Unofficial
+        Instruction { name: "NOP", clock_cycles: 4, addr_mode: AddressingMode::ABX, function: NOP}, // Unofficial
+        Instruction { name: "AND", clock_cycles: 4, addr_mode: AddressingMode::ABX, function: AND},
+        Instruction { name: "ROL", clock_cycles: 7, addr_mode: AddressingMode::ABX, function: ROL},
+        Instruction { name: "RLA", clock_cycles: 7, addr_mode: AddressingMode::ABX, function: RLA}, // Unofficial
+        Instruction { name: "RTI", clock_cycles: 6, addr_mode: AddressingMode::IMP, function: RTI},
+        Instruction { name: "EOR", clock_cycles: 6, addr_mode: AddressingMode::IZX, function: EOR},
+        Instruction { name: "JAM", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: JAM}, // Unofficial
+        Instruction { name: "SRE", clock_cycles: 8, addr_mode: AddressingMode::IZX, function: SRE}, // Unofficial
+        Instruction { name: "NOP", clock_cycles: 3, addr_mode: AddressingMode::ZP0, function: NOP}, // Unofficial
+        Instruction { name: "EOR", clock_cycles: 3, addr_mode: AddressingMode::ZP0, function: EOR},
+        Instruction { name: "LSR", clock_cycles: 5, addr_mode: AddressingMode::ZP0, function: LSR},
+        Instruction { name: "SRE", clock_cycles: 5, addr_mode: AddressingMode::ZP0, function: SRE}, // Unofficial
+        Instruction { name: "PHA", clock_cycles: 3, addr_mode: AddressingMode::IMP, function: PHA},
+        Instruction { name: "EOR", clock_cycles: 2, addr_mode: AddressingMode::IMM, function: EOR},
+        Instruction { name: "LSR", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: LSR},
+        Instruction { name: "ALR", clock_cycles: 2, addr_mode: AddressingMode::IMM, function: ALR}, // Unofficial
+        Instruction { name: "JMP", clock_cycles: 3, addr_mode: AddressingMode::ABS, function: JMP},
+        Instruction { name: "EOR", clock_cycles: 4, addr_mode: AddressingMode::ABS, function: EOR},
+        Instruction { name: "LSR", clock_cycles: 6, addr_mode: AddressingMode::ABS, function: LSR},
+        Instruction { name: "SRE", clock_cycles: 6, addr_mode: AddressingMode::ABS, function: SRE}, // Unofficial
+        Instruction { name: "BVC", clock_cycles: 2, addr_mode: AddressingMode::REL, function: BVC},
+        Instruction { name: "EOR", clock_cycles: 5, addr_mode: AddressingMode::IZY, function: EOR},
+        Instruction { name: "JAM", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: JAM}, // Unofficial
+        Instruction { name: "SRE", clock_cycles: 8, addr_mode: AddressingMode::IZY, function: SRE}, // Unofficial
+        Instruction { name: "NOP", clock_cycles: 4, addr_mode: AddressingMode::ZPX, function: NOP}, // Unofficial
+        Instruction { name: "EOR", clock_cycles: 4, addr_mode: AddressingMode::ZPX, function: EOR},
+        Instruction { name: "LSR", clock_cycles: 6, addr_mode: AddressingMode::ZPX, function: LSR},
+        Instruction { name: "SRE", clock_cycles: 6, addr_mode: AddressingMode::ZPX, function: SRE}, // Unofficial
+        Instruction { name: "CLI", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: CLI},
+        Instruction { name: "EOR", clock_cycles: 4, addr_mode: AddressingMode::ABY, function: EOR},
+        Instruction { name: "NOP", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: NOP}, // Unofficial
+        Instruction { name: "SRE", clock_cycles: 7, addr_mode: AddressingMode::ABY, function: SRE}, // Unofficial
+        Instruction { name: "NOP", clock_cycles: 4, addr_mode: AddressingMode::ABX, function: NOP}, // Unofficial
+        Instruction { name: "EOR", clock_cycles: 4, addr_mode: AddressingMode::ABX, function: EOR},
+        Instruction { name: "LSR", clock_cycles: 7, addr_mode: AddressingMode::ABX, function: LSR},
+        Instruction { name: "SRE", clock_cycles: 7, addr_mode: AddressingMode::ABX, function: SRE}, // Unofficial
+        Instruction { name: "RTS", clock_cycles: 6, addr_mode: AddressingMode::IMP, function: RTS},
+        Instruction { name: "ADC", clock_cycles: 6, addr_mode: AddressingMode::IZX, function: ADC},
+        Instruction { name: "JAM", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: JAM}, // Unofficial
+        Instruction { name: "RRA", clock_cycles: 8, addr_mode: AddressingMode::IZX, function: RRA}, // Unofficial
+        Instruction { name: "NOP", clock_cycles: 3, addr_mode: AddressingMode::ZP0, function: NOP}, // Unofficial
+        Instruction { name: "ADC", clock_cycles: 3, addr_mode: AddressingMode::ZP0, function: ADC},
+        Instruction { name: "ROR", clock_cycles: 5, addr_mode: AddressingMode::ZP0, function: ROR},
+        Instruction { name: "RRA", clock_cycles: 5, addr_mode: AddressingMode::ZP0, function: RRA}, // Unofficial
+        Instruction { name: "PLA", clock_cycles: 4, addr_mode: AddressingMode::IMP, function: PLA},
+        Instruction { name: "ADC", clock_cycles: 2, addr_mode: AddressingMode::IMM, function: ADC},
+        Instruction { name: "ROR", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: ROR},
+        Instruction { name: "ARR", clock_cycles: 2, addr_mode: AddressingMode::IMM, function: ARR}, // Unofficial
+        Instruction { name: "JMP", clock_cycles: 5, addr_mode: AddressingMode::IND, function: JMP},
+        Instruction { name: "ADC", clock_cycles: 4, addr_mode: AddressingMode::ABS, function: ADC},
+        Instruction { name: "ROR", clock_cycles: 6, addr_mode: AddressingMode::ABS, function: ROR},
+        Instruction { name: "RRA", clock_cycles: 6, addr_mode: AddressingMode::ABS, function: RRA}, // Unofficial
+        Instruction { name: "BVS", clock_cycles: 2, addr_mode: AddressingMode::REL, function: BVS},
+        Instruction { name: "ADC", clock_cycles: 5, addr_mode: AddressingMode::IZY, function: ADC},
+        Instruction { name: "JAM", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: JAM}, // Unofficial
+        Instruction { name: "RRA", clock_cycles: 8, addr_mode: AddressingMode::IZY, function: RRA}, // Unofficial
+        Instruction { name: "NOP", clock_cycles: 4, addr_mode: AddressingMode::ZPX, function: NOP}, // Unofficial
+        Instruction { name: "ADC", clock_cycles: 4, addr_mode: AddressingMode::ZPX, function: ADC},
+        Instruction { name: "ROR", clock_cycles: 6, addr_mode: AddressingMode::ZPX, function: ROR},
+        Instruction { name: "RRA", clock_cycles: 6, addr_mode: AddressingMode::ZPX, function: RRA}, // Unofficial
+        Instruction { name: "SEI", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: SEI},
+        Instruction { name: "ADC", clock_cycles: 4, addr_mode: AddressingMode::ABY, function: ADC},
+        Instruction { name: "NOP", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: NOP}, // Unofficial
+        Instruction { name: "RRA", clock_cycles: 7, addr_mode: AddressingMode::ABY, function: RRA}, // Unofficial
+        Instruction { name: "NOP", clock_cycles: 4, addr_mode: AddressingMode::ABX, function: NOP}, // Unofficial
+        Instruction { name: "ADC", clock_cycles: 4, addr_mode: AddressingMode::ABX, function: ADC},
+        Instruction { name: "ROR", clock_cycles: 7, addr_mode: AddressingMode::ABX, function: ROR},
+        Instruction { name: "RRA", clock_cycles: 7, addr_mode: AddressingMode::ABX, function: RRA}, // Unofficial
+        Instruction { name: "NOP", clock_cycles: 2, addr_mode: AddressingMode::IMM, function: NOP}, // Unofficial
+        Instruction { name: "STA", clock_cycles: 6, addr_mode: AddressingMode::IZX, function: STA},
+        Instruction { name: "NOP", clock_cycles: 2, addr_mode: AddressingMode::IMM, function: NOP}, // Unofficial
+        Instruction { name: "SAX", clock_cycles: 6, addr_mode: AddressingMode::IZX, function: SAX}, // Unofficial
+        Instruction { name: "STY", clock_cycles: 3, addr_mode: AddressingMode::ZP0, function: STY},
+        Instruction { name: "STA", clock_cycles: 3, addr_mode: AddressingMode::ZP0, function: STA},
+        Instruction { name: "STX", clock_cycles: 3, addr_mode: AddressingMode::ZP0, function: STX},
+        Instruction { name: "SAX", clock_cycles: 3, addr_mode: AddressingMode::ZP0, function: SAX}, // Unofficial
+        Instruction { name: "DEY", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: DEY},
+        Instruction { name: "NOP", clock_cycles: 2, addr_mode: AddressingMode::IMM, function: NOP}, // Unofficial
+        Instruction { name: "TXA", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: TXA},
+        Instruction { name: "XAA", clock_cycles: 2, addr_mode: AddressingMode::IMM, function: XAA}, // Unofficial
+        Instruction { name: "STY", clock_cycles: 4, addr_mode: AddressingMode::ABS, function: STY},
+        Instruction { name: "STA", clock_cycles: 4, addr_mode: AddressingMode::ABS, function: STA},
+        Instruction { name: "STX", clock_cycles: 4, addr_mode: AddressingMode::ABS, function: STX},
+        Instruction { name: "SAX", clock_cycles: 4, addr_mode: AddressingMode::ABS, function: SAX}, // Unofficial
+        Instruction { name: "BCC", clock_cycles: 2, addr_mode: AddressingMode::REL, function: BCC},
+        Instruction { name: "STA", clock_cycles: 6, addr_mode: AddressingMode::IZY, function: STA},
+        Instruction { name: "JAM", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: JAM}, // Unofficial
+        Instruction { name: "AHX", clock_cycles: 6, addr_mode: AddressingMode::IZY, function: AHX}, // Unofficial
+        Instruction { name: "STY", clock_cycles: 4, addr_mode: AddressingMode::ZPX, function: STY},
+        Instruction { name: "STA", clock_cycles: 4, addr_mode: AddressingMode::ZPX, function: STA},
+        Instruction { name: "STX", clock_cycles: 4, addr_mode: AddressingMode::ZPY, function: STX},
+        Instruction { name: "SAX", clock_cycles: 4, addr_mode: AddressingMode::ZPY, function: SAX}, // Unofficial
+        Instruction { name: "TYA", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: TYA},
+        Instruction { name: "STA", clock_cycles: 5, addr_mode: AddressingMode::ABY, function: STA},
+        Instruction { name: "TXS", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: TXS},
+        Instruction { name: "TAS", clock_cycles: 5, addr_mode: AddressingMode::ABY, function: TAS}, // Unofficial
+        Instruction { name: "SHY", clock_cycles: 5, addr_mode: AddressingMode::ABX, function: SHY}, // Unofficial
+        Instruction { name: "STA", clock_cycles: 5, addr_mode: AddressingMode::ABX, function: STA},
+        Instruction { name: "SHX", clock_cycles: 5, addr_mode: AddressingMode::ABY, function: SHX}, // Unofficial
+        Instruction { name: "AHX", clock_cycles: 5, addr_mode: AddressingMode::ABY, function: AHX}, // Unofficial
+        Instruction { name: "LDY", clock_cycles: 2, addr_mode: AddressingMode::IMM, function: LDY},
+        Instruction { name: "LDA", clock_cycles: 6, addr_mode: AddressingMode::IZX, function: LDA},
+        Instruction { name: "LDX", clock_cycles: 2, addr_mode: AddressingMode::IMM, function: LDX},
+        Instruction { name: "LAX", clock_cycles: 6, addr_mode: AddressingMode::IZX, function: LAX}, // Unofficial
+        Instruction { name: "LDY", clock_cycles: 3, addr_mode: AddressingMode::ZP0, function: LDY},
+        Instruction { name: "LDA", clock_cycles: 3, addr_mode: AddressingMode::ZP0, function: LDA},
+        Instruction { name: "LDX", clock_cycles: 3, addr_mode: AddressingMode::ZP0, function: LDX},
+        Instruction { name: "LAX", clock_cycles: 3, addr_mode: AddressingMode::ZP0, function: LAX}, // Unofficial
+        Instruction { name: "TAY", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: TAY},
+        Instruction { name: "LDA", clock_cycles: 2, addr_mode: AddressingMode::IMM, function: LDA},
+        Instruction { name: "TAX", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: TAX},
+        Instruction { name: "LXA", clock_cycles: 2, addr_mode: AddressingMode::IMM, function: LXA}, // Unofficial
+        Instruction { name: "LDY", clock_cycles: 4, addr_mode: AddressingMode::ABS, function: LDY},
+        Instruction { name: "LDA", clock_cycles: 4, addr_mode: AddressingMode::ABS, function: LDA},
+        Instruction { name: "LDX", clock_cycles: 4, addr_mode: AddressingMode::ABS, function: LDX},
+        Instruction { name: "LAX", clock_cycles: 4, addr_mode: AddressingMode::ABS, function: LAX}, // Unofficial
+        Instruction { name: "BCS", clock_cycles: 2, addr_mode: AddressingMode::REL, function: BCS},
+        Instruction { name: "LDA", clock_cycles: 5, addr_mode: AddressingMode::IZY, function: LDA},
+        Instruction { name: "JAM", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: JAM}, // Unofficial
+        Instruction { name: "LAX", clock_cycles: 5, addr_mode: AddressingMode::IZY, function: LAX}, // Unofficial
+        Instruction { name: "LDY", clock_cycles: 4, addr_mode: AddressingMode::ZPX, function: LDY},
+        Instruction { name: "LDA", clock_cycles: 4, addr_mode: AddressingMode::ZPX, function: LDA},
+        Instruction { name: "LDX", clock_cycles: 4, addr_mode: AddressingMode::ZPY, function: LDX},
+        Instruction { name: "LAX", clock_cycles: 4, addr_mode: AddressingMode::ZPY, function: LAX}, // Unofficial
+        Instruction { name: "CLV", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: CLV},
+        Instruction { name: "LDA", clock_cycles: 4, addr_mode: AddressingMode::ABY, function: LDA},
+        Instruction { name: "TSX", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: TSX},
+        Instruction { name: "LAS", clock_cycles: 4, addr_mode: AddressingMode::ABY, function: LAS}, // Unofficial
+        Instruction { name: "LDY", clock_cycles: 4, addr_mode: AddressingMode::ABX, function: LDY},
+        Instruction { name: "LDA", clock_cycles: 4, addr_mode: AddressingMode::ABX, function: LDA},
+        Instruction { name: "LDX", clock_cycles: 4, addr_mode: AddressingMode::ABY, function: LDX},
+        Instruction { name: "LAX", clock_cycles: 4, addr_mode: AddressingMode::ABY, function: LAX}, // Unofficial
+        Instruction { name: "CPY", clock_cycles: 2, addr_mode: AddressingMode::IMM, function: CPY},
+        Instruction { name: "CMP", clock_cycles: 6, addr_mode: AddressingMode::IZX, function: CMP},
+        Instruction { name: "NOP", clock_cycles: 2, addr_mode: AddressingMode::IMM, function: NOP}, // Unofficial
+        Instruction { name: "DCP", clock_cycles: 8, addr_mode: AddressingMode::IZX, function: DCP}, // Unofficial
+        Instruction { name: "CPY", clock_cycles: 3, addr_mode: AddressingMode::ZP0, function: CPY},
+        Instruction { name: "CMP", clock_cycles: 3, addr_mode: AddressingMode::ZP0, function: CMP},
+        Instruction { name: "DEC", clock_cycles: 5, addr_mode: AddressingMode::ZP0, function: DEC},
+        Instruction { name: "DCP", clock_cycles: 5, addr_mode: AddressingMode::ZP0, function: DCP}, // Unofficial
+        Instruction { name: "INY", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: INY},
+        Instruction { name: "CMP", clock_cycles: 2, addr_mode: AddressingMode::IMM, function: CMP},
+        Instruction { name: "DEX", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: DEX},
+        Instruction { name: "AXS", clock_cycles: 2, addr_mode: AddressingMode::IMM, function: AXS}, // Unofficial
+        Instruction { name: "CPY", clock_cycles: 4, addr_mode: AddressingMode::ABS, function: CPY},
+        Instruction { name: "CMP", clock_cycles: 4, addr_mode: AddressingMode::ABS, function: CMP},
+        Instruction { name: "DEC", clock_cycles: 6, addr_mode: AddressingMode::ABS, function: DEC},
+        Instruction { name: "DCP", clock_cycles: 6, addr_mode: AddressingMode::ABS, function: DCP}, // Unofficial
+        Instruction { name: "BNE", clock_cycles: 2, addr_mode: AddressingMode::REL, function: BNE},
+        Instruction { name: "CMP", clock_cycles: 5, addr_mode: AddressingMode::IZY, function: CMP},
+        Instruction { name: "JAM", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: JAM}, // Unofficial
+        Instruction { name: "DCP", clock_cycles: 8, addr_mode: AddressingMode::IZY, function: DCP}, // Unofficial
+        Instruction { name: "NOP", clock_cycles: 4, addr_mode: AddressingMode::ZPX, function: NOP}, // Unofficial
+        Instruction { name: "CMP", clock_cycles: 4, addr_mode: AddressingMode::ZPX, function: CMP},
+        Instruction { name: "DEC", clock_cycles: 6, addr_mode: AddressingMode::ZPX, function: DEC},
+        Instruction { name: "DCP", clock_cycles: 6, addr_mode: AddressingMode::ZPX, function: DCP}, // Unofficial
+        Instruction { name: "CLD", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: CLD},
+        Instruction { name: "CMP", clock_cycles: 4, addr_mode: AddressingMode::ABY, function: CMP},
+        Instruction { name: "NOP", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: NOP}, // Unofficial
+        Instruction { name: "DCP", clock_cycles: 7, addr_mode: AddressingMode::ABY, function: DCP}, // Unofficial
+        Instruction { name: "NOP", clock_cycles: 4, addr_mode: AddressingMode::ABX, function: NOP}, // Unofficial
+        Instruction { name: "CMP", clock_cycles: 4, addr_mode: AddressingMode::ABX, function: CMP},
+        Instruction { name: "DEC", clock_cycles: 7, addr_mode: AddressingMode::ABX, function: DEC},
+        Instruction { name: "DCP", clock_cycles: 7, addr_mode: AddressingMode::ABX, function: DCP}, // Unofficial
+        Instruction { name: "CPX", clock_cycles: 2, addr_mode: AddressingMode::IMM, function: CPX},
+        Instruction { name: "SBC", clock_cycles: 6, addr_mode: AddressingMode::IZX, function: SBC},
+        Instruction { name: "NOP", clock_cycles: 2, addr_mode: AddressingMode::IMM, function: NOP}, // Unofficial
+        Instruction { name: "ISC", clock_cycles: 8, addr_mode: AddressingMode::IZX, function: ISC}, // Unofficial
+        Instruction { name: "CPX", clock_cycles: 3, addr_mode: AddressingMode::ZP0, function: CPX},
+        Instruction { name: "SBC", clock_cycles: 3, addr_mode: AddressingMode::ZP0, function: SBC},
+        Instruction { name: "INC", clock_cycles: 5, addr_mode: AddressingMode::ZP0, function: INC},
+        Instruction { name: "ISC", clock_cycles: 5, addr_mode: AddressingMode::ZP0, function: ISC}, // Unofficial
+        Instruction { name: "INX", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: INX},
+        Instruction { name: "SBC", clock_cycles: 2, addr_mode: AddressingMode::IMM, function: SBC},
+        Instruction { name: "NOP", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: NOP},
+        Instruction { name: "SBC", clock_cycles: 2, addr_mode: AddressingMode::IMM, function: SBC}, // Unofficial
+        Instruction { name: "CPX", clock_cycles: 4, addr_mode: AddressingMode::ABS, function: CPX},
+        Instruction { name: "SBC", clock_cycles: 4, addr_mode: AddressingMode::ABS, function: SBC},
+        Instruction { name: "INC", clock_cycles: 6, addr_mode: AddressingMode::ABS, function: INC},
+        Instruction { name: "ISC", clock_cycles: 6, addr_mode: AddressingMode::ABS, function: ISC}, // Unofficial
+        Instruction { name: "BEQ", clock_cycles: 2, addr_mode: AddressingMode::REL, function: BEQ},
+        Instruction { name: "SBC", clock_cycles: 5, addr_mode: AddressingMode::IZY, function: SBC},
+        Instruction { name: "JAM", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: JAM}, // Unofficial
+        Instruction { name: "ISC", clock_cycles: 8, addr_mode: AddressingMode::IZY, function: ISC}, // Unofficial
+        Instruction { name: "NOP", clock_cycles: 4, addr_mode: AddressingMode::ZPX, function: NOP}, // Unofficial
+        Instruction { name: "SBC", clock_cycles: 4, addr_mode: AddressingMode::ZPX, function: SBC},
+        Instruction { name: "INC", clock_cycles: 6, addr_mode: AddressingMode::ZPX, function: INC},
+        Instruction { name: "ISC", clock_cycles: 6, addr_mode: AddressingMode::ZPX, function: ISC}, // Unofficial
+        Instruction { name: "SED", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: SED},
+        Instruction { name: "SBC", clock_cycles: 4, addr_mode: AddressingMode::ABY, function: SBC},
+        Instruction { name: "NOP", clock_cycles: 2, addr_mode: AddressingMode::IMP, function: NOP}, // Unofficial
+        Instruction { name: "ISC", clock_cycles: 7, addr_mode: AddressingMode::ABY, function: ISC}, // Unofficial
+        Instruction { name: "NOP", clock_cycles: 4, addr_mode: AddressingMode::ABX, function: NOP}, // Unofficial
+        Instruction { name: "SBC", clock_cycles: 4, addr_mode: AddressingMode::ABX, function: SBC},
+        Instruction { name: "INC", clock_cycles: 7, addr_mode: AddressingMode::ABX, function: INC},
+        Instruction { name: "ISC", clock_cycles: 7, addr_mode: AddressingMode::ABX, function: ISC}, // Unofficial
+    ];
+}
+
+/// The table without a bus, all the disassembler and tracer need are the names and modes
+pub const INSTRUCTION_LOOKUP: [Instruction<NoBus>; 0xFF + 1] = InstructionSet::<NoBus>::LOOKUP;
+
@@ -0,0 +1,382 @@
+use crate::bus::CpuBus;
+use crate::cpu::{CPUFlags, Cpu6502};
+use super::instruction::{AddressingMode, Operation};
+
+// https://www.nesdev.org/wiki/CPU_addressing_modes
+// https://www.nesdev.org/6502_cpu.txt
+//
+// The 6502 touches the bus on every single cycle, even when it has nothing
+// useful to read. Those dummy reads and writes are visible to anything with
+// side effects on access (the ppu registers, mapper registers) so each cycle
+// here does exactly the access the real chip does on that cycle.
+//
+// `cpu.step` counts the cycles after the opcode fetch, step 1 is the 2nd
+// cycle of the instruction.
+
+/// Runs one cycle of the current instruction, true once it was the last one
+pub fn instruction_cycle<B: CpuBus>(cpu: &mut Cpu6502<B>) -> bool {
+    match cpu.opcode {
+        0x00 => brk_cycle(cpu),
+        0x20 => jsr_cycle(cpu),
+        0x40 => rti_cycle(cpu),
+        0x60 => rts_cycle(cpu),
+        0x08 | 0x48 => push_cycle(cpu),
+        0x28 | 0x68 => pull_cycle(cpu),
+        _ => match cpu.addressing_mode {
+            AddressingMode::IMP | AddressingMode::YYY => implied_cycle(cpu),
+            AddressingMode::REL => branch_cycle(cpu),
+            _ => memory_cycle(cpu),
+        },
+    }
+}
+
+// Implied and accumulator instructions, the cpu reads the next byte and throws it away
+fn implied_cycle<B: CpuBus>(cpu: &mut Cpu6502<B>) -> bool {
+    cpu.read_bus(cpu.pc);
+    cpu.fetched = cpu.acc;
+    cpu.fetched_ready = true;
+    (cpu.instruction.function)(cpu);
+    true
+}
+
+// PHA, PHP: the instruction does the push on the last cycle
+fn push_cycle<B: CpuBus>(cpu: &mut Cpu6502<B>) -> bool {
+    match cpu.step {
+        1 => {
+            cpu.read_bus(cpu.pc);
+            false
+        }
+        _ => {
+            (cpu.instruction.function)(cpu);
+            true
+        }
+    }
+}
+
+// PLA, PLP: the stack pointer gets incremented during a dummy read of the stack
+fn pull_cycle<B: CpuBus>(cpu: &mut Cpu6502<B>) -> bool {
+    match cpu.step {
+        1 => {
+            cpu.read_bus(cpu.pc);
+            false
+        }
+        2 => {
+            cpu.read_bus(0x0100 + cpu.stack_pointer as u16);
+            false
+        }
+        _ => {
+            (cpu.instruction.function)(cpu);
+            true
+        }
+    }
+}
+
+// The return address gets pushed between reading the low and high byte of the
+// target, so what ends up on the stack is the address of the high byte
+fn jsr_cycle<B: CpuBus>(cpu: &mut Cpu6502<B>) -> bool {
+    match cpu.step {
+        1 => {
+            cpu.temp = cpu.read_bus(cpu.pc) as u16;
+            cpu.pc = cpu.pc.wrapping_add(1);
+        }
+        2 => {
+            cpu.read_bus(0x0100 + cpu.stack_pointer as u16);
+        }
+        3 => cpu.push((cpu.pc >> 8) as u8),
+        4 => cpu.push(cpu.pc as u8),
+        _ => {
+            cpu.addr_abs = ((cpu.read_bus(cpu.pc) as u16) << 8) | cpu.temp;
+            (cpu.instruction.function)(cpu);
+            return true;
+        }
+    }
+    false
+}
+
+fn rts_cycle<B: CpuBus>(cpu: &mut Cpu6502<B>) -> bool {
+    match cpu.step {
+        1 => {
+            cpu.read_bus(cpu.pc);
+        }
+        2 => {
+            cpu.read_bus(0x0100 + cpu.stack_pointer as u16);
+        }
+        3 => cpu.temp = cpu.pull() as u16,
+        4 => cpu.pc = ((cpu.pull() as u16) << 8) | cpu.temp,
+        _ => {
+            cpu.read_bus(cpu.pc);
+            (cpu.instruction.function)(cpu);
+            return true;
+        }
+    }
+    false
+}
+
+fn rti_cycle<B: CpuBus>(cpu: &mut Cpu6502<B>) -> bool {
+    match cpu.step {
+        1 => {
+            cpu.read_bus(cpu.pc);
+        }
+        2 => {
+            cpu.read_bus(0x0100 + cpu.stack_pointer as u16);
+        }
+        3 => cpu.status = cpu.pull(),
+        4 => cpu.temp = cpu.pull() as u16,
+        _ => {
+            cpu.pc = ((cpu.pull() as u16) << 8) | cpu.temp;
+            (cpu.instruction.function)(cpu);
+            return true;
+        }
+    }
+    false
+}
+
+// BRK skips the byte after it, then pushes pc and status with the break flag
+// set and jumps through the irq vector. Irq and nmi run the same sequence
+// without touching pc or setting B. An nmi that shows up before the vector
+// gets picked hijacks the sequence, BRK and irq end up in the nmi handler.
+fn brk_cycle<B: CpuBus>(cpu: &mut Cpu6502<B>) -> bool {
+    match cpu.step {
+        1 => {
+            cpu.read_bus(cpu.pc);
+            if !cpu.hardware_interrupt {
+                cpu.pc = cpu.pc.wrapping_add(1);
+            }
+        }
+        2 => cpu.push((cpu.pc >> 8) as u8),
+        3 => cpu.push(cpu.pc as u8),
+        4 => {
+            let status = if cpu.hardware_interrupt {
+                cpu.status & !CPUFlags::B
+            } else {
+                cpu.status | CPUFlags::B
+            };
+            cpu.push(status | CPUFlags::U);
+            cpu.set_flag(CPUFlags::I, true);
+            cpu.addr_abs = if cpu.need_nmi {
+                cpu.need_nmi = false;
+                0xFFFA
+            } else {
+                0xFFFE
+            };
+        }
+        5 => cpu.temp = cpu.read_bus(cpu.addr_abs) as u16,
+        _ => {
+            cpu.addr_abs = ((cpu.read_bus(cpu.addr_abs.wrapping_add(1)) as u16) << 8) | cpu.temp;
+            (cpu.instruction.function)(cpu);
+            return true;
+        }
+    }
+    false
+}
+
+// A taken branch spends a cycle adding the offset to the low byte of pc, and
+// if that carried into the high byte another one fixing it. Both of them read
+// whatever pc points at right then.
+//
+// Taken branches poll interrupts before the operand fetch rather than at the
+// end of it, an irq or nmi that turned up during the operand fetch of a branch
+// that doesn't cross a page waits for the instruction after.
+fn branch_cycle<B: CpuBus>(cpu: &mut Cpu6502<B>) -> bool {
+    match cpu.step {
+        1 => {
+            cpu.addr_rel = cpu.read_bus(cpu.pc) as u16;
+            cpu.pc = cpu.pc.wrapping_add(1);
+            if cpu.addr_rel & 0x80 > 0 {
+                cpu.addr_rel |= 0xFF00;
+            }
+            (cpu.instruction.function)(cpu);
+            !cpu.branch_taken
+        }
+        2 => {
+            if cpu.run_irq && !cpu.prev_run_irq {
+                cpu.run_irq = false;
+            }
+            // The nmi stays latched, it just isn't seen until the next instruction
+            if cpu.need_nmi && !cpu.prev_need_nmi {
+                cpu.skip_nmi_poll = true;
+            }
+            cpu.read_bus(cpu.pc);
+            cpu.pc = (cpu.pc & 0xFF00) | (cpu.addr_abs & 0x00FF);
+            cpu.pc == cpu.addr_abs
+        }
+        _ => {
+            cpu.read_bus(cpu.pc);
+            cpu.pc = cpu.addr_abs;
+            true
+        }
+    }
+}
+
+// Everything with an operand in memory, first work out the address then
+// read, write or read-modify-write it
+fn memory_cycle<B: CpuBus>(cpu: &mut Cpu6502<B>) -> bool {
+    let mode = cpu.addressing_mode;
+    if mode == AddressingMode::IMM {
+        cpu.addr_abs = cpu.pc;
+        cpu.pc = cpu.pc.wrapping_add(1);
+        (cpu.instruction.function)(cpu);
+        return true;
+    }
+
+    let address_steps = address_cycles(mode);
+    if cpu.step <= address_steps {
+        address_cycle(cpu);
+        // Jumps only need the address, they're done as soon as it's known
+        if cpu.step == address_steps && matches!(cpu.opcode, 0x4C | 0x6C) {
+            (cpu.instruction.function)(cpu);
+            return true;
+        }
+        return false;
+    }
+
+    // The indexed modes add the index to the low byte first and read from
+    // there while the high byte gets fixed. Reads that didn't cross a page
+    // can use that read, everything else has to throw it away.
+    let indexed = matches!(mode, AddressingMode::ABX | AddressingMode::ABY | AddressingMode::IZY);
+    let crossed = cpu.temp & 0xFF00 != cpu.addr_abs & 0xFF00;
+    let mut step = cpu.step - address_steps;
+    if indexed && (crossed || cpu.operation != Operation::Read) {
+        if step == 1 {
+            cpu.read_bus((cpu.temp & 0xFF00) | (cpu.addr_abs & 0x00FF));
+            return false;
+        }
+        step -= 1;
+    }
+
+    match (cpu.operation, step) {
+        // Read-modify-writes write the unmodified value back while they work
+        (Operation::ReadModifyWrite, 1) => {
+            cpu.fetched = cpu.read_bus(cpu.addr_abs);
+            cpu.fetched_ready = true;
+            false
+        }
+        (Operation::ReadModifyWrite, 2) => {
+            cpu.write_bus(cpu.addr_abs, cpu.fetched);
+            false
+        }
+        _ => {
+            (cpu.instruction.function)(cpu);
+            true
+        }
+    }
+}
+
+// How many cycles each addressing mode spends working out its address
+fn address_cycles(mode: AddressingMode) -> u8 {
+    match mode {
+        AddressingMode::ZP0 => 1,
+        AddressingMode::ZPX | AddressingMode::ZPY => 2,
+        AddressingMode::ABS | AddressingMode::ABX | AddressingMode::ABY => 2,
+        AddressingMode::IZY => 3,
+        AddressingMode::IND | AddressingMode::IZX => 4,
+        _ => 0,
+    }
+}
+
+// One cycle of working out the address, it ends up in addr_abs. The indexed
+// modes leave the address before indexing in temp.
+fn address_cycle<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    match (cpu.addressing_mode, cpu.step) {
+        // Address Mode: Zero Page
+        // To save program bytes, zero page addressing allows you to absolutely address
+        // a location in first 0xFF bytes of address range. Clearly this only requires
+        // one byte instead of the usual two.
+        (AddressingMode::ZP0, _) => {
+            cpu.addr_abs = cpu.read_bus(cpu.pc) as u16;
+            cpu.pc = cpu.pc.wrapping_add(1);
+        }
+
+        // Address Mode: Zero Page with X or Y Offset
+        // Fundamentally the same as Zero Page addressing, but the index register is
+        // added to the supplied single byte address, wrapping inside the zero page.
+        // The cpu reads the unindexed address while it does the add.
+        (AddressingMode::ZPX, 1) | (AddressingMode::ZPY, 1) => {
+            cpu.temp = cpu.read_bus(cpu.pc) as u16;
+            cpu.pc = cpu.pc.wrapping_add(1);
+        }
+        (AddressingMode::ZPX, _) | (AddressingMode::ZPY, _) => {
+            cpu.read_bus(cpu.temp);
+            let index = if cpu.addressing_mode == AddressingMode::ZPX {
+                cpu.x_reg
+            } else {
+                cpu.y_reg
+            };
+            cpu.addr_abs = (cpu.temp as u8).wrapping_add(index) as u16;
+        }
+
+        // Address Mode: Absolute, with X Offset or with Y Offset
+        // A full 16-bit address is loaded and the index register (if any) added to it
+        (AddressingMode::ABS, 1) | (AddressingMode::ABX, 1) | (AddressingMode::ABY, 1) => {
+            cpu.temp = cpu.read_bus(cpu.pc) as u16;
+            cpu.pc = cpu.pc.wrapping_add(1);
+        }
+        (AddressingMode::ABS, _) | (AddressingMode::ABX, _) | (AddressingMode::ABY, _) => {
+            cpu.temp |= (cpu.read_bus(cpu.pc) as u16) << 8;
+            cpu.pc = cpu.pc.wrapping_add(1);
+            let index = match cpu.addressing_mode {
+                AddressingMode::ABX => cpu.x_reg,
+                AddressingMode::ABY => cpu.y_reg,
+                _ => 0,
+            };
+            cpu.addr_abs = cpu.temp.wrapping_add(index as u16);
+        }
+
+        // Address Mode: Indirect
+        // The supplied 16-bit address is read to get the actual 16-bit address. This is
+        // instruction is unusual in that it has a bug in the hardware! To emulate its
+        // function accurately, we also need to emulate this bug. If the low byte of the
+        // supplied address is 0xFF, then to read the high byte of the actual address
+        // we need to cross a page boundary. This doesnt actually work on the chip as
+        // designed, instead it wraps back around in the same page, yielding an
+        // invalid actual address
+        (AddressingMode::IND, 1) => {
+            cpu.temp = cpu.read_bus(cpu.pc) as u16;
+            cpu.pc = cpu.pc.wrapping_add(1);
+        }
+        (AddressingMode::IND, 2) => {
+            cpu.temp |= (cpu.read_bus(cpu.pc) as u16) << 8;
+            cpu.pc = cpu.pc.wrapping_add(1);
+        }
+        (AddressingMode::IND, 3) => cpu.addr_abs = cpu.read_bus(cpu.temp) as u16,
+        (AddressingMode::IND, _) => {
+            let hi_addr = (cpu.temp & 0xFF00) | (cpu.temp.wrapping_add(1) & 0x00FF);
+            cpu.addr_abs |= (cpu.read_bus(hi_addr) as u16) << 8;
+        }
+
+        // Address Mode: Indirect X
+        // The supplied 8-bit address is offset by X Register to index
+        // a location in page 0x00. The actual 16-bit address is read
+        // from this location
+        (AddressingMode::IZX, 1) => {
+            cpu.temp = cpu.read_bus(cpu.pc) as u16;
+            cpu.pc = cpu.pc.wrapping_add(1);
+        }
+        (AddressingMode::IZX, 2) => {
+            cpu.read_bus(cpu.temp);
+            cpu.temp = (cpu.temp as u8).wrapping_add(cpu.x_reg) as u16;
+        }
+        (AddressingMode::IZX, 3) => cpu.addr_abs = cpu.read_bus(cpu.temp) as u16,
+        (AddressingMode::IZX, _) => {
+            let hi_addr = (cpu.temp as u8).wrapping_add(1) as u16;
+            cpu.addr_abs |= (cpu.read_bus(hi_addr) as u16) << 8;
+        }
+
+        // Address Mode: Indirect Y
+        // The supplied 8-bit address indexes a location in page 0x00. From
+        // here the actual 16-bit address is read, and the contents of
+        // Y Register is added to it to offset it.
+        (AddressingMode::IZY, 1) => {
+            cpu.temp = cpu.read_bus(cpu.pc) as u16;
+            cpu.pc = cpu.pc.wrapping_add(1);
+        }
+        (AddressingMode::IZY, 2) => cpu.addr_abs = cpu.read_bus(cpu.temp) as u16,
+        (AddressingMode::IZY, _) => {
+            let hi_addr = (cpu.temp as u8).wrapping_add(1) as u16;
+            cpu.temp = ((cpu.read_bus(hi_addr) as u16) << 8) | cpu.addr_abs;
+            cpu.addr_abs = cpu.temp.wrapping_add(cpu.y_reg as u16);
+        }
+
+        _ => {}
+    }
+}
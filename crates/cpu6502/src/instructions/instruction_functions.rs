@@ -0,0 +1,833 @@
+use crate::bus::CpuBus;
+use crate::cpu::{CPUFlags, Cpu6502};
+use crate::instructions::instruction::AddressingMode;
+
+#[allow(non_snake_case)]
+pub fn ADC<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    // Grab the data we're adding to the acc
+    cpu.fetch();
+    add_with_carry(cpu, cpu.fetched);
+}
+
+// Instruction: Bitwise Logic AND
+// Function:    A = A & M
+// Flags Out:   N, Z
+#[allow(non_snake_case)]
+pub fn AND<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.fetch();
+    cpu.acc &= cpu.fetched;
+    set_nz_flags(cpu, cpu.acc);
+}
+
+// Instruction: Arithmetic Shift Left
+// Function:    A = C <- (A << 1) <- 0
+// Flags Out:   N, Z, C
+#[allow(non_snake_case)]
+pub fn ASL<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.fetch();
+    cpu.temp = (cpu.fetched as u16) << 1;
+    set_carry(cpu, cpu.temp);
+    set_nz_flags(cpu, cpu.temp as u8);
+    if cpu.addressing_mode == AddressingMode::IMP {
+        cpu.acc = (cpu.temp & 0x00FF) as u8;
+    } else {
+        cpu.write_bus(cpu.addr_abs, cpu.temp as u8);
+    }
+}
+
+// Instruction: Branch if Carry Clear
+// Function:    if(C == 0) pc = address
+#[allow(non_snake_case)]
+pub fn BCC<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    if cpu.get_flag(CPUFlags::C) == 0 {
+        take_branch(cpu);
+    }
+}
+
+// Instruction: Branch if Carry Set
+// Function:    if(C == 1) pc = address
+#[allow(non_snake_case)]
+pub fn BCS<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    if cpu.get_flag(CPUFlags::C) == 1 {
+        take_branch(cpu);
+    }
+}
+
+// Instruction: Branch if Equal
+// Function:    if(Z == 1) pc = address
+#[allow(non_snake_case)]
+pub fn BEQ<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    if cpu.get_flag(CPUFlags::Z) == 1 {
+        take_branch(cpu);
+    }
+}
+
+#[allow(non_snake_case)]
+pub fn BIT<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.fetch();
+    cpu.temp = (cpu.acc & cpu.fetched) as u16;
+    cpu.set_flag(CPUFlags::Z, cpu.temp as u8 == 0x00);
+    cpu.set_flag(CPUFlags::N, (cpu.fetched & CPUFlags::N) > 0);
+    cpu.set_flag(CPUFlags::V, (cpu.fetched & CPUFlags::V) > 0);
+}
+
+// Instruction: Branch if Negative
+// Function:    if(N == 1) pc = address
+#[allow(non_snake_case)]
+pub fn BMI<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    if cpu.get_flag(CPUFlags::N) == 1 {
+        take_branch(cpu);
+    }
+}
+
+// Instruction: Branch if Not Equal
+// Function:    if(Z == 0) pc = address
+#[allow(non_snake_case)]
+pub fn BNE<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    if cpu.get_flag(CPUFlags::Z) == 0 {
+        take_branch(cpu);
+    }
+}
+
+// Instruction: Branch if Positive
+// Function:    if(N == 0) pc = address
+#[allow(non_snake_case)]
+pub fn BPL<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    if cpu.get_flag(CPUFlags::N) == 0 {
+        take_branch(cpu);
+    }
+}
+
+// Instruction: Break
+// Function:    Program Sourced Interrupt
+// Note:        The pushes and vector fetch happen over the cycles before this,
+//              irq and nmi run through here too
+#[allow(non_snake_case)]
+pub fn BRK<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.pc = cpu.addr_abs;
+}
+
+// Instruction: Branch if Overflow Clear
+// Function:    if(V == 0) pc = address
+#[allow(non_snake_case)]
+pub fn BVC<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    if cpu.get_flag(CPUFlags::V) == 0 {
+        take_branch(cpu);
+    }
+}
+
+// Instruction: Branch if Overflow Set
+// Function:    if(V == 1) pc = address
+#[allow(non_snake_case)]
+pub fn BVS<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    if cpu.get_flag(CPUFlags::V) == 1 {
+        take_branch(cpu);
+    }
+}
+
+// Instruction: Clear Carry Flag
+// Function:    C = 0
+#[allow(non_snake_case)]
+pub fn CLC<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.set_flag(CPUFlags::C, false);
+}
+
+// Instruction: Clear Decimal Flag
+// Function:    D = 0
+#[allow(non_snake_case)]
+pub fn CLD<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.set_flag(CPUFlags::D, false);
+}
+
+// Instruction: Disable Interrupts / Clear Interrupt Flag
+// Function:    I = 0
+#[allow(non_snake_case)]
+pub fn CLI<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.set_flag(CPUFlags::I, false);
+}
+
+// Instruction: Clear Overflow Flag
+// Function:    V = 0
+#[allow(non_snake_case)]
+pub fn CLV<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.set_flag(CPUFlags::V, false);
+}
+
+// Instruction: Compare Accumulator
+// Function:    C <- A >= M      Z <- (A - M) == 0
+// Flags Out:   N, C, Z
+#[allow(non_snake_case)]
+pub fn CMP<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.fetch();
+    compare(cpu, cpu.acc);
+}
+
+// Instruction: Compare X Register
+// Function:    C <- X >= M      Z <- (X - M) == 0
+// Flags Out:   N, C, Z
+#[allow(non_snake_case)]
+pub fn CPX<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.fetch();
+    compare(cpu, cpu.x_reg);
+}
+
+// Instruction: Compare Y Register
+// Function:    C <- Y >= M      Z <- (Y - M) == 0
+// Flags Out:   N, C, Z
+#[allow(non_snake_case)]
+pub fn CPY<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.fetch();
+    compare(cpu, cpu.y_reg);
+}
+
+// Instruction: Decrement Value at Memory Location
+// Function:    M = M - 1
+// Flags Out:   N, Z
+#[allow(non_snake_case)]
+pub fn DEC<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.fetch();
+    let x = cpu.fetched.wrapping_sub(1);
+    cpu.temp = x as u16;
+    cpu.write_bus(cpu.addr_abs, x);
+    set_nz_flags(cpu, x);
+}
+
+// Instruction: Decrement X Register
+// Function:    X = X - 1
+// Flags Out:   N, Z
+#[allow(non_snake_case)]
+pub fn DEX<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.x_reg = cpu.x_reg.wrapping_sub(1);
+    set_nz_flags(cpu, cpu.x_reg);
+}
+
+// Instruction: Decrement Y Register
+// Function:    Y = Y - 1
+// Flags Out:   N, Z
+#[allow(non_snake_case)]
+pub fn DEY<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.y_reg = cpu.y_reg.wrapping_sub(1);
+    set_nz_flags(cpu, cpu.y_reg);
+}
+
+// Instruction: Bitwise Logic XOR
+// Function:    A = A xor M
+// Flags Out:   N, Z
+#[allow(non_snake_case)]
+pub fn EOR<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.fetch();
+    cpu.acc ^= cpu.fetched;
+    set_nz_flags(cpu, cpu.acc);
+}
+
+// Instruction: Increment Value at Memory Location
+// Function:    M = M + 1
+// Flags Out:   N, Z
+#[allow(non_snake_case)]
+pub fn INC<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.fetch();
+    let x = cpu.fetched.wrapping_add(1);
+    cpu.temp = x as u16;
+    cpu.write_bus(cpu.addr_abs, x);
+    set_nz_flags(cpu, x);
+}
+
+// Instruction: Increment X Register
+// Function:    X = X + 1
+// Flags Out:   N, Z
+#[allow(non_snake_case)]
+pub fn INX<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    let x = cpu.x_reg.wrapping_add(1);
+    cpu.x_reg = x;
+    set_nz_flags(cpu, x);
+}
+
+// Instruction: Increment Y Register
+// Function:    Y = Y + 1
+// Flags Out:   N, Z
+#[allow(non_snake_case)]
+pub fn INY<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    let x = cpu.y_reg.wrapping_add(1);
+    cpu.y_reg = x;
+    set_nz_flags(cpu, x);
+}
+
+#[allow(non_snake_case)]
+pub fn JMP<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.pc = cpu.addr_abs;
+}
+
+// Instruction: Jump to Subroutine
+// Note:        The return address was pushed while the target was being read
+#[allow(non_snake_case)]
+pub fn JSR<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.pc = cpu.addr_abs;
+}
+
+#[allow(non_snake_case)]
+pub fn LDA<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.fetch();
+    cpu.acc = cpu.fetched;
+    set_nz_flags(cpu, cpu.acc);
+}
+
+#[allow(non_snake_case)]
+pub fn LDX<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.fetch();
+    cpu.x_reg = cpu.fetched;
+    set_nz_flags(cpu, cpu.x_reg);
+}
+
+#[allow(non_snake_case)]
+pub fn LDY<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.fetch();
+    cpu.y_reg = cpu.fetched;
+    set_nz_flags(cpu, cpu.y_reg);
+}
+
+#[allow(non_snake_case)]
+pub fn LSR<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.fetch();
+    cpu.temp = (cpu.fetched >> 1) as u16;
+
+    set_nz_flags(cpu, cpu.temp as u8);
+    cpu.set_flag(CPUFlags::C, cpu.fetched & 0x01 > 0);
+
+    if cpu.addressing_mode == AddressingMode::IMP {
+        cpu.acc = cpu.temp as u8;
+    } else {
+        cpu.write_bus(cpu.addr_abs, cpu.temp as u8);
+    }
+}
+
+// Instruction: No Operation
+// Note:        The unofficial versions still read their operand
+#[allow(non_snake_case)]
+pub fn NOP<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    if cpu.addressing_mode != AddressingMode::IMP {
+        cpu.fetch();
+    }
+}
+
+// Instruction: Bitwise Logic OR
+// Function:    A = A | M
+// Flags Out:   N, Z
+#[allow(non_snake_case)]
+pub fn ORA<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.fetch();
+    cpu.acc |= cpu.fetched;
+    set_nz_flags(cpu, cpu.acc);
+}
+
+// Instruction: Push Accumulator to Stack
+// Function:    A -> stack
+#[allow(non_snake_case)]
+pub fn PHA<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.write_bus(0x0100 + cpu.stack_pointer as u16, cpu.acc);
+    cpu.stack_pointer = cpu.stack_pointer.wrapping_sub(1);
+}
+
+// Instruction: Push Status Register to Stack
+// Function:    status -> stack
+// Note:        Break flag is set to 1 before push
+#[allow(non_snake_case)]
+pub fn PHP<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.write_bus(
+        0x0100 + cpu.stack_pointer as u16,
+        cpu.status | CPUFlags::B | CPUFlags::U,
+    );
+    cpu.set_flag(CPUFlags::B, false);
+    cpu.set_flag(CPUFlags::U, false);
+    cpu.stack_pointer = cpu.stack_pointer.wrapping_sub(1);
+}
+
+// Instruction: Pop Accumulator off Stack
+// Function:    A <- stack
+// Flags Out:   N, Z
+#[allow(non_snake_case)]
+pub fn PLA<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.stack_pointer = cpu.stack_pointer.wrapping_add(1);
+    cpu.acc = cpu.read_bus(0x0100 + cpu.stack_pointer as u16);
+    set_nz_flags(cpu, cpu.acc);
+}
+
+// Instruction: Pop Status Register off Stack
+// Function:    Status <- stack
+#[allow(non_snake_case)]
+pub fn PLP<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.stack_pointer = cpu.stack_pointer.wrapping_add(1);
+    cpu.status = cpu.read_bus(0x0100 + cpu.stack_pointer as u16);
+    cpu.set_flag(CPUFlags::U, true); // not needed??
+}
+
+#[allow(non_snake_case)]
+pub fn ROL<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.fetch();
+    cpu.temp = ((cpu.fetched as u16) << 1) | (cpu.get_flag(CPUFlags::C)) as u16;
+    let x: u8 = (cpu.temp & 0x00FF) as u8;
+
+    set_carry(cpu, cpu.temp);
+    set_nz_flags(cpu, cpu.temp as u8);
+    if cpu.addressing_mode == AddressingMode::IMP {
+        cpu.acc = x
+    } else {
+        cpu.write_bus(cpu.addr_abs, x)
+    }
+}
+
+#[allow(non_snake_case)]
+pub fn ROR<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.fetch();
+    cpu.temp = ((cpu.fetched >> 1) | cpu.get_flag(CPUFlags::C) << 7) as u16;
+    let x: u8 = (cpu.temp & 0x00FF) as u8;
+
+    // needs a special case
+    cpu.set_flag(CPUFlags::C, cpu.fetched & 0x01 > 0);
+
+    set_nz_flags(cpu, cpu.temp as u8);
+    if cpu.addressing_mode == AddressingMode::IMP {
+        cpu.acc = x
+    } else {
+        cpu.write_bus(cpu.addr_abs, x);
+    }
+}
+
+// Instruction: Return from Interrupt
+// Note:        Status and pc were pulled over the cycles before this, B and U
+//              only exist on the stack
+#[allow(non_snake_case)]
+pub fn RTI<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.status &= !CPUFlags::B;
+    cpu.status &= !CPUFlags::U;
+}
+
+// Instruction: Return from Subroutine
+// Note:        JSR pushed the address of its last byte, step past it
+#[allow(non_snake_case)]
+pub fn RTS<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.pc = cpu.pc.wrapping_add(1);
+}
+
+// Instruction: Subtraction with Borrow In
+// Function:    A = A - M - (1 - C)
+// Flags Out:   C, V, N, Z
+#[allow(non_snake_case)]
+pub fn SBC<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.fetch();
+    subtract_with_borrow(cpu, cpu.fetched);
+}
+
+// Instruction: Set Carry Flag
+// Function:    C = 1
+#[allow(non_snake_case)]
+pub fn SEC<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.set_flag(CPUFlags::C, true);
+}
+
+// Instruction: Set Decimal Flag
+// Function:    D = 1
+#[allow(non_snake_case)]
+pub fn SED<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.set_flag(CPUFlags::D, true);
+}
+
+// Instruction: Set Interrupt Flag / Enable Interrupts
+// Function:    I = 1
+#[allow(non_snake_case)]
+pub fn SEI<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.set_flag(CPUFlags::I, true);
+}
+
+// Instruction: Store Accumulator at Address
+// Function:    M = A
+#[allow(non_snake_case)]
+pub fn STA<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.write_bus(cpu.addr_abs, cpu.acc);
+}
+
+// Instruction: Store X Register at Address
+// Function:    M = X
+#[allow(non_snake_case)]
+pub fn STX<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.write_bus(cpu.addr_abs, cpu.x_reg);
+}
+
+// Instruction: Store Y Register at Address
+// Function:    M = Y
+#[allow(non_snake_case)]
+pub fn STY<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.write_bus(cpu.addr_abs, cpu.y_reg);
+}
+
+// Instruction: Transfer Accumulator to X Register
+// Function:    X = A
+// Flags Out:   N, Z
+#[allow(non_snake_case)]
+pub fn TAX<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.x_reg = cpu.acc;
+    set_nz_flags(cpu, cpu.x_reg);
+}
+
+// Instruction: Transfer Accumulator to Y Register
+// Function:    Y = A
+// Flags Out:   N, Z
+#[allow(non_snake_case)]
+pub fn TAY<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.y_reg = cpu.acc;
+    set_nz_flags(cpu, cpu.y_reg);
+}
+
+// Instruction: Transfer Stack Pointer to X Register
+// Function:    X = stack pointer
+// Flags Out:   N, Z
+#[allow(non_snake_case)]
+pub fn TSX<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.x_reg = cpu.stack_pointer;
+    set_nz_flags(cpu, cpu.x_reg);
+}
+
+// Instruction: Transfer X Register to Accumulator
+// Function:    A = X
+// Flags Out:   N, Z
+#[allow(non_snake_case)]
+pub fn TXA<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.acc = cpu.x_reg;
+    set_nz_flags(cpu, cpu.acc);
+}
+
+// Instruction: Transfer X Register to Stack Pointer
+// Function:    stack pointer = X
+#[allow(non_snake_case)]
+pub fn TXS<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.stack_pointer = cpu.x_reg;
+}
+
+// Instruction: Transfer Y Register to Accumulator
+// Function:    A = Y
+// Flags Out:   N, Z
+#[allow(non_snake_case)]
+pub fn TYA<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.acc = cpu.y_reg;
+    set_nz_flags(cpu, cpu.acc);
+}
+
+// Unofficial opcodes
+// https://www.nesdev.org/wiki/CPU_unofficial_opcodes
+//
+// Most of these are two official instructions glued together because the
+// decoder enables both of them at once.
+
+// Instruction: Store A & X & (high byte of address + 1)
+// Function:    M = A & X & (H + 1)
+#[allow(non_snake_case)]
+pub fn AHX<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    let value = cpu.acc & cpu.x_reg;
+    store_and_high_byte(cpu, value, cpu.y_reg);
+}
+
+// Instruction: AND then Logical Shift Right
+// Function:    A = (A & M) >> 1
+// Flags Out:   N, Z, C
+#[allow(non_snake_case)]
+pub fn ALR<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.fetch();
+    let x = cpu.acc & cpu.fetched;
+    cpu.set_flag(CPUFlags::C, x & 0x01 > 0);
+    cpu.acc = x >> 1;
+    set_nz_flags(cpu, cpu.acc);
+}
+
+// Instruction: AND then copy N to C
+// Function:    A = A & M
+// Flags Out:   N, Z, C
+#[allow(non_snake_case)]
+pub fn ANC<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    AND(cpu);
+    cpu.set_flag(CPUFlags::C, cpu.acc & 0x80 > 0);
+}
+
+// Instruction: AND then Rotate Right
+// Function:    A = (A & M) >> 1 | C << 7
+// Flags Out:   N, Z, C = bit 6, V = bit 6 ^ bit 5
+#[allow(non_snake_case)]
+pub fn ARR<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.fetch();
+    let x = cpu.acc & cpu.fetched;
+    cpu.acc = (x >> 1) | (cpu.get_flag(CPUFlags::C) << 7);
+    set_nz_flags(cpu, cpu.acc);
+    cpu.set_flag(CPUFlags::C, cpu.acc & 0x40 > 0);
+    cpu.set_flag(CPUFlags::V, ((cpu.acc >> 6) ^ (cpu.acc >> 5)) & 0x01 > 0);
+}
+
+// Instruction: AND X with A then Subtract without borrow
+// Function:    X = (A & X) - M
+// Flags Out:   N, Z, C
+#[allow(non_snake_case)]
+pub fn AXS<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.fetch();
+    let x = cpu.acc & cpu.x_reg;
+    compare(cpu, x);
+    cpu.x_reg = x.wrapping_sub(cpu.fetched);
+}
+
+// Instruction: Decrement then Compare
+// Function:    M = M - 1, A - M
+// Flags Out:   N, Z, C
+#[allow(non_snake_case)]
+pub fn DCP<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.fetch();
+    cpu.fetched = cpu.fetched.wrapping_sub(1);
+    cpu.write_bus(cpu.addr_abs, cpu.fetched);
+    compare(cpu, cpu.acc);
+}
+
+// Instruction: Increment then Subtract with Borrow In
+// Function:    M = M + 1, A = A - M - (1 - C)
+// Flags Out:   C, V, N, Z
+#[allow(non_snake_case)]
+pub fn ISC<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.fetch();
+    cpu.fetched = cpu.fetched.wrapping_add(1);
+    cpu.write_bus(cpu.addr_abs, cpu.fetched);
+    subtract_with_borrow(cpu, cpu.fetched);
+}
+
+// Instruction: Jam
+// Function:    Locks up the cpu, only a reset gets it going again
+#[allow(non_snake_case)]
+pub fn JAM<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.pc = cpu.pc.wrapping_sub(1);
+    cpu.jammed = true;
+}
+
+// Instruction: Load A, X and the stack pointer
+// Function:    A = X = SP = M & SP
+// Flags Out:   N, Z
+#[allow(non_snake_case)]
+pub fn LAS<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.fetch();
+    let x = cpu.fetched & cpu.stack_pointer;
+    cpu.acc = x;
+    cpu.x_reg = x;
+    cpu.stack_pointer = x;
+    set_nz_flags(cpu, x);
+}
+
+// Instruction: Load A and X
+// Function:    A = X = M
+// Flags Out:   N, Z
+#[allow(non_snake_case)]
+pub fn LAX<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    LDA(cpu);
+    cpu.x_reg = cpu.acc;
+}
+
+// Instruction: Load A and X, unstable
+// Function:    A = X = (A | magic) & M
+// Flags Out:   N, Z
+// Note:        The magic constant changes between chips, $EE is the common one
+#[allow(non_snake_case)]
+pub fn LXA<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.fetch();
+    cpu.acc = (cpu.acc | 0xEE) & cpu.fetched;
+    cpu.x_reg = cpu.acc;
+    set_nz_flags(cpu, cpu.acc);
+}
+
+// Instruction: Rotate Left then AND
+// Function:    M = C <- (M << 1) <- C, A = A & M
+// Flags Out:   N, Z, C
+#[allow(non_snake_case)]
+pub fn RLA<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.fetch();
+    let x = (cpu.fetched << 1) | cpu.get_flag(CPUFlags::C);
+    cpu.set_flag(CPUFlags::C, cpu.fetched & 0x80 > 0);
+    cpu.write_bus(cpu.addr_abs, x);
+    cpu.acc &= x;
+    set_nz_flags(cpu, cpu.acc);
+}
+
+// Instruction: Rotate Right then Add with Carry In
+// Function:    M = C -> (M >> 1) -> C, A = A + M + C
+// Flags Out:   C, V, N, Z
+#[allow(non_snake_case)]
+pub fn RRA<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.fetch();
+    let x = (cpu.fetched >> 1) | (cpu.get_flag(CPUFlags::C) << 7);
+    cpu.set_flag(CPUFlags::C, cpu.fetched & 0x01 > 0);
+    cpu.write_bus(cpu.addr_abs, x);
+    add_with_carry(cpu, x);
+}
+
+// Instruction: Store A & X
+// Function:    M = A & X
+#[allow(non_snake_case)]
+pub fn SAX<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.write_bus(cpu.addr_abs, cpu.acc & cpu.x_reg);
+}
+
+// Instruction: Store X & (high byte of address + 1)
+// Function:    M = X & (H + 1)
+#[allow(non_snake_case)]
+pub fn SHX<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    store_and_high_byte(cpu, cpu.x_reg, cpu.y_reg);
+}
+
+// Instruction: Store Y & (high byte of address + 1)
+// Function:    M = Y & (H + 1)
+#[allow(non_snake_case)]
+pub fn SHY<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    store_and_high_byte(cpu, cpu.y_reg, cpu.x_reg);
+}
+
+// Instruction: Arithmetic Shift Left then OR
+// Function:    M = C <- (M << 1) <- 0, A = A | M
+// Flags Out:   N, Z, C
+#[allow(non_snake_case)]
+pub fn SLO<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.fetch();
+    let x = cpu.fetched << 1;
+    cpu.set_flag(CPUFlags::C, cpu.fetched & 0x80 > 0);
+    cpu.write_bus(cpu.addr_abs, x);
+    cpu.acc |= x;
+    set_nz_flags(cpu, cpu.acc);
+}
+
+// Instruction: Logical Shift Right then XOR
+// Function:    M = 0 -> (M >> 1) -> C, A = A xor M
+// Flags Out:   N, Z, C
+#[allow(non_snake_case)]
+pub fn SRE<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.fetch();
+    let x = cpu.fetched >> 1;
+    cpu.set_flag(CPUFlags::C, cpu.fetched & 0x01 > 0);
+    cpu.write_bus(cpu.addr_abs, x);
+    cpu.acc ^= x;
+    set_nz_flags(cpu, cpu.acc);
+}
+
+// Instruction: Transfer A & X to the stack pointer then store like AHX
+// Function:    SP = A & X, M = SP & (H + 1)
+#[allow(non_snake_case)]
+pub fn TAS<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.stack_pointer = cpu.acc & cpu.x_reg;
+    store_and_high_byte(cpu, cpu.stack_pointer, cpu.y_reg);
+}
+
+// Instruction: Transfer X to A then AND, unstable
+// Function:    A = (A | magic) & X & M
+// Flags Out:   N, Z
+#[allow(non_snake_case)]
+pub fn XAA<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.fetch();
+    cpu.acc = (cpu.acc | 0xEE) & cpu.x_reg & cpu.fetched;
+    set_nz_flags(cpu, cpu.acc);
+}
+
+#[allow(non_snake_case)]
+pub fn XXX<B: CpuBus>(_cpu: &mut Cpu6502<B>) {}
+
+// Shared by ADC and the unofficial opcodes built on it
+fn add_with_carry<B: CpuBus>(cpu: &mut Cpu6502<B>, value: u8) {
+    let acc = cpu.acc;
+    let carry = cpu.get_flag(CPUFlags::C) as u16;
+    binary_add(cpu, value);
+
+    if decimal_mode(cpu) {
+        // The nmos 6502 fixes up each nibble of the binary sum, only the
+        // carry reflects the decimal result, N, V and Z stay binary
+        let mut low = (acc & 0x0F) as u16 + (value & 0x0F) as u16 + carry;
+        if low > 0x09 {
+            low += 0x06;
+        }
+        let mut high = (acc >> 4) as u16 + (value >> 4) as u16 + (low > 0x0F) as u16;
+        if high > 0x09 {
+            high += 0x06;
+        }
+        cpu.set_flag(CPUFlags::C, high > 0x0F);
+        cpu.acc = ((high << 4) | (low & 0x0F)) as u8;
+    }
+}
+
+// Shared by SBC and the unofficial opcodes built on it
+fn subtract_with_borrow<B: CpuBus>(cpu: &mut Cpu6502<B>, value: u8) {
+    let acc = cpu.acc;
+    let borrow = 1 - cpu.get_flag(CPUFlags::C) as i16;
+    // Subtracting is adding the inverted value, the carry is the borrow
+    binary_add(cpu, value ^ 0xFF);
+
+    if decimal_mode(cpu) {
+        // All flags, carry included, come from the binary subtraction
+        let mut low = (acc & 0x0F) as i16 - (value & 0x0F) as i16 - borrow;
+        if low < 0 {
+            low = ((low - 0x06) & 0x0F) - 0x10;
+        }
+        let mut result = (acc & 0xF0) as i16 - (value & 0xF0) as i16 + low;
+        if result < 0 {
+            result -= 0x60;
+        }
+        cpu.acc = result as u8;
+    }
+}
+
+fn decimal_mode<B: CpuBus>(cpu: &Cpu6502<B>) -> bool {
+    cpu.decimal_enabled && cpu.get_flag(CPUFlags::D) == 1
+}
+
+fn binary_add<B: CpuBus>(cpu: &mut Cpu6502<B>, value: u8) {
+    // Add is performed in 16-bit domain for emulation to capture any
+    // carry bit, which will exist in bit 8 of the 16-bit word
+    cpu.temp = cpu.acc as u16 + value as u16 + cpu.get_flag(CPUFlags::C) as u16;
+    cpu.set_flag(
+        CPUFlags::V,
+        (!(cpu.acc ^ value) as u16 & (cpu.acc as u16 ^ cpu.temp)) & 0x0080 > 0,
+    );
+    set_carry(cpu, cpu.temp);
+    set_nz_flags(cpu, cpu.temp as u8);
+
+    cpu.acc = cpu.temp as u8;
+}
+
+// Compares a register against the fetched value like CMP, CPX and CPY
+fn compare<B: CpuBus>(cpu: &mut Cpu6502<B>, reg: u8) {
+    let x = reg.wrapping_sub(cpu.fetched);
+    cpu.temp = x as u16;
+    cpu.set_flag(CPUFlags::C, reg >= cpu.fetched);
+    set_nz_flags(cpu, x);
+}
+
+// The SH* stores and the high byte of the address, if indexing crossed a page
+// the value ends up as the high byte of the address too
+fn store_and_high_byte<B: CpuBus>(cpu: &mut Cpu6502<B>, value: u8, index: u8) {
+    let base = cpu.addr_abs.wrapping_sub(index as u16);
+    let x = value & ((base >> 8) as u8).wrapping_add(1);
+    let addr = if (base & 0xFF00) != (cpu.addr_abs & 0xFF00) {
+        ((x as u16) << 8) | (cpu.addr_abs & 0x00FF)
+    } else {
+        cpu.addr_abs
+    };
+    cpu.write_bus(addr, x);
+}
+
+fn set_carry<B: CpuBus>(cpu: &mut Cpu6502<B>, reg: u16) {
+    cpu.set_flag(CPUFlags::C, reg & 0xFF00 > 0);
+}
+
+fn set_z_if_reg_zero<B: CpuBus>(cpu: &mut Cpu6502<B>, reg: u8) {
+    cpu.set_flag(CPUFlags::Z, reg == 0);
+}
+
+fn set_n_if_bit_set<B: CpuBus>(cpu: &mut Cpu6502<B>, reg: u8) {
+    cpu.set_flag(CPUFlags::N, reg & 0x80 > 0);
+}
+
+fn set_nz_flags<B: CpuBus>(cpu: &mut Cpu6502<B>, reg: u8) {
+    set_z_if_reg_zero(cpu, reg);
+    set_n_if_bit_set(cpu, reg);
+}
+
+// The branch target, the cpu spends the extra cycles getting pc there
+fn take_branch<B: CpuBus>(cpu: &mut Cpu6502<B>) {
+    cpu.addr_abs = cpu.pc.wrapping_add(cpu.addr_rel);
+    cpu.branch_taken = true;
+}
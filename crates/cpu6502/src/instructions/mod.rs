@@ -1,3 +1,4 @@
 pub mod instruction;
+pub mod instruction_cycles;
 pub mod instruction_functions;
 
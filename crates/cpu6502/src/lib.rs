@@ -0,0 +1,12 @@
+//! A cycle stepped nmos 6502, the one inside the nes' 2A03. It doesn't know
+//! anything about the nes, it runs against whatever implements `CpuBus`.
+
+pub mod bus;
+pub mod cpu;
+pub mod instructions;
+
+#[cfg(test)]
+mod cpu_tests;
+
+pub use bus::{BusReader, BusWriter, CpuBus};
+pub use cpu::{CPUFlags, Cpu6502};
@@ -0,0 +1,129 @@
+use crate::consts::{
+    debug_consts::PPU_GAME_SCALE,
+    emulation_consts::{AUDIO_SAMPLE_RATE, EMU_START_STATE},
+};
+use crate::emulator::FrameSync;
+use crate::nes::Region;
+use crate::trace::TraceFilter;
+
+use std::ops::RangeInclusive;
+
+pub const USAGE: &str = "\
+Usage: nes-rs [OPTIONS] <ROM>
+
+Options:
+    --paused            Start with emulation stopped (default)
+    --run               Start running as soon as the window opens
+    --scale <N>         Scale of the game window (default 2)
+    --region <REGION>   ntsc, pal or dendy (default taken from the rom header)
+    --sample-rate <HZ>  Audio output rate, e.g. 44100 or 48000 (default 48000)
+    --trace <FILE>      Log every executed instruction to FILE
+    --trace-pc <RANGE>  Only trace instructions in a hex range, e.g. C000-C0FF
+    --trace-frame <N>   Only trace once N frames have been drawn
+    -h, --help          Print this message";
+
+pub struct Args {
+    pub rom: String,
+    pub start_state: FrameSync,
+    pub scale: f32,
+    pub region: Option<Region>,
+    pub sample_rate: u32,
+    pub trace: Option<String>,
+    pub trace_filter: TraceFilter,
+}
+
+impl Args {
+    pub fn parse() -> Result<Args, String> {
+        Args::parse_from(std::env::args().skip(1))
+    }
+
+    pub fn parse_from<I: Iterator<Item = String>>(mut args: I) -> Result<Args, String> {
+        let mut rom: Option<String> = None;
+        let mut start_state = EMU_START_STATE;
+        let mut scale = PPU_GAME_SCALE;
+        let mut region: Option<Region> = None;
+        let mut sample_rate = AUDIO_SAMPLE_RATE;
+        let mut trace: Option<String> = None;
+        let mut trace_filter = TraceFilter::default();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => return Err(String::new()),
+                "--paused" => start_state = FrameSync::Stop,
+                "--run" => start_state = FrameSync::Run,
+                "--scale" => {
+                    let value = args.next().ok_or("--scale expects a value")?;
+                    scale = match value.parse::<f32>() {
+                        Ok(s) if s > 0.0 => s,
+                        _ => return Err(format!("Invalid scale \"{}\"", value)),
+                    };
+                }
+                "--region" => {
+                    let value = args.next().ok_or("--region expects a value")?;
+                    region = match value.to_lowercase().as_str() {
+                        "ntsc" => Some(Region::Ntsc),
+                        "pal" => Some(Region::Pal),
+                        "dendy" => Some(Region::Dendy),
+                        _ => return Err(format!("Unknown region \"{}\"", value)),
+                    };
+                }
+                "--sample-rate" => {
+                    let value = args.next().ok_or("--sample-rate expects a value")?;
+                    sample_rate = match value.parse::<u32>() {
+                        Ok(r) if (8000..=192000).contains(&r) => r,
+                        _ => return Err(format!("Invalid sample rate \"{}\"", value)),
+                    };
+                }
+                "--trace" => trace = Some(args.next().ok_or("--trace expects a file")?),
+                "--trace-pc" => {
+                    let value = args.next().ok_or("--trace-pc expects a range")?;
+                    trace_filter.pc_range = match parse_hex_range(&value) {
+                        Some(range) => Some(range),
+                        None => return Err(format!("Invalid pc range \"{}\"", value)),
+                    };
+                }
+                "--trace-frame" => {
+                    let value = args.next().ok_or("--trace-frame expects a value")?;
+                    trace_filter.start_frame = match value.parse::<i32>() {
+                        Ok(f) if f >= 0 => f,
+                        _ => return Err(format!("Invalid frame \"{}\"", value)),
+                    };
+                }
+                x if x.starts_with('-') => return Err(format!("Unknown option \"{}\"", x)),
+                _ => {
+                    if rom.is_some() {
+                        return Err(format!("Unexpected argument \"{}\"", arg));
+                    }
+                    rom = Some(arg);
+                }
+            }
+        }
+
+        match rom {
+            Some(rom) => Ok(Args {
+                rom,
+                start_state,
+                scale,
+                region,
+                sample_rate,
+                trace,
+                trace_filter,
+            }),
+            None => Err("No rom file given".to_string()),
+        }
+    }
+}
+
+// "C000-C0FF", a single address traces just that one instruction
+fn parse_hex_range(value: &str) -> Option<RangeInclusive<u16>> {
+    let mut parts = value.splitn(2, '-');
+    let start = u16::from_str_radix(parts.next()?.trim_start_matches('$'), 16).ok()?;
+    let end = match parts.next() {
+        Some(end) => u16::from_str_radix(end.trim_start_matches('$'), 16).ok()?,
+        None => start,
+    };
+    if start > end {
+        return None;
+    }
+    Some(start..=end)
+}
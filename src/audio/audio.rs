@@ -1,89 +1,155 @@
-const SCALING: f32 = std::f32::consts::FRAC_2_PI;
-
-use crate::ppu::structures::Register;
-
-struct AudioEnvelope {
-    length: u8,
-    frequency: u8,
-}
-
-
+// https://www.nesdev.org/wiki/APU
+//
+// $4000-$4003  Pulse 1, see pulse.rs
+// $4004-$4007  Pulse 2
+// $4008-$400B  Triangle, see triangle.rs
+// $400C-$400F  Noise, see noise.rs
+// $4010-$4013  DMC, see dmc.rs
+// $4015        Write ---D NT21  Channel enables
+//              Read  IF-D NT21  I: dmc irq, F: frame irq, D: dmc active, N/T/2/1: length counter > 0
+// $4017        Frame counter, see frame_counter.rs
+
+use super::dmc::Dmc;
+use super::frame_counter::FrameCounter;
+use super::noise::Noise;
+use super::pulse::Pulse;
+use super::triangle::Triangle;
+use crate::nes::Region;
 
 pub struct APU2A03 {
-    
+    pulse: [Pulse; 2],
+    triangle: Triangle,
+    noise: Noise,
+    dmc: Dmc,
+    frame_counter: FrameCounter,
+    cycle: usize,
+    /// The channels get mixed with resistors, not summed, so one loud
+    /// channel makes the others quieter. These are the nesdev approximations.
+    pulse_table: [f32; 31],
+    tnd_table: [f32; 203],
 }
 
 impl APU2A03 {
-    fn cpu_read(addr: u16) -> u8 {
-        0
+    pub fn new(region: Region) -> Self {
+        APU2A03 {
+            pulse: [Pulse::new(true), Pulse::new(false)],
+            triangle: Triangle::default(),
+            noise: Noise::new(region),
+            dmc: Dmc::new(region),
+            frame_counter: FrameCounter::new(region),
+            cycle: 0,
+            pulse_table: mixer_table(95.52, 8128.0),
+            tnd_table: mixer_table(163.67, 24329.0),
+        }
     }
 
-    fn cpu_write(addr: u16, data: u8) {
+    /// Only $4015 can be read, reading it acknowledges the frame irq but not the dmc's
+    pub fn cpu_read(&mut self, addr: u16, read_only: bool) -> u8 {
+        if addr != 0x4015 {
+            return 0;
+        }
+        let mut status = 0;
+        status |= self.pulse[0].length.active() as u8;
+        status |= (self.pulse[1].length.active() as u8) << 1;
+        status |= (self.triangle.length.active() as u8) << 2;
+        status |= (self.noise.length.active() as u8) << 3;
+        status |= ((self.dmc.bytes_remaining > 0) as u8) << 4;
+        status |= (self.frame_counter.irq as u8) << 6;
+        status |= (self.dmc.irq as u8) << 7;
+        if !read_only {
+            self.frame_counter.irq = false;
+        }
+        status
+    }
+
+    pub fn cpu_write(&mut self, addr: u16, data: u8) {
         match addr {
-            // Pulse 1
-            0x4000 => {}
-            0x4001 => {}
-            0x4002 => {}
-            0x4003 => {}
-
-            // Pulse 2
-            0x4004 => {}
-            0x4005 => {}
-            0x4006 => {}
-            0x4007 => {}
-
-            // Triangle
-            0x4008 => {}
-            0x4009 => {}
-            0x400A => {}
-            0x400B => {}
-
-            // Noise
-            0x400C => {}
-            0x400D => {}
-            0x400E => {}
-            0x400F => {}
-
-            // DMC
-            0x4010 => {}
-            0x4011 => {}
-            0x4012 => {}
-            0x4013 => {}
-
-            // Unused
-            0x4014 => {}
-
-            // All - Channel enable and length counter
-            0x4015 => {}
-
-            // Unused
-            0x4016 => {}
-
-            // All - Frame counter
-            0x4017 => {}
+            0x4000..=0x4003 => self.pulse[0].write(addr & 0x03, data),
+            0x4004..=0x4007 => self.pulse[1].write(addr & 0x03, data),
+            0x4008..=0x400B => self.triangle.write(addr & 0x03, data),
+            0x400C..=0x400F => self.noise.write(addr & 0x03, data),
+            0x4010..=0x4013 => self.dmc.write(addr & 0x03, data),
+
+            0x4015 => {
+                self.pulse[0].length.set_enabled(data & 0x01 > 0);
+                self.pulse[1].length.set_enabled(data & 0x02 > 0);
+                self.triangle.length.set_enabled(data & 0x04 > 0);
+                self.noise.length.set_enabled(data & 0x08 > 0);
+                self.dmc.set_enabled(data & 0x10 > 0);
+            }
+
+            0x4017 => self.frame_counter.write(data, self.cycle % 2 == 1),
             _ => {}
         }
     }
 
-    fn clock() {}
+    /// Once per cpu cycle
+    pub fn clock(&mut self) {
+        let frame = self.frame_counter.clock();
+        if frame.quarter {
+            self.pulse[0].envelope.clock();
+            self.pulse[1].envelope.clock();
+            self.noise.envelope.clock();
+            self.triangle.clock_linear();
+        }
+        if frame.half {
+            for pulse in self.pulse.iter_mut() {
+                pulse.length.clock();
+                pulse.clock_sweep();
+            }
+            self.triangle.length.clock();
+            self.noise.length.clock();
+        }
 
-    fn reset() {}
-}
+        self.pulse[0].clock();
+        self.pulse[1].clock();
+        self.triangle.clock();
+        self.noise.clock();
+        self.dmc.clock();
+        self.cycle += 1;
+    }
 
-fn pulse_wave_generator(duty_cycle: f32, harmonics: usize, frequency: f32, time: f32) -> f32 {
-    let (mut a, mut b): (f32, f32) = (0.0, 0.0);
-    let p: f32 = duty_cycle * std::f32::consts::TAU;
-    for n in 1..=harmonics {
-        let harmonic: f32 = n as f32;
-        let c: f32 = harmonic * frequency * std::f32::consts::TAU * time;
-        a += c.sin() / harmonic;
-        b += (c - p * harmonic).sin() / harmonic;
+    /// True while the frame counter is pulling the cpu's irq line low
+    pub fn frame_irq(&self) -> bool {
+        self.frame_counter.irq
     }
-    SCALING * (a - b)
-}
 
-fn triangle_wave_generator() {}
+    /// True while the dmc is pulling the cpu's irq line low
+    pub fn dmc_irq(&self) -> bool {
+        self.dmc.irq
+    }
 
-fn noise_wave_generator() {}
+    /// The address the dmc wants its next sample byte from, the bus has to
+    /// halt the cpu and fetch it then hand it over with `dmc_dma_fill`
+    pub fn dmc_dma_address(&self) -> Option<u16> {
+        self.dmc.dma_address()
+    }
 
-fn dmc_wave_generator() {}
+    pub fn dmc_dma_fill(&mut self, data: u8) {
+        self.dmc.fill(data);
+    }
+
+    /// Mixed output of all the channels, 0.0 to about 1.0
+    pub fn output(&self) -> f32 {
+        let pulse = self.pulse[0].output() + self.pulse[1].output();
+        let tnd = 3 * self.triangle.output() as usize
+            + 2 * self.noise.output() as usize
+            + self.dmc.output() as usize;
+        self.pulse_table[pulse as usize] + self.tnd_table[tnd]
+    }
+
+    /// Silences every channel and restarts the frame counter in the mode it was in
+    pub fn reset(&mut self) {
+        self.cpu_write(0x4015, 0x00);
+        self.frame_counter.reset();
+    }
+}
+
+// https://www.nesdev.org/wiki/APU_Mixer#Lookup_Table
+fn mixer_table<const N: usize>(scale: f32, divisor: f32) -> [f32; N] {
+    let mut table = [0.0; N];
+    for (i, level) in table.iter_mut().enumerate().skip(1) {
+        *level = scale / (divisor / i as f32 + 100.0);
+    }
+    table
+}
@@ -0,0 +1,101 @@
+// http://www.slack.net/~ant/bl-synth/
+//
+// Band-limited step synthesis, the same idea as blargg's blip_buf. The apu's
+// output only ever changes in steps, so instead of generating every cpu
+// cycle and filtering, each change in level gets added as a band-limited
+// impulse at its exact (fractional) position in the output. Summing those
+// impulses up afterwards gives the band-limited steps, without the aliasing
+// that sampling the raw square waves would cause.
+
+use std::f64::consts::PI;
+
+/// Fractional positions between output samples an impulse can land on
+const PHASES: usize = 128;
+/// Width of an impulse in output samples, also how far output lags behind input
+const TAPS: usize = 16;
+/// Where the impulses get cut off, as a fraction of the output's nyquist frequency
+const CUTOFF: f64 = 0.8;
+
+pub(super) struct BlipBuffer {
+    /// Output samples per input clock
+    factor: f64,
+    /// Where the current frame starts in `deltas`, in fractional output samples
+    offset: f64,
+    deltas: Vec<f32>,
+    integrator: f32,
+    kernel: Vec<[f32; TAPS]>,
+}
+
+impl BlipBuffer {
+    pub(super) fn new(clock_rate: f64, sample_rate: u32) -> Self {
+        BlipBuffer {
+            factor: sample_rate as f64 / clock_rate,
+            offset: 0.0,
+            deltas: vec![0.0; TAPS],
+            integrator: 0.0,
+            kernel: (0..PHASES).map(impulse).collect(),
+        }
+    }
+
+    /// Adds a change in level `time` input clocks into the current frame
+    pub(super) fn add_delta(&mut self, time: u32, delta: f32) {
+        let position = self.offset + time as f64 * self.factor;
+        let mut index = position as usize;
+        let mut phase = ((position - index as f64) * PHASES as f64).round() as usize;
+        if phase == PHASES {
+            index += 1;
+            phase = 0;
+        }
+
+        if self.deltas.len() < index + TAPS {
+            self.deltas.resize(index + TAPS, 0.0);
+        }
+        for (d, k) in self.deltas[index..index + TAPS].iter_mut().zip(self.kernel[phase].iter()) {
+            *d += delta * k;
+        }
+    }
+
+    /// Closes off a frame `clocks` input clocks long and appends every
+    /// output sample that's complete to `out`
+    pub(super) fn end_frame(&mut self, clocks: u32, out: &mut Vec<f32>) {
+        self.offset += clocks as f64 * self.factor;
+        let count = self.offset as usize;
+        if self.deltas.len() < count + TAPS {
+            self.deltas.resize(count + TAPS, 0.0);
+        }
+
+        for delta in self.deltas.drain(..count) {
+            self.integrator += delta;
+            out.push(self.integrator);
+        }
+        self.offset -= count as f64;
+    }
+}
+
+// A windowed sinc for an impulse that lands `phase / PHASES` of a sample
+// late, normalised so a step of 1.0 always ends up exactly 1.0 higher
+fn impulse(phase: usize) -> [f32; TAPS] {
+    let mut kernel = [0.0; TAPS];
+    let center = (TAPS / 2) as f64 - 1.0 + phase as f64 / PHASES as f64;
+    for (i, k) in kernel.iter_mut().enumerate() {
+        let x = i as f64 - center;
+        let sinc = if x == 0.0 {
+            1.0
+        } else {
+            (PI * CUTOFF * x).sin() / (PI * CUTOFF * x)
+        };
+        // Blackman window over the whole kernel
+        let w = (i as f64 + 1.0 - phase as f64 / PHASES as f64) / TAPS as f64;
+        let window = 0.42 - 0.5 * (2.0 * PI * w).cos() + 0.08 * (4.0 * PI * w).cos();
+        *k = (sinc * window) as f32;
+    }
+    let sum: f32 = kernel.iter().sum();
+    for k in kernel.iter_mut() {
+        *k /= sum;
+    }
+    // Dividing rounds, so the last tap gets whatever makes the total exactly
+    // 1.0 when it's added up in the same order the integrator does
+    let rest: f32 = kernel[..TAPS - 1].iter().sum();
+    kernel[TAPS - 1] = 1.0 - rest;
+    kernel
+}
@@ -0,0 +1,157 @@
+// https://www.nesdev.org/wiki/APU_DMC
+//
+// $4010  IL-- RRRR  I: irq enable, L: loop, R: rate index
+// $4011  -DDD DDDD  Direct load of the output level
+// $4012  Sample address, $C000 + A * 64
+// $4013  Sample length, L * 16 + 1 bytes
+//
+// Plays 1 bit delta encoded samples straight out of cpu memory. The bytes get
+// fetched by dma, the bus does that part and hands them over with `fill`.
+
+use crate::nes::Region;
+
+// Periods in cpu cycles
+const NTSC_RATES: [u16; 16] = [
+    428, 380, 340, 320, 286, 254, 226, 214, 190, 160, 142, 128, 106, 84, 72, 54,
+];
+const PAL_RATES: [u16; 16] = [
+    398, 354, 316, 298, 276, 236, 210, 198, 176, 148, 132, 118, 98, 78, 66, 50,
+];
+
+pub(super) struct Dmc {
+    rates: &'static [u16; 16],
+    irq_enabled: bool,
+    pub(super) irq: bool,
+    looping: bool,
+    period: u16,
+    timer: u16,
+    level: u8,
+
+    sample_address: u16,
+    sample_length: u16,
+    current_address: u16,
+    pub(super) bytes_remaining: u16,
+    sample_buffer: Option<u8>,
+
+    shift: u8,
+    bits_remaining: u8,
+    silence: bool,
+}
+
+impl Dmc {
+    pub(super) fn new(region: Region) -> Self {
+        let rates = match region {
+            Region::Pal => &PAL_RATES,
+            Region::Ntsc | Region::Dendy => &NTSC_RATES,
+        };
+        Dmc {
+            rates,
+            irq_enabled: false,
+            irq: false,
+            looping: false,
+            period: rates[0],
+            timer: 0,
+            level: 0,
+            sample_address: 0xC000,
+            sample_length: 1,
+            current_address: 0xC000,
+            bytes_remaining: 0,
+            sample_buffer: None,
+            shift: 0,
+            bits_remaining: 8,
+            silence: true,
+        }
+    }
+
+    pub(super) fn write(&mut self, register: u16, data: u8) {
+        match register {
+            0 => {
+                self.irq_enabled = data & 0x80 > 0;
+                if !self.irq_enabled {
+                    self.irq = false;
+                }
+                self.looping = data & 0x40 > 0;
+                self.period = self.rates[(data & 0x0F) as usize];
+            }
+            1 => self.level = data & 0x7F,
+            2 => self.sample_address = 0xC000 | ((data as u16) << 6),
+            _ => self.sample_length = ((data as u16) << 4) + 1,
+        }
+    }
+
+    /// From bit 4 of $4015, enabling only restarts the sample if the last one finished
+    pub(super) fn set_enabled(&mut self, enabled: bool) {
+        self.irq = false;
+        if !enabled {
+            self.bytes_remaining = 0;
+        } else if self.bytes_remaining == 0 {
+            self.restart();
+        }
+    }
+
+    fn restart(&mut self) {
+        self.current_address = self.sample_address;
+        self.bytes_remaining = self.sample_length;
+    }
+
+    /// Where the next sample byte has to come from, if the buffer needs one
+    pub(super) fn dma_address(&self) -> Option<u16> {
+        if self.sample_buffer.is_none() && self.bytes_remaining > 0 {
+            Some(self.current_address)
+        } else {
+            None
+        }
+    }
+
+    /// Takes the byte fetched from `dma_address`
+    pub(super) fn fill(&mut self, data: u8) {
+        self.sample_buffer = Some(data);
+        // The address wraps around to $8000, not $0000
+        self.current_address = self.current_address.checked_add(1).unwrap_or(0x8000);
+        self.bytes_remaining -= 1;
+        if self.bytes_remaining == 0 {
+            if self.looping {
+                self.restart();
+            } else if self.irq_enabled {
+                self.irq = true;
+            }
+        }
+    }
+
+    /// Once per cpu cycle
+    pub(super) fn clock(&mut self) {
+        if self.timer > 0 {
+            self.timer -= 1;
+            return;
+        }
+        self.timer = self.period - 1;
+
+        if !self.silence {
+            // The level saturates instead of wrapping
+            if self.shift & 0x01 > 0 {
+                if self.level <= 125 {
+                    self.level += 2;
+                }
+            } else if self.level >= 2 {
+                self.level -= 2;
+            }
+        }
+        self.shift >>= 1;
+        self.bits_remaining -= 1;
+
+        if self.bits_remaining == 0 {
+            self.bits_remaining = 8;
+            match self.sample_buffer.take() {
+                Some(data) => {
+                    self.shift = data;
+                    self.silence = false;
+                }
+                None => self.silence = true,
+            }
+        }
+    }
+
+    pub(super) fn output(&self) -> u8 {
+        self.level
+    }
+}
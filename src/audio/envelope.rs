@@ -0,0 +1,55 @@
+// https://www.nesdev.org/wiki/APU_Envelope
+//
+// Used by both pulses and the noise, either a constant volume or a decay
+// from 15 to 0 that can loop. Shares its loop bit with the length counter's halt.
+//
+// --LC VVVV  L: loop, C: constant volume, V: volume or decay period
+
+#[derive(Default)]
+pub(super) struct Envelope {
+    start: bool,
+    looping: bool,
+    constant: bool,
+    volume: u8,
+    divider: u8,
+    decay: u8,
+}
+
+impl Envelope {
+    pub(super) fn write(&mut self, data: u8) {
+        self.looping = data & 0x20 > 0;
+        self.constant = data & 0x10 > 0;
+        self.volume = data & 0x0F;
+    }
+
+    /// Writing the channel's length register starts the decay over
+    pub(super) fn restart(&mut self) {
+        self.start = true;
+    }
+
+    /// Quarter frame
+    pub(super) fn clock(&mut self) {
+        if self.start {
+            self.start = false;
+            self.decay = 15;
+            self.divider = self.volume;
+        } else if self.divider == 0 {
+            self.divider = self.volume;
+            if self.decay > 0 {
+                self.decay -= 1;
+            } else if self.looping {
+                self.decay = 15;
+            }
+        } else {
+            self.divider -= 1;
+        }
+    }
+
+    pub(super) fn output(&self) -> u8 {
+        if self.constant {
+            self.volume
+        } else {
+            self.decay
+        }
+    }
+}
@@ -0,0 +1,117 @@
+// https://www.nesdev.org/wiki/APU_Frame_Counter
+//
+// $4017  MI-- ----  M: 5 step mode, I: irq inhibit
+//
+// Clocks the envelopes and triangle's linear counter every quarter frame, the
+// length counters and sweeps every half frame. The 4 step mode raises an irq
+// on its last step unless inhibited.
+
+use crate::nes::Region;
+
+// Cpu cycles into the sequence each step lands on, the last one starts it over
+const NTSC_STEPS: [[u32; 6]; 2] = [
+    [7457, 14913, 22371, 29828, 29829, 29830],
+    [7457, 14913, 22371, 29829, 37281, 37282],
+];
+const PAL_STEPS: [[u32; 6]; 2] = [
+    [8313, 16627, 24939, 33252, 33253, 33254],
+    [8313, 16627, 24939, 33253, 41565, 41566],
+];
+
+/// What the frame counter clocked this cycle
+#[derive(Default)]
+pub(super) struct FrameClocks {
+    pub(super) quarter: bool,
+    pub(super) half: bool,
+}
+
+pub(super) struct FrameCounter {
+    steps: &'static [[u32; 6]; 2],
+    five_step: bool,
+    irq_inhibit: bool,
+    pub(super) irq: bool,
+    cycle: u32,
+    step: usize,
+    /// $4017 writes take effect 3 or 4 cycles late
+    pending_write: Option<(u8, u8)>,
+}
+
+impl FrameCounter {
+    pub(super) fn new(region: Region) -> Self {
+        FrameCounter {
+            steps: match region {
+                Region::Pal => &PAL_STEPS,
+                Region::Ntsc | Region::Dendy => &NTSC_STEPS,
+            },
+            five_step: false,
+            irq_inhibit: false,
+            irq: false,
+            cycle: 0,
+            step: 0,
+            pending_write: None,
+        }
+    }
+
+    /// `odd_cycle` is whether the write landed between apu cycles
+    pub(super) fn write(&mut self, data: u8, odd_cycle: bool) {
+        self.irq_inhibit = data & 0x40 > 0;
+        if self.irq_inhibit {
+            self.irq = false;
+        }
+        let delay = if odd_cycle { 4 } else { 3 };
+        self.pending_write = Some((data, delay));
+    }
+
+    pub(super) fn reset(&mut self) {
+        self.irq = false;
+        self.cycle = 0;
+        self.step = 0;
+        self.pending_write = None;
+    }
+
+    /// Once per cpu cycle
+    pub(super) fn clock(&mut self) -> FrameClocks {
+        let mut clocks = FrameClocks::default();
+
+        if let Some((data, delay)) = self.pending_write {
+            if delay > 1 {
+                self.pending_write = Some((data, delay - 1));
+            } else {
+                self.pending_write = None;
+                self.five_step = data & 0x80 > 0;
+                self.cycle = 0;
+                self.step = 0;
+                // Switching to 5 step mode clocks everything straight away
+                if self.five_step {
+                    clocks.quarter = true;
+                    clocks.half = true;
+                }
+                return clocks;
+            }
+        }
+
+        self.cycle += 1;
+        if self.cycle != self.steps[self.five_step as usize][self.step] {
+            return clocks;
+        }
+
+        match self.step {
+            0 | 2 => clocks.quarter = true,
+            1 | 4 => {
+                clocks.quarter = true;
+                clocks.half = true;
+            }
+            _ => {}
+        }
+        if !self.five_step && self.step >= 3 && !self.irq_inhibit {
+            self.irq = true;
+        }
+
+        self.step += 1;
+        if self.step == 6 {
+            self.step = 0;
+            self.cycle = 0;
+        }
+        clocks
+    }
+}
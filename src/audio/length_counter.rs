@@ -0,0 +1,44 @@
+// https://www.nesdev.org/wiki/APU_Length_Counter
+//
+// Silences a channel once it counts down to 0, the top 5 bits of the
+// channel's last register pick the starting value out of the table.
+
+const LENGTH_TABLE: [u8; 32] = [
+    10, 254, 20, 2, 40, 4, 80, 6, 160, 8, 60, 10, 14, 12, 26, 14,
+    12, 16, 24, 18, 48, 20, 96, 22, 192, 24, 72, 26, 16, 28, 32, 30,
+];
+
+#[derive(Default)]
+pub(super) struct LengthCounter {
+    enabled: bool,
+    pub(super) halt: bool,
+    counter: u8,
+}
+
+impl LengthCounter {
+    /// From the channel's bit in $4015, disabling clears the counter right away
+    pub(super) fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.counter = 0;
+        }
+    }
+
+    /// Loads are ignored while the channel is disabled
+    pub(super) fn load(&mut self, data: u8) {
+        if self.enabled {
+            self.counter = LENGTH_TABLE[(data >> 3) as usize];
+        }
+    }
+
+    /// Half frame
+    pub(super) fn clock(&mut self) {
+        if !self.halt && self.counter > 0 {
+            self.counter -= 1;
+        }
+    }
+
+    pub(super) fn active(&self) -> bool {
+        self.counter > 0
+    }
+}
@@ -1 +1,15 @@
 pub mod audio;
+mod blip;
+mod dmc;
+mod envelope;
+mod frame_counter;
+mod length_counter;
+mod noise;
+mod output;
+mod pulse;
+#[cfg(test)]
+mod tests;
+mod triangle;
+
+pub use audio::APU2A03;
+pub use output::AudioOutput;
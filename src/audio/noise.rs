@@ -0,0 +1,85 @@
+// https://www.nesdev.org/wiki/APU_Noise
+//
+// $400C  --LC VVVV  L: length halt / envelope loop, C: constant volume, V: volume
+// $400E  M--- PPPP  M: short mode, P: period index
+// $400F  LLLL L---  L: length counter load
+
+use super::envelope::Envelope;
+use super::length_counter::LengthCounter;
+use crate::nes::Region;
+
+// Periods in cpu cycles
+const NTSC_PERIODS: [u16; 16] = [
+    4, 8, 16, 32, 64, 96, 128, 160, 202, 254, 380, 508, 762, 1016, 2034, 4068,
+];
+const PAL_PERIODS: [u16; 16] = [
+    4, 8, 14, 30, 60, 88, 118, 148, 188, 236, 354, 472, 708, 944, 1890, 3778,
+];
+
+pub(super) struct Noise {
+    pub(super) envelope: Envelope,
+    pub(super) length: LengthCounter,
+    periods: &'static [u16; 16],
+    short_mode: bool,
+    period: u16,
+    timer: u16,
+    shift: u16,
+}
+
+impl Noise {
+    pub(super) fn new(region: Region) -> Self {
+        let periods = match region {
+            Region::Pal => &PAL_PERIODS,
+            Region::Ntsc | Region::Dendy => &NTSC_PERIODS,
+        };
+        Noise {
+            envelope: Envelope::default(),
+            length: LengthCounter::default(),
+            periods,
+            short_mode: false,
+            period: periods[0],
+            timer: 0,
+            // The shift register powers up as 1, all zeroes would never change
+            shift: 1,
+        }
+    }
+
+    pub(super) fn write(&mut self, register: u16, data: u8) {
+        match register {
+            0 => {
+                self.length.halt = data & 0x20 > 0;
+                self.envelope.write(data);
+            }
+            2 => {
+                self.short_mode = data & 0x80 > 0;
+                self.period = self.periods[(data & 0x0F) as usize];
+            }
+            3 => {
+                self.length.load(data);
+                self.envelope.restart();
+            }
+            _ => {}
+        }
+    }
+
+    /// Once per cpu cycle
+    pub(super) fn clock(&mut self) {
+        if self.timer == 0 {
+            self.timer = self.period - 1;
+            // Short mode taps bit 6 instead of bit 1, giving a 93 step loop
+            let tap = if self.short_mode { 6 } else { 1 };
+            let feedback = (self.shift ^ (self.shift >> tap)) & 0x01;
+            self.shift = (self.shift >> 1) | (feedback << 14);
+        } else {
+            self.timer -= 1;
+        }
+    }
+
+    pub(super) fn output(&self) -> u8 {
+        if self.shift & 0x01 > 0 || !self.length.active() {
+            0
+        } else {
+            self.envelope.output()
+        }
+    }
+}
@@ -0,0 +1,95 @@
+// https://www.nesdev.org/wiki/APU_Mixer
+//
+// Takes the mixed level once per cpu cycle and turns it into samples at the
+// output rate. After resampling it goes through the same filters the
+// console's output stage has: two high-passes at 90Hz and 440Hz, then a low-pass at 14kHz.
+
+use super::blip::BlipBuffer;
+use std::f32::consts::PI;
+
+struct HighPass {
+    alpha: f32,
+    last_in: f32,
+    last_out: f32,
+}
+
+impl HighPass {
+    fn new(cutoff: f32, sample_rate: u32) -> Self {
+        let rc = 1.0 / (2.0 * PI * cutoff);
+        let dt = 1.0 / sample_rate as f32;
+        HighPass {
+            alpha: rc / (rc + dt),
+            last_in: 0.0,
+            last_out: 0.0,
+        }
+    }
+
+    fn filter(&mut self, sample: f32) -> f32 {
+        self.last_out = self.alpha * (self.last_out + sample - self.last_in);
+        self.last_in = sample;
+        self.last_out
+    }
+}
+
+struct LowPass {
+    alpha: f32,
+    last_out: f32,
+}
+
+impl LowPass {
+    fn new(cutoff: f32, sample_rate: u32) -> Self {
+        let rc = 1.0 / (2.0 * PI * cutoff);
+        let dt = 1.0 / sample_rate as f32;
+        LowPass {
+            alpha: dt / (rc + dt),
+            last_out: 0.0,
+        }
+    }
+
+    fn filter(&mut self, sample: f32) -> f32 {
+        self.last_out += self.alpha * (sample - self.last_out);
+        self.last_out
+    }
+}
+
+pub struct AudioOutput {
+    blip: BlipBuffer,
+    high_pass: [HighPass; 2],
+    low_pass: LowPass,
+    level: f32,
+    /// Cpu cycles since the frame started
+    clocks: u32,
+}
+
+impl AudioOutput {
+    pub fn new(clock_rate: f64, sample_rate: u32) -> Self {
+        AudioOutput {
+            blip: BlipBuffer::new(clock_rate, sample_rate),
+            high_pass: [HighPass::new(90.0, sample_rate), HighPass::new(440.0, sample_rate)],
+            low_pass: LowPass::new(14000.0, sample_rate),
+            level: 0.0,
+            clocks: 0,
+        }
+    }
+
+    /// Once per cpu cycle with the mixed output level
+    pub fn clock(&mut self, level: f32) {
+        if level != self.level {
+            self.blip.add_delta(self.clocks, level - self.level);
+            self.level = level;
+        }
+        self.clocks += 1;
+    }
+
+    /// Replaces `out` with the filtered samples for everything clocked since the last frame
+    pub fn end_frame(&mut self, out: &mut Vec<f32>) {
+        out.clear();
+        self.blip.end_frame(self.clocks, out);
+        self.clocks = 0;
+        for sample in out.iter_mut() {
+            let mut s = self.high_pass[0].filter(*sample);
+            s = self.high_pass[1].filter(s);
+            *sample = self.low_pass.filter(s);
+        }
+    }
+}
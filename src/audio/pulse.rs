@@ -0,0 +1,120 @@
+// https://www.nesdev.org/wiki/APU_Pulse
+// https://www.nesdev.org/wiki/APU_Sweep
+//
+// $4000/$4004  DDLC VVVV  D: duty, L: length halt / envelope loop, C: constant volume, V: volume
+// $4001/$4005  EPPP NSSS  Sweep  E: enable, P: period, N: negate, S: shift
+// $4002/$4006  Timer low
+// $4003/$4007  LLLL LHHH  L: length counter load, H: timer high
+
+use super::envelope::Envelope;
+use super::length_counter::LengthCounter;
+
+// Read with the step counting down, like the hardware's sequencer
+const DUTY_TABLE: [[u8; 8]; 4] = [
+    [0, 1, 0, 0, 0, 0, 0, 0],
+    [0, 1, 1, 0, 0, 0, 0, 0],
+    [0, 1, 1, 1, 1, 0, 0, 0],
+    [1, 0, 0, 1, 1, 1, 1, 1],
+];
+
+#[derive(Default)]
+struct Sweep {
+    enabled: bool,
+    period: u8,
+    negate: bool,
+    shift: u8,
+    divider: u8,
+    reload: bool,
+}
+
+#[derive(Default)]
+pub(super) struct Pulse {
+    pub(super) envelope: Envelope,
+    pub(super) length: LengthCounter,
+    sweep: Sweep,
+    duty: u8,
+    step: u8,
+    pub(super) period: u16,
+    timer: u16,
+    /// Pulse 1 negates its sweep with one's complement, pulse 2 with two's
+    ones_complement: bool,
+}
+
+impl Pulse {
+    pub(super) fn new(ones_complement: bool) -> Self {
+        Pulse {
+            ones_complement,
+            ..Default::default()
+        }
+    }
+
+    pub(super) fn write(&mut self, register: u16, data: u8) {
+        match register {
+            0 => {
+                self.duty = data >> 6;
+                self.length.halt = data & 0x20 > 0;
+                self.envelope.write(data);
+            }
+            1 => {
+                self.sweep.enabled = data & 0x80 > 0;
+                self.sweep.period = (data >> 4) & 0x07;
+                self.sweep.negate = data & 0x08 > 0;
+                self.sweep.shift = data & 0x07;
+                self.sweep.reload = true;
+            }
+            2 => self.period = (self.period & 0x0700) | data as u16,
+            _ => {
+                self.period = (self.period & 0x00FF) | (((data & 0x07) as u16) << 8);
+                self.length.load(data);
+                self.envelope.restart();
+                self.step = 0;
+            }
+        }
+    }
+
+    /// Once per cpu cycle, the timer counts apu cycles so it reloads with twice the period
+    pub(super) fn clock(&mut self) {
+        if self.timer == 0 {
+            self.timer = self.period * 2 + 1;
+            self.step = self.step.wrapping_sub(1) & 0x07;
+        } else {
+            self.timer -= 1;
+        }
+    }
+
+    /// Half frame
+    pub(super) fn clock_sweep(&mut self) {
+        if self.sweep.divider == 0 && self.sweep.enabled && self.sweep.shift > 0 && !self.muted() {
+            self.period = self.target_period();
+        }
+        if self.sweep.divider == 0 || self.sweep.reload {
+            self.sweep.divider = self.sweep.period;
+            self.sweep.reload = false;
+        } else {
+            self.sweep.divider -= 1;
+        }
+    }
+
+    // The sweep keeps working this out even while it's disabled
+    fn target_period(&self) -> u16 {
+        let change = self.period >> self.sweep.shift;
+        if self.sweep.negate {
+            self.period.wrapping_sub(change + self.ones_complement as u16)
+        } else {
+            self.period + change
+        }
+    }
+
+    // Too high a target mutes the channel whether the sweep is enabled or not
+    fn muted(&self) -> bool {
+        self.period < 8 || (!self.sweep.negate && self.target_period() > 0x07FF)
+    }
+
+    pub(super) fn output(&self) -> u8 {
+        if self.muted() || !self.length.active() || DUTY_TABLE[self.duty as usize][self.step as usize] == 0 {
+            0
+        } else {
+            self.envelope.output()
+        }
+    }
+}
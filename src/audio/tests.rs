@@ -0,0 +1,238 @@
+use super::blip::BlipBuffer;
+use super::dmc::Dmc;
+use super::frame_counter::FrameCounter;
+use super::length_counter::LengthCounter;
+use super::pulse::Pulse;
+use super::APU2A03;
+use crate::nes::Region;
+
+/// Clocks the frame counter `cycles` times, returns the cycles (counting from 1)
+/// that had a quarter frame and the ones that had a half frame
+fn frame_clocks(frame_counter: &mut FrameCounter, cycles: u32) -> (Vec<u32>, Vec<u32>) {
+    let (mut quarters, mut halves) = (Vec::new(), Vec::new());
+    for cycle in 1..=cycles {
+        let clocks = frame_counter.clock();
+        if clocks.quarter {
+            quarters.push(cycle);
+        }
+        if clocks.half {
+            halves.push(cycle);
+        }
+    }
+    (quarters, halves)
+}
+
+#[test]
+fn test_four_step_sequence() {
+    let mut frame_counter = FrameCounter::new(Region::Ntsc);
+    let (quarters, halves) = frame_clocks(&mut frame_counter, 29830 + 7457);
+    // The sequence is 29830 cycles long before it starts over
+    assert_eq!(quarters, [7457, 14913, 22371, 29829, 29830 + 7457]);
+    assert_eq!(halves, [14913, 29829]);
+}
+
+#[test]
+fn test_five_step_sequence() {
+    let mut frame_counter = FrameCounter::new(Region::Ntsc);
+    frame_counter.write(0x80, false);
+    // Switching to 5 step clocks everything once the write lands
+    let (quarters, halves) = frame_clocks(&mut frame_counter, 3);
+    assert_eq!((quarters, halves), (vec![3], vec![3]));
+
+    let (quarters, halves) = frame_clocks(&mut frame_counter, 37282 + 7457);
+    assert_eq!(quarters, [7457, 14913, 22371, 37281, 37282 + 7457]);
+    assert_eq!(halves, [14913, 37281]);
+    assert!(!frame_counter.irq);
+}
+
+#[test]
+fn test_frame_irq() {
+    let mut frame_counter = FrameCounter::new(Region::Ntsc);
+    frame_clocks(&mut frame_counter, 29827);
+    assert!(!frame_counter.irq);
+    // Set on each of the last 3 cycles, acknowledging in between doesn't stick
+    for _ in 29828..=29830 {
+        frame_counter.clock();
+        assert!(frame_counter.irq);
+        frame_counter.irq = false;
+    }
+    frame_counter.clock();
+    assert!(!frame_counter.irq);
+
+    // Setting the inhibit flag clears it and keeps it from coming back
+    let mut frame_counter = FrameCounter::new(Region::Ntsc);
+    frame_clocks(&mut frame_counter, 29830);
+    frame_counter.write(0x40, false);
+    assert!(!frame_counter.irq);
+    frame_clocks(&mut frame_counter, 29830);
+    assert!(!frame_counter.irq);
+}
+
+#[test]
+fn test_frame_counter_write_delay() {
+    // 3 cycles if the write landed on an apu cycle, 4 if it was in between
+    for &(odd_cycle, delay) in [(false, 3), (true, 4)].iter() {
+        let mut frame_counter = FrameCounter::new(Region::Ntsc);
+        frame_clocks(&mut frame_counter, 100);
+        frame_counter.write(0x80, odd_cycle);
+        let (quarters, _) = frame_clocks(&mut frame_counter, delay);
+        assert_eq!(quarters, [delay]);
+        // and the sequence starts over from there
+        let (quarters, _) = frame_clocks(&mut frame_counter, 7457);
+        assert_eq!(quarters, [7457]);
+    }
+}
+
+#[test]
+fn test_length_table() {
+    let table = [
+        10, 254, 20, 2, 40, 4, 80, 6, 160, 8, 60, 10, 14, 12, 26, 14, 12, 16, 24, 18, 48, 20, 96,
+        22, 192, 24, 72, 26, 16, 28, 32, 30,
+    ];
+    for (index, &length) in table.iter().enumerate() {
+        let mut counter = LengthCounter::default();
+        counter.set_enabled(true);
+        counter.load((index as u8) << 3);
+        for _ in 0..length {
+            assert!(counter.active(), "index {} stopped early", index);
+            counter.clock();
+        }
+        assert!(
+            !counter.active(),
+            "index {} didn't stop at {}",
+            index,
+            length
+        );
+    }
+}
+
+#[test]
+fn test_length_counter_enable() {
+    let mut counter = LengthCounter::default();
+    // Loads are ignored while the channel is disabled
+    counter.load(0x08);
+    assert!(!counter.active());
+
+    counter.set_enabled(true);
+    counter.load(0x08);
+    assert!(counter.active());
+    // Halting stops the count
+    counter.halt = true;
+    for _ in 0..300 {
+        counter.clock();
+    }
+    assert!(counter.active());
+    // Disabling clears it right away
+    counter.set_enabled(false);
+    assert!(!counter.active());
+}
+
+#[test]
+fn test_sweep_negate() {
+    // Pulse 1 subtracts the one's complement, one more than pulse 2
+    for &(ones_complement, period) in [(true, 0x17F), (false, 0x180)].iter() {
+        let mut pulse = Pulse::new(ones_complement);
+        pulse.write(2, 0x00);
+        pulse.write(3, 0x02);
+        // Enabled, divider period 0, negate, shift 2
+        pulse.write(1, 0x8A);
+        pulse.clock_sweep();
+        assert_eq!(pulse.period, period);
+    }
+}
+
+#[test]
+fn test_status_read_acknowledges_frame_irq() {
+    let mut apu = APU2A03::new(Region::Ntsc);
+    for _ in 0..29830 {
+        apu.clock();
+    }
+    // A one byte sample with its irq enabled, the irq goes up once it's fetched
+    apu.cpu_write(0x4010, 0x80);
+    apu.cpu_write(0x4013, 0x00);
+    apu.cpu_write(0x4015, 0x10);
+    apu.dmc_dma_fill(0x00);
+    assert!(apu.frame_irq() && apu.dmc_irq());
+
+    // Peeking changes nothing
+    assert_eq!(apu.cpu_read(0x4015, true) & 0xC0, 0xC0);
+    assert!(apu.frame_irq());
+    // Reading only clears the frame irq
+    assert_eq!(apu.cpu_read(0x4015, false) & 0xC0, 0xC0);
+    assert!(!apu.frame_irq() && apu.dmc_irq());
+    assert_eq!(apu.cpu_read(0x4015, false) & 0xC0, 0x80);
+}
+
+/// Clocks the dmc until its buffer needs the next sample byte, returns where
+/// that comes from or None if the sample is over
+fn next_dma_address(dmc: &mut Dmc) -> Option<u16> {
+    // Long enough for the slowest rate to play out a whole byte
+    for _ in 0..428 * 9 {
+        if dmc.dma_address().is_some() {
+            break;
+        }
+        dmc.clock();
+    }
+    dmc.dma_address()
+}
+
+#[test]
+fn test_dmc_address_wrap() {
+    let mut dmc = Dmc::new(Region::Ntsc);
+    dmc.write(0, 0x0F);
+    // 65 bytes from $FFC0
+    dmc.write(2, 0xFF);
+    dmc.write(3, 0x04);
+    dmc.set_enabled(true);
+    for addr in 0xFFC0..=0xFFFF {
+        assert_eq!(next_dma_address(&mut dmc), Some(addr));
+        dmc.fill(0x00);
+    }
+    assert_eq!(next_dma_address(&mut dmc), Some(0x8000));
+}
+
+#[test]
+fn test_dmc_loop_and_irq() {
+    // A one byte sample at $C000 with its irq enabled
+    for &looping in [false, true].iter() {
+        let mut dmc = Dmc::new(Region::Ntsc);
+        dmc.write(0, 0x8F | if looping { 0x40 } else { 0x00 });
+        dmc.write(2, 0x00);
+        dmc.write(3, 0x00);
+        dmc.set_enabled(true);
+        dmc.fill(0x00);
+        if looping {
+            // Looping starts over instead of raising the irq
+            assert!(!dmc.irq);
+            assert_eq!(next_dma_address(&mut dmc), Some(0xC000));
+        } else {
+            assert!(dmc.irq);
+            assert_eq!(next_dma_address(&mut dmc), None);
+        }
+    }
+}
+
+#[test]
+fn test_status_write_acknowledges_dmc_irq() {
+    let mut apu = APU2A03::new(Region::Ntsc);
+    apu.cpu_write(0x4010, 0x80);
+    apu.cpu_write(0x4013, 0x00);
+    apu.cpu_write(0x4015, 0x10);
+    apu.dmc_dma_fill(0x00);
+    assert!(apu.dmc_irq());
+    // Any write to $4015 clears it, enabling the channel again or not
+    apu.cpu_write(0x4015, 0x10);
+    assert!(!apu.dmc_irq());
+}
+
+#[test]
+fn test_blip_step() {
+    // Every phase the step can land on has to add up to exactly the step
+    for time in 0..64 {
+        let mut blip = BlipBuffer::new(1_789_773.0, 48000);
+        blip.add_delta(time, 1.0);
+        let mut out = Vec::new();
+        blip.end_frame(2000, &mut out);
+        assert_eq!(out.last(), Some(&1.0), "step at clock {}", time);
+    }
+}
@@ -0,0 +1,72 @@
+// https://www.nesdev.org/wiki/APU_Triangle
+//
+// $4008  CRRR RRRR  C: length halt / linear counter control, R: linear counter reload
+// $400A  Timer low
+// $400B  LLLL LHHH  L: length counter load, H: timer high
+
+use super::length_counter::LengthCounter;
+
+const SEQUENCE: [u8; 32] = [
+    15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0,
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15,
+];
+
+#[derive(Default)]
+pub(super) struct Triangle {
+    pub(super) length: LengthCounter,
+    control: bool,
+    linear_reload_value: u8,
+    linear_counter: u8,
+    linear_reload: bool,
+    step: u8,
+    period: u16,
+    timer: u16,
+}
+
+impl Triangle {
+    pub(super) fn write(&mut self, register: u16, data: u8) {
+        match register {
+            0 => {
+                self.control = data & 0x80 > 0;
+                self.length.halt = self.control;
+                self.linear_reload_value = data & 0x7F;
+            }
+            2 => self.period = (self.period & 0x0700) | data as u16,
+            3 => {
+                self.period = (self.period & 0x00FF) | (((data & 0x07) as u16) << 8);
+                self.length.load(data);
+                self.linear_reload = true;
+            }
+            _ => {}
+        }
+    }
+
+    /// Once per cpu cycle, unlike the other channels the triangle's timer runs at the full cpu rate
+    pub(super) fn clock(&mut self) {
+        if self.timer == 0 {
+            self.timer = self.period;
+            // The sequencer just stops where it is instead of going silent
+            if self.length.active() && self.linear_counter > 0 {
+                self.step = (self.step + 1) & 0x1F;
+            }
+        } else {
+            self.timer -= 1;
+        }
+    }
+
+    /// Quarter frame
+    pub(super) fn clock_linear(&mut self) {
+        if self.linear_reload {
+            self.linear_counter = self.linear_reload_value;
+        } else if self.linear_counter > 0 {
+            self.linear_counter -= 1;
+        }
+        if !self.control {
+            self.linear_reload = false;
+        }
+    }
+
+    pub(super) fn output(&self) -> u8 {
+        SEQUENCE[self.step as usize]
+    }
+}
@@ -1,13 +1,19 @@
+use crate::audio::APU2A03;
 use crate::cartridge::Cartridge;
+use crate::nes::Region;
 use crate::ppu::PPU;
+use bitflags::bitflags;
+use cpu6502::{BusReader, BusWriter};
 use std::{cell::RefCell, rc::Rc};
 
-pub trait BusReader {
-    fn bus_read(&mut self, addr: u16, read_only: bool) -> u8;
-}
-
-pub trait BusWriter {
-    fn bus_write(&mut self, addr: u16, data: u8);
+bitflags! {
+    /// Everything that can pull the cpu's irq line low. It's an open collector
+    /// line so it stays asserted for as long as any one of them holds it.
+    pub struct IrqSource: u8 {
+        const FRAME_COUNTER = 1 << 0;
+        const DMC = 1 << 1;
+        const MAPPER = 1 << 2;
+    }
 }
 
 pub struct Bus {
@@ -21,25 +27,38 @@ pub struct Bus {
     pub dma_transfer: bool,
     pub dma_dummy: bool,
 
+    /// Cycles the dmc has held the cpu halted for, while it waits to fetch a sample byte
+    pub dmc_halt: Option<u8>,
+    /// Address of the cpu's last bus access if it was a read, the dmc can
+    /// only halt the cpu on a read and the halted cpu keeps repeating it
+    pub cpu_read_addr: Option<u16>,
+
+    irq_sources: IrqSource,
+
     cart: Rc<RefCell<Cartridge>>,
 
     pub ppu: PPU,
+    pub apu: APU2A03,
 }
 
 impl Bus {
-    pub fn new(cart: Rc<RefCell<Cartridge>>) -> Self {
+    pub fn new(cart: Rc<RefCell<Cartridge>>, region: Region) -> Self {
         let ram: [u8; 0x0800] = [0; 0x0800];
-        let ppu: PPU = PPU::new(cart.clone());
+        let ppu: PPU = PPU::new(cart.clone(), region);
         Bus {
             ram,
             cart: cart.clone(),
             ppu,
+            apu: APU2A03::new(region),
             clock_cycle: 0,
             dma_page: 0,
             dma_addr: 0,
             dma_data: 0,
             dma_transfer: false,
             dma_dummy: true,
+            dmc_halt: None,
+            cpu_read_addr: None,
+            irq_sources: IrqSource::empty(),
         }
     }
 
@@ -51,19 +70,35 @@ impl Bus {
         todo!()
     }
 
-    pub(crate) fn cpu_read(&mut self, addr: u16, _read_only: bool) -> u8 {
-        if let Ok(d) = self.cart.borrow().cpu_read(addr) {
+    pub(crate) fn set_irq(&mut self, source: IrqSource, asserted: bool) {
+        self.irq_sources.set(source, asserted);
+    }
+
+    /// State of the cpu's irq line
+    pub(crate) fn irq(&self) -> bool {
+        !self.irq_sources.is_empty()
+    }
+
+    pub(crate) fn cpu_read(&mut self, addr: u16, read_only: bool) -> u8 {
+        let cart_data = if read_only {
+            self.cart.borrow().cpu_peek(addr)
+        } else {
+            self.cart.borrow_mut().cpu_read(addr)
+        };
+        if let Some(d) = cart_data {
             return d;
         } else if (0x0000..=0x1FFF).contains(&addr) {
             return self.ram[(addr & 0x07FF) as usize];
         } else if (0x2000..=0x3FFF).contains(&addr) {
-            return self.ppu.cpu_read(addr & 0x0007, _read_only);
+            return self.ppu.cpu_read(addr & 0x0007, read_only);
+        } else if addr == 0x4015 {
+            return self.apu.cpu_read(addr, read_only);
         }
         0x00
     }
 
     pub(crate) fn cpu_write(&mut self, addr: u16, data: u8) {
-        if let Ok(_) = self.cart.borrow_mut().cpu_write(addr, data) {
+        if self.cart.borrow_mut().cpu_write(addr, data) {
             return;
         }
         if (0x0000..=0x1FFF).contains(&addr) {
@@ -74,7 +109,25 @@ impl Bus {
             self.dma_page = data;
             self.dma_addr = 0x00;
             self.dma_transfer = true;
-        } else if (0x4016..=0x4017).contains(&addr) {
+        } else if addr == 0x4016 {
+        } else if (0x4000..=0x4017).contains(&addr) {
+            self.apu.cpu_write(addr, data);
+        }
+    }
+}
+
+impl BusReader for Bus {
+    fn bus_read(&mut self, addr: u16, read_only: bool) -> u8 {
+        if !read_only {
+            self.cpu_read_addr = Some(addr);
         }
+        self.cpu_read(addr, read_only)
+    }
+}
+
+impl BusWriter for Bus {
+    fn bus_write(&mut self, addr: u16, data: u8) {
+        self.cpu_read_addr = None;
+        self.cpu_write(addr, data);
     }
 }
@@ -0,0 +1,234 @@
+use super::{CartridgeError, MIRROR};
+
+pub(super) const INES_MAGIC: [u8; 4] = [b'N', b'E', b'S', 0x1A];
+
+// https://www.nesdev.org/wiki/INES
+// https://www.nesdev.org/wiki/NES_2.0
+//
+// The raw 16 byte header, the meaning of bytes 8 through 15
+// depends on which format the file is in.
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub(super) struct CartHeadder {
+    pub name: [u8; 4],
+    pub prg_rom_chunks: u8,
+    pub chr_rom_chunks: u8,
+    pub mapper1: u8,
+    pub mapper2: u8,
+    pub prg_ram_size: u8, // NES 2.0: mapper msb / submapper
+    pub tv_system1: u8,   // NES 2.0: prg / chr rom size msb
+    pub tv_system2: u8,   // NES 2.0: prg ram / nvram shift
+    pub unused: [u8; 5],  // NES 2.0: chr ram shift, timing, system type, misc roms, expansion device
+}
+
+impl CartHeadder {
+    pub(super) fn from_bytes(bytes: [u8; 16]) -> Self {
+        CartHeadder {
+            name: [bytes[0], bytes[1], bytes[2], bytes[3]],
+            prg_rom_chunks: bytes[4],
+            chr_rom_chunks: bytes[5],
+            mapper1: bytes[6],
+            mapper2: bytes[7],
+            prg_ram_size: bytes[8],
+            tv_system1: bytes[9],
+            tv_system2: bytes[10],
+            unused: [bytes[11], bytes[12], bytes[13], bytes[14], bytes[15]],
+        }
+    }
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HeaderFormat {
+    INes,
+    Nes2,
+}
+
+/// CPU/PPU timing the rom was made for
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Timing {
+    Ntsc,
+    Pal,
+    MultiRegion,
+    Dendy,
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConsoleType {
+    Nes,
+    /// Holds the Vs. System ppu type and hardware type (byte 13)
+    VsSystem { ppu_type: u8, hardware_type: u8 },
+    Playchoice10,
+    /// Holds the extended console type (byte 13, bits 0-3)
+    Extended(u8),
+}
+
+/// Everything the header tells us about the board, with sizes in bytes.
+/// For iNES 1.0 files the NES 2.0 only fields are filled with sane defaults.
+#[allow(unused)]
+#[derive(Debug, Clone, Copy)]
+pub struct CartInfo {
+    pub format: HeaderFormat,
+    pub mapper_id: u16,
+    pub submapper_id: u8,
+    pub prg_rom_size: usize,
+    pub chr_rom_size: usize,
+    pub prg_ram_size: usize,
+    pub prg_nvram_size: usize,
+    pub chr_ram_size: usize,
+    pub chr_nvram_size: usize,
+    pub mirror: MIRROR,
+    pub four_screen: bool,
+    pub battery: bool,
+    pub trainer: bool,
+    pub timing: Timing,
+    pub console_type: ConsoleType,
+    pub misc_roms: u8,
+    pub expansion_device: u8,
+}
+
+impl CartInfo {
+    pub(super) fn from_header(headder: &CartHeadder) -> Result<CartInfo, CartridgeError> {
+        if headder.name != INES_MAGIC {
+            return Err(CartridgeError::BadMagic(headder.name));
+        }
+
+        // Byte 7 bits 2-3: 00 is iNES as long as bytes 12-15 are clear, 10 is NES 2.0.
+        // Anything else is an old dump with junk like "DiskDude!" from byte 7 on,
+        // only bytes 4-6 can be trusted
+        let format = match (headder.mapper2 >> 2) & 0x03 {
+            0b00 if headder.unused[1..] == [0; 4] => HeaderFormat::INes,
+            0b10 => HeaderFormat::Nes2,
+            _ => {
+                let archaic = CartHeadder {
+                    mapper2: 0,
+                    prg_ram_size: 0,
+                    tv_system1: 0,
+                    tv_system2: 0,
+                    unused: [0; 5],
+                    ..*headder
+                };
+                return CartInfo::from_header(&archaic);
+            }
+        };
+
+        // True for vertical
+        // false for horizontal
+        let mirror = match (headder.mapper1 & 0x01) > 0 {
+            true => MIRROR::VERTICAL,
+            false => MIRROR::HORIZONTAL,
+        };
+        let battery = headder.mapper1 & 0x02 > 0;
+        let trainer = headder.mapper1 & 0x04 > 0;
+        let four_screen = headder.mapper1 & 0x08 > 0;
+        let mapper_lo = ((headder.mapper2 & 0b11110000) | (headder.mapper1 >> 4)) as u16;
+
+        let info = match format {
+            HeaderFormat::INes => {
+                // Byte 8 is the prg ram size in 8k units, 0 infers 8k for compatibility
+                let prg_ram_size = (headder.prg_ram_size.max(1) as usize) * 8192;
+                CartInfo {
+                    format,
+                    mapper_id: mapper_lo,
+                    submapper_id: 0,
+                    prg_rom_size: (headder.prg_rom_chunks as usize) * 16384,
+                    chr_rom_size: (headder.chr_rom_chunks as usize) * 8192,
+                    prg_ram_size: if battery { 0 } else { prg_ram_size },
+                    prg_nvram_size: if battery { prg_ram_size } else { 0 },
+                    chr_ram_size: if headder.chr_rom_chunks == 0 { 8192 } else { 0 },
+                    chr_nvram_size: 0,
+                    mirror,
+                    four_screen,
+                    battery,
+                    trainer,
+                    timing: if headder.tv_system1 & 0x01 > 0 {
+                        Timing::Pal
+                    } else {
+                        Timing::Ntsc
+                    },
+                    console_type: match headder.mapper2 & 0x03 {
+                        1 => ConsoleType::VsSystem {
+                            ppu_type: 0,
+                            hardware_type: 0,
+                        },
+                        2 => ConsoleType::Playchoice10,
+                        _ => ConsoleType::Nes,
+                    },
+                    misc_roms: 0,
+                    expansion_device: 0,
+                }
+            }
+            HeaderFormat::Nes2 => {
+                let [chr_ram_shift, timing, system_type, misc_roms, expansion_device] =
+                    headder.unused;
+                CartInfo {
+                    format,
+                    mapper_id: mapper_lo | (((headder.prg_ram_size & 0x0F) as u16) << 8),
+                    submapper_id: headder.prg_ram_size >> 4,
+                    prg_rom_size: nes2_rom_size(
+                        headder.prg_rom_chunks,
+                        headder.tv_system1 & 0x0F,
+                        16384,
+                    )
+                    .ok_or(CartridgeError::UnsupportedFormat("PRG rom size is too big"))?,
+                    chr_rom_size: nes2_rom_size(
+                        headder.chr_rom_chunks,
+                        headder.tv_system1 >> 4,
+                        8192,
+                    )
+                    .ok_or(CartridgeError::UnsupportedFormat("CHR rom size is too big"))?,
+                    prg_ram_size: nes2_ram_size(headder.tv_system2 & 0x0F),
+                    prg_nvram_size: nes2_ram_size(headder.tv_system2 >> 4),
+                    chr_ram_size: nes2_ram_size(chr_ram_shift & 0x0F),
+                    chr_nvram_size: nes2_ram_size(chr_ram_shift >> 4),
+                    mirror,
+                    four_screen,
+                    battery,
+                    trainer,
+                    timing: match timing & 0x03 {
+                        0 => Timing::Ntsc,
+                        1 => Timing::Pal,
+                        2 => Timing::MultiRegion,
+                        _ => Timing::Dendy,
+                    },
+                    console_type: match headder.mapper2 & 0x03 {
+                        0 => ConsoleType::Nes,
+                        1 => ConsoleType::VsSystem {
+                            ppu_type: system_type & 0x0F,
+                            hardware_type: system_type >> 4,
+                        },
+                        2 => ConsoleType::Playchoice10,
+                        _ => ConsoleType::Extended(system_type & 0x0F),
+                    },
+                    misc_roms: misc_roms & 0x03,
+                    expansion_device: expansion_device & 0x3F,
+                }
+            }
+        };
+        Ok(info)
+    }
+}
+
+/// NES 2.0 rom sizes are either a 12 bit count of `unit` sized banks or,
+/// when the msb nibble is 0xF, an exponent-multiplier pair packed in the lsb.
+/// None if that doesn't fit in a usize.
+fn nes2_rom_size(lsb: u8, msb: u8, unit: usize) -> Option<usize> {
+    if msb == 0x0F {
+        let exponent = (lsb >> 2) as u32;
+        let multiplier = ((lsb & 0x03) as usize) * 2 + 1;
+        2usize.checked_pow(exponent)?.checked_mul(multiplier)
+    } else {
+        Some((((msb as usize) << 8) | lsb as usize) * unit)
+    }
+}
+
+/// NES 2.0 ram sizes are stored as a shift count, 0 means there is none
+fn nes2_ram_size(shift: u8) -> usize {
+    if shift == 0 {
+        0
+    } else {
+        64 << shift
+    }
+}
@@ -0,0 +1,545 @@
+use std::{
+    error,
+    fmt,
+    fs,
+    fs::File, 
+    io, 
+    io::prelude::*, 
+    mem, 
+    path::{Path, PathBuf},
+};
+
+mod header;
+#[cfg(test)]
+mod tests;
+
+pub use header::{CartInfo, Timing};
+use header::CartHeadder;
+use crate::mapper::{
+    MappedAddr, Mapper000, Mapper001, Mapper002, Mapper003, Mapper004, Mapper005, Mapper007,
+    Mapper009, Mapper010, Mapper011, Mapper019, Mapper021, Mapper024, Mapper034, Mapper066,
+    Mapper069, Mapper085, MapperTrait, NametablePage, PpuData, PpuEvent, PpuFetch,
+};
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy)]
+pub enum MIRROR {
+    HORIZONTAL,
+    VERTICAL,
+    OnescreenLo,
+    OnescreenHi,
+    /// The top two nametables are in the console's vram, the bottom two in 2k of vram on the cartridge
+    FourScreen,
+}
+
+/// Everything that can go wrong while turning a rom file into a `Cartridge`
+#[derive(Debug)]
+pub enum CartridgeError {
+    Io(io::Error),
+    /// The file is smaller than the 16 byte header
+    TruncatedHeader,
+    /// The header does not start with "NES\x1A", holds what was found instead
+    BadMagic([u8; 4]),
+    /// The header says there is a trainer but the file ends before all 512 bytes of it
+    TruncatedTrainer,
+    /// The header asks for more PRG rom than the file has
+    TruncatedPrg { expected: usize, found: usize },
+    /// The header asks for more CHR rom than the file has
+    TruncatedChr { expected: usize, found: usize },
+    /// The header is valid but describes something this emulator can't load
+    UnsupportedFormat(&'static str),
+    UnsupportedMapper(u16),
+}
+
+impl fmt::Display for CartridgeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CartridgeError::Io(e) => write!(f, "{}", e),
+            CartridgeError::TruncatedHeader => write!(f, "file is too small to hold an iNES header"),
+            CartridgeError::BadMagic(m) => write!(f, "not an iNES file, bad magic {:02X?}", m),
+            CartridgeError::TruncatedTrainer => write!(f, "trainer is truncated"),
+            CartridgeError::TruncatedPrg { expected, found } => write!(
+                f,
+                "PRG rom is truncated, expected {} bytes but found {}",
+                expected, found
+            ),
+            CartridgeError::TruncatedChr { expected, found } => write!(
+                f,
+                "CHR rom is truncated, expected {} bytes but found {}",
+                expected, found
+            ),
+            CartridgeError::UnsupportedFormat(why) => write!(f, "unsupported rom: {}", why),
+            CartridgeError::UnsupportedMapper(id) => write!(f, "mapper {:03} is not supported", id),
+        }
+    }
+}
+
+impl error::Error for CartridgeError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            CartridgeError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for CartridgeError {
+    fn from(e: io::Error) -> Self {
+        CartridgeError::Io(e)
+    }
+}
+
+/// Pattern memory on the board, boards without CHR rom ship ram instead
+/// and the game uploads its own tiles through $2007
+#[allow(unused)]
+pub enum ChrMemory {
+    Rom(Vec<u8>),
+    Ram(Vec<u8>),
+}
+
+impl ChrMemory {
+    pub fn len(&self) -> usize {
+        match self {
+            ChrMemory::Rom(x) | ChrMemory::Ram(x) => x.len(),
+        }
+    }
+
+    fn read(&self, addr: usize) -> Option<u8> {
+        match self {
+            ChrMemory::Rom(x) | ChrMemory::Ram(x) => x.get(addr).copied(),
+        }
+    }
+
+    /// Returns false if this is rom or the address is out of range
+    fn write(&mut self, addr: usize, data: u8) -> bool {
+        match self {
+            ChrMemory::Rom(_) => false,
+            ChrMemory::Ram(x) => match x.get_mut(addr) {
+                Some(v) => {
+                    *v = data;
+                    true
+                }
+                None => false,
+            },
+        }
+    }
+}
+
+const TRAINER_OFFSET: usize = 0x1000; // $7000 - $6000
+const TRAINER_SIZE: usize = 512;
+
+#[allow(unused)]
+pub struct Cartridge {
+    mapper: Box<dyn MapperTrait>,
+    prg_memory: Vec<u8>,
+    prg_ram: Vec<u8>,
+    prg_ram_dirty: bool,
+    save_file: Option<PathBuf>,
+    chr_memory: ChrMemory,
+    /// Extra nametable memory on the board, the console only has room for 2 nametables
+    nametable_vram: Vec<u8>,
+    mapper_id: u16,
+    pub info: CartInfo,
+}
+
+impl Cartridge {
+    #[cfg(test)]
+    pub fn from(pick: Rom) -> Result<Cartridge, CartridgeError> {
+        let cart_name = match pick {
+            // https://www.nesdev.org/wiki/Emulator_tests
+            Rom::NesTest => "test-roms/cpu/nestest.nes".to_string(),
+            Rom::CpuDummyReads => "test-roms/cpu/cpu_dummy_reads.nes".to_string(),
+            Rom::CpuDummyWritesPpuMem => "test-roms/cpu/cpu_dummy_writes_ppumem.nes".to_string(),
+            Rom::PpuColorTest => "test-roms/ppu/color_test.nes".to_string(),
+            Rom::Mario => "test-roms/carts/super_mario.nes".to_string(),
+            Rom::DonkeyKong => "test-roms/carts/Donkey Kong (World) (Rev A).nes".to_string(),
+        };
+        Cartridge::new(cart_name)
+    }
+
+    pub fn new(file_name: String) -> Result<Self, CartridgeError> {
+        let mut f: File = File::open(&file_name)?;
+        Cartridge::load(&mut f, Path::new(&file_name))
+    }
+
+    /// Reads a rom image from anything, `file_name` is only used to find the .sav next to it
+    pub fn load<R: Read>(f: &mut R, file_name: &Path) -> Result<Self, CartridgeError> {
+        // javid makes a fuckin mistake
+        // how could he not know that the size of [char; x] != [u8; x] in rust
+        // unbelieeeeeeeeeeeeeeeeeevable.
+        let mut headder_bytes = [0; mem::size_of::<CartHeadder>()];
+        if read_up_to(f, &mut headder_bytes)? < headder_bytes.len() {
+            return Err(CartridgeError::TruncatedHeader);
+        }
+        let headder = CartHeadder::from_bytes(headder_bytes);
+
+        let info = CartInfo::from_header(&headder)?;
+
+        // Work ram at $6000-$7FFF, boards with a trainer always get at least 8k
+        // since that's where it has to live.
+        let mut prg_ram: Vec<u8> = vec![0; info.prg_ram_size + info.prg_nvram_size];
+        let mut trainer = [0; TRAINER_SIZE];
+        if info.trainer {
+            if prg_ram.len() < 0x2000 {
+                prg_ram.resize(0x2000, 0);
+            }
+            if read_up_to(f, &mut trainer)? < TRAINER_SIZE {
+                return Err(CartridgeError::TruncatedTrainer);
+            }
+        }
+
+        // Battery backed ram lives in a .sav next to the rom
+        let save_file = if info.battery {
+            let save_file = file_name.with_extension("sav");
+            let nvram = info.prg_ram_size..info.prg_ram_size + info.prg_nvram_size;
+            load_save_file(&save_file, &mut prg_ram[nvram]);
+            Some(save_file)
+        } else {
+            None
+        };
+
+        // The trainer is loaded at $7000-$71FF before the game starts, on top of the save
+        if info.trainer {
+            prg_ram[TRAINER_OFFSET..TRAINER_OFFSET + TRAINER_SIZE].copy_from_slice(&trainer);
+        }
+
+        let mapper_id = info.mapper_id;
+        let (mirror, nametable_vram) = if info.four_screen {
+            (MIRROR::FourScreen, vec![0; 0x800])
+        } else {
+            (info.mirror, vec![])
+        };
+
+        let prg_memory = read_rom(f, info.prg_rom_size)?;
+        if prg_memory.len() < info.prg_rom_size {
+            return Err(CartridgeError::TruncatedPrg {
+                expected: info.prg_rom_size,
+                found: prg_memory.len(),
+            });
+        }
+
+        let chr_memory = if info.chr_rom_size == 0 {
+            // Nothing to read, the game fills this in itself
+            let size = (info.chr_ram_size + info.chr_nvram_size).max(8192);
+            ChrMemory::Ram(vec![0; size])
+        } else {
+            let chr_rom = read_rom(f, info.chr_rom_size)?;
+            if chr_rom.len() < info.chr_rom_size {
+                return Err(CartridgeError::TruncatedChr {
+                    expected: info.chr_rom_size,
+                    found: chr_rom.len(),
+                });
+            }
+            ChrMemory::Rom(chr_rom)
+        };
+
+        let mapper: Box<dyn MapperTrait> = match mapper_id {
+            0 => Box::new(Mapper000::new(prg_memory.len(), prg_ram.len(), mirror)),
+            1 => Box::new(Mapper001::new(prg_memory.len(), chr_memory.len(), prg_ram.len())),
+            // Submapper 1 means no bus conflicts, 2 means there are
+            2 => Box::new(Mapper002::new(prg_memory.len(), mirror, info.submapper_id != 1)),
+            3 => Box::new(Mapper003::new(
+                prg_memory.len(),
+                chr_memory.len(),
+                mirror,
+                info.submapper_id != 1,
+            )),
+            4 => Box::new(Mapper004::new(
+                prg_memory.len(),
+                chr_memory.len(),
+                prg_ram.len(),
+                info.four_screen,
+                mirror,
+            )),
+            5 => Box::new(Mapper005::new(prg_memory.len(), chr_memory.len(), prg_ram.len())),
+            // Most AxROM boards are AOROM which has no conflicts, only trust the submapper
+            7 => Box::new(Mapper007::new(prg_memory.len(), info.submapper_id == 2)),
+            9 => Box::new(Mapper009::new(prg_memory.len(), chr_memory.len())),
+            10 => Box::new(Mapper010::new(prg_memory.len(), chr_memory.len(), prg_ram.len())),
+            11 => Box::new(Mapper011::new(prg_memory.len(), chr_memory.len(), mirror)),
+            19 => Box::new(Mapper019::new(prg_memory.len(), chr_memory.len(), prg_ram.len())),
+            21 | 22 | 23 | 25 => Box::new(Mapper021::new(
+                mapper_id,
+                info.submapper_id,
+                prg_memory.len(),
+                chr_memory.len(),
+                prg_ram.len(),
+            )),
+            24 | 26 => Box::new(Mapper024::new(
+                prg_memory.len(),
+                chr_memory.len(),
+                prg_ram.len(),
+                mapper_id == 26,
+            )),
+            34 => Box::new(Mapper034::new(
+                info.submapper_id,
+                prg_memory.len(),
+                chr_memory.len(),
+                prg_ram.len(),
+                mirror,
+            )),
+            66 => Box::new(Mapper066::new(prg_memory.len(), chr_memory.len(), mirror)),
+            69 => Box::new(Mapper069::new(prg_memory.len(), chr_memory.len(), prg_ram.len())),
+            85 => Box::new(Mapper085::new(prg_memory.len(), chr_memory.len(), prg_ram.len())),
+            x => return Err(CartridgeError::UnsupportedMapper(x)),
+        };
+
+        let cart = Cartridge {
+            mapper,
+            prg_memory,
+            prg_ram,
+            prg_ram_dirty: false,
+            save_file,
+            chr_memory,
+            nametable_vram,
+            mapper_id,
+            info,
+        };
+        Ok(cart)
+    }
+
+    fn get_mapper(&self) -> &dyn MapperTrait {
+        &*self.mapper
+    }
+
+    // None means the cartridge does not respond to this address
+    pub fn cpu_read(&mut self, addr: u16) -> Option<u8> {
+        let mapped = self.mapper.cpu_map_read(addr).or_else(|| self.trainer_ram(addr))?;
+        self.read_mapped(mapped)
+    }
+
+    /// Same as `cpu_read` minus the side effects, for anything that isn't the cpu
+    pub fn cpu_peek(&self, addr: u16) -> Option<u8> {
+        let mapped = self.get_mapper().cpu_map_peek(addr).or_else(|| self.trainer_ram(addr))?;
+        self.read_mapped(mapped)
+    }
+
+    // Boards like UxROM never put anything at $6000, the ram a trainer
+    // forced gets mapped there anyway so the trainer can be reached
+    fn trainer_ram(&self, addr: u16) -> Option<MappedAddr> {
+        match addr {
+            0x6000..=0x7FFF if self.info.trainer => {
+                Some(MappedAddr::PrgRam((addr & 0x1FFF) as u32))
+            }
+            _ => None,
+        }
+    }
+
+    fn read_mapped(&self, mapped: MappedAddr) -> Option<u8> {
+        match mapped {
+            MappedAddr::PrgRom(a) => self.prg_memory.get(a as usize).copied(),
+            MappedAddr::PrgRam(a) => self.prg_ram.get(a as usize).copied(),
+            MappedAddr::Data(x) => Some(x),
+            MappedAddr::Handled => None,
+        }
+    }
+
+    // Returns true if the cartridge took the write
+    pub fn cpu_write(&mut self, addr: u16, data: u8) -> bool {
+        let data = if self.get_mapper().bus_conflicts() {
+            match self.get_mapper().cpu_map_peek(addr) {
+                Some(MappedAddr::PrgRom(a)) => {
+                    data & self.prg_memory.get(a as usize).copied().unwrap_or(0xFF)
+                }
+                _ => data,
+            }
+        } else {
+            data
+        };
+        match self.mapper.cpu_map_write(addr, data).or_else(|| self.trainer_ram(addr)) {
+            // Rom, nothing to write to
+            Some(MappedAddr::PrgRom(_)) => true,
+            Some(MappedAddr::Handled) | Some(MappedAddr::Data(_)) => true,
+            Some(MappedAddr::PrgRam(a)) => {
+                if let Some(x) = self.prg_ram.get_mut(a as usize) {
+                    *x = data;
+                    self.prg_ram_dirty = true;
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Which vram page a nametable address goes to, boards like MMC1 change it at runtime
+    pub fn nametable_page(&self, addr: u16) -> NametablePage {
+        self.get_mapper().nametable_page(addr)
+    }
+
+    pub fn nametable_read(&self, addr: u16) -> u8 {
+        self.get_mapper().nametable_read(addr)
+    }
+
+    pub fn nametable_write(&mut self, addr: u16, data: u8) {
+        self.mapper.nametable_write(addr, data)
+    }
+
+    /// Reads from one of the 1k pages of nametable vram on the board
+    pub fn vram_read(&self, page: usize, addr: u16) -> u8 {
+        let index = page * 0x0400 + (addr & 0x03FF) as usize;
+        self.nametable_vram.get(index).copied().unwrap_or(0)
+    }
+
+    pub fn vram_write(&mut self, page: usize, addr: u16, data: u8) {
+        let index = page * 0x0400 + (addr & 0x03FF) as usize;
+        if let Some(x) = self.nametable_vram.get_mut(index) {
+            *x = data;
+        }
+    }
+
+    /// Lets the mapper see every address the ppu puts on its bus, called once the read is done
+    pub fn ppu_address(&mut self, addr: u16) {
+        self.mapper.ppu_address(addr)
+    }
+
+    /// A fetch made while rendering, None means nothing special happened
+    /// and the ppu should carry on with a normal read
+    pub fn ppu_fetch(&mut self, addr: u16, kind: PpuFetch) -> Option<u8> {
+        match self.mapper.ppu_fetch(addr, kind)? {
+            PpuData::Chr(a) => self.chr_memory.read(a as usize),
+            PpuData::Value(x) => Some(x),
+        }
+    }
+
+    pub fn ppu_event(&mut self, event: PpuEvent) {
+        self.mapper.ppu_event(event)
+    }
+
+    pub fn cpu_clock(&mut self) {
+        self.mapper.cpu_clock()
+    }
+
+    /// True while the cartridge is asserting irq
+    pub fn irq_state(&self) -> bool {
+        self.get_mapper().irq_state()
+    }
+
+    pub fn audio_output(&self) -> f32 {
+        self.get_mapper().audio_output()
+    }
+
+    /// The battery backed part of prg ram, NES 2.0 boards can have volatile ram in front of it
+    fn prg_nvram(&self) -> &[u8] {
+        let start = self.info.prg_ram_size;
+        &self.prg_ram[start..start + self.info.prg_nvram_size]
+    }
+
+    /// Writes battery backed ram out to the .sav file if anything changed since the last flush
+    pub fn flush_save(&mut self) -> io::Result<()> {
+        if let Some(save_file) = &self.save_file {
+            if self.prg_ram_dirty {
+                fs::write(save_file, self.prg_nvram())?;
+                self.prg_ram_dirty = false;
+            }
+        }
+        Ok(())
+    }
+
+    /// The board's half of a save state, the mapper's registers and every
+    /// ram on the cartridge. Rom never changes so it's left out.
+    #[allow(unused)]
+    pub fn save_state(&self) -> Vec<u8> {
+        let mapper_state = self.get_mapper().save_state();
+        let mut state = Vec::new();
+        state.extend_from_slice(&self.mapper_id.to_le_bytes());
+        state.extend_from_slice(&(mapper_state.len() as u32).to_le_bytes());
+        state.extend_from_slice(&mapper_state);
+        state.extend_from_slice(&self.prg_ram);
+        if let ChrMemory::Ram(chr_ram) = &self.chr_memory {
+            state.extend_from_slice(chr_ram);
+        }
+        state.extend_from_slice(&self.nametable_vram);
+        state
+    }
+
+    /// Returns false and leaves everything alone if `state` came from a different board
+    #[allow(unused)]
+    pub fn load_state(&mut self, state: &[u8]) -> bool {
+        if state.len() < 6 || state[0..2] != self.mapper_id.to_le_bytes() {
+            return false;
+        }
+        let mapper_len = u32::from_le_bytes([state[2], state[3], state[4], state[5]]) as usize;
+        let chr_ram_len = match &self.chr_memory {
+            ChrMemory::Ram(chr_ram) => chr_ram.len(),
+            ChrMemory::Rom(_) => 0,
+        };
+        let ram_len = self.prg_ram.len() + chr_ram_len + self.nametable_vram.len();
+        if state.len() != 6 + mapper_len + ram_len {
+            return false;
+        }
+
+        let (mapper_state, rest) = state[6..].split_at(mapper_len);
+        if !self.mapper.load_state(mapper_state) {
+            return false;
+        }
+        let (prg_ram, rest) = rest.split_at(self.prg_ram.len());
+        self.prg_ram.copy_from_slice(prg_ram);
+        self.prg_ram_dirty = true;
+        let (chr_ram, nametable_vram) = rest.split_at(chr_ram_len);
+        if let ChrMemory::Ram(x) = &mut self.chr_memory {
+            x.copy_from_slice(chr_ram);
+        }
+        self.nametable_vram.copy_from_slice(nametable_vram);
+        true
+    }
+
+    pub fn ppu_read(&self, addr: u16) -> Option<u8> {
+        let mapped_addr = self.get_mapper().ppu_map_read(addr)?;
+        self.chr_memory.read(mapped_addr as usize)
+    }
+
+    // Returns true if the cartridge took the write, writes to chr rom
+    // are swallowed since the ppu has nowhere else to put them
+    pub fn ppu_write(&mut self, addr: u16, data: u8) -> bool {
+        if let Some(mapped_addr) = self.get_mapper().ppu_map_write(addr) {
+            self.chr_memory.write(mapped_addr as usize, data);
+            return true;
+        }
+        false
+    }
+}
+
+/// A missing save just means a new game, anything else is reported but
+/// not fatal since the game can still be played.
+fn load_save_file(save_file: &Path, prg_ram: &mut [u8]) {
+    match fs::read(save_file) {
+        Ok(data) => {
+            let len = data.len().min(prg_ram.len());
+            prg_ram[..len].copy_from_slice(&data[..len]);
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => eprintln!("Could not read save file {:?}: {}", save_file, e),
+    }
+}
+
+/// Like read_exact but hands back how much was read instead of failing on a short file
+fn read_up_to<R: Read>(f: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut total = 0;
+    while total < buf.len() {
+        match f.read(&mut buf[total..]) {
+            Ok(0) => break,
+            Ok(n) => total += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(total)
+}
+
+/// Reads up to `size` bytes of rom. Only what's actually in the file gets
+/// allocated, a broken NES 2.0 header can ask for terabytes.
+fn read_rom<R: Read>(f: &mut R, size: usize) -> io::Result<Vec<u8>> {
+    let mut rom = Vec::new();
+    f.take(size as u64).read_to_end(&mut rom)?;
+    Ok(rom)
+}
+
+#[allow(unused)]
+pub enum Rom {
+    NesTest,
+    CpuDummyReads,
+    CpuDummyWritesPpuMem,
+    PpuColorTest,
+    Mario,
+    DonkeyKong,
+}
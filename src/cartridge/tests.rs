@@ -0,0 +1,1191 @@
+use super::header::{CartHeadder, HeaderFormat};
+use super::{CartInfo, Cartridge, CartridgeError, Timing};
+use crate::mapper::{NametablePage, PpuEvent};
+use crate::nes::Region;
+use std::{env, fs, path::Path};
+
+/// An iNES header followed by `body` bytes of filler
+fn image(prg_chunks: u8, chr_chunks: u8, flags6: u8, flags7: u8, body: usize) -> Vec<u8> {
+    let mut image = vec![
+        b'N', b'E', b'S', 0x1A, prg_chunks, chr_chunks, flags6, flags7,
+    ];
+    image.resize(16, 0);
+    image.resize(16 + body, 0xEA);
+    image
+}
+
+fn load(image: &[u8]) -> Result<Cartridge, CartridgeError> {
+    Cartridge::load(&mut &image[..], Path::new("test.nes"))
+}
+
+fn load_ok(image: &[u8]) -> Cartridge {
+    match load(image) {
+        Ok(cart) => cart,
+        Err(e) => panic!("the rom didn't load: {}", e),
+    }
+}
+
+fn load_error(image: &[u8]) -> CartridgeError {
+    match load(image) {
+        Ok(_) => panic!("the rom loaded"),
+        Err(e) => e,
+    }
+}
+
+#[test]
+fn test_truncated_header() {
+    let error = load_error(&[b'N', b'E', b'S', 0x1A, 0x01]);
+    assert!(
+        matches!(error, CartridgeError::TruncatedHeader),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn test_bad_magic() {
+    let mut image = image(1, 1, 0, 0, 0x6000);
+    image[3] = 0x00;
+    let error = load_error(&image);
+    assert!(
+        matches!(error, CartridgeError::BadMagic([b'N', b'E', b'S', 0x00])),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn test_truncated_trainer() {
+    let error = load_error(&image(1, 1, 0x04, 0, 100));
+    assert!(
+        matches!(error, CartridgeError::TruncatedTrainer),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn test_truncated_prg() {
+    let error = load_error(&image(2, 1, 0, 0, 0x4000));
+    assert!(
+        matches!(
+            error,
+            CartridgeError::TruncatedPrg {
+                expected: 0x8000,
+                found: 0x4000
+            }
+        ),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn test_truncated_chr() {
+    let error = load_error(&image(1, 1, 0, 0, 0x4000 + 100));
+    assert!(
+        matches!(
+            error,
+            CartridgeError::TruncatedChr {
+                expected: 0x2000,
+                found: 100
+            }
+        ),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn test_unsupported_mapper() {
+    let error = load_error(&image(1, 1, 0xF0, 0xF0, 0x6000));
+    assert!(
+        matches!(error, CartridgeError::UnsupportedMapper(255)),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn test_rom_size_overflow() {
+    // NES 2.0 exponent-multiplier sizes, 2^63 * 7 doesn't fit in a usize
+    let mut image = image(0xFF, 1, 0, 0x08, 0x6000);
+    image[9] = 0x0F;
+    let error = load_error(&image);
+    assert!(
+        matches!(error, CartridgeError::UnsupportedFormat(_)),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn test_huge_rom_size() {
+    // 2^63 and 2^40 bytes, neither gets allocated before the file runs out
+    for &(prg_size, expected) in [(0xFC, 1 << 63), (0xA0, 1 << 40)].iter() {
+        let mut image = image(prg_size, 1, 0, 0x08, 256);
+        image[9] = 0x0F;
+        let error = load_error(&image);
+        assert!(
+            matches!(error, CartridgeError::TruncatedPrg { expected: e, found: 256 } if e == expected),
+            "{}",
+            error
+        );
+    }
+}
+
+#[test]
+fn test_archaic_ines() {
+    // Bytes 7-15 of old dumps were often tagged by the tool that made them
+    let mut disk_dude = image(1, 1, 0x11, 0, 0x6000);
+    disk_dude[7..16].copy_from_slice(b"DiskDude!");
+    let cart = load_ok(&disk_dude);
+
+    assert_eq!(cart.info.format, HeaderFormat::INes);
+    assert_eq!(cart.info.mapper_id, 1);
+    assert_eq!(cart.info.prg_ram_size, 0x2000);
+
+    // A tag that leaves byte 7 looking like iNES still gets caught by bytes 12-15
+    let mut tagged = image(1, 1, 0x11, 0x40, 0x6000);
+    tagged[12..16].copy_from_slice(b"Dude");
+    let cart = load_ok(&tagged);
+    assert_eq!(cart.info.mapper_id, 1);
+}
+
+/// A NES 2.0 header with `bytes` (index, value) patched in
+fn nes2_info(bytes: &[(usize, u8)]) -> CartInfo {
+    let mut headder = [
+        b'N', b'E', b'S', 0x1A, 0, 0, 0, 0x08, 0, 0, 0, 0, 0, 0, 0, 0,
+    ];
+    for &(i, value) in bytes {
+        headder[i] = value;
+    }
+    match CartInfo::from_header(&CartHeadder::from_bytes(headder)) {
+        Ok(info) => info,
+        Err(e) => panic!("{}", e),
+    }
+}
+
+#[test]
+fn test_nes2_rom_sizes() {
+    // (byte 4, byte 5, byte 9) => (prg rom, chr rom)
+    let cases = [
+        ((0x02, 0x01, 0x00), (0x8000, 0x2000)),
+        // The msb nibbles make a 12 bit bank count
+        ((0x02, 0x01, 0x21), (0x102 * 0x4000, 0x201 * 0x2000)),
+        // A 0xF msb nibble means 2^E * (M * 2 + 1), EEEE EEMM in the lsb
+        ((0x15, 0x00, 0x0F), (96, 0)),
+        ((20 << 2, 0x00, 0x0F), (0x100000, 0)),
+        ((0x01, (13 << 2) | 0x01, 0xF0), (0x4000, 0x6000)),
+        ((0x01, (10 << 2) | 0x03, 0xF0), (0x4000, 7 * 1024)),
+    ];
+    for &((prg, chr, msb), expected) in cases.iter() {
+        let info = nes2_info(&[(4, prg), (5, chr), (9, msb)]);
+        assert_eq!(
+            (info.prg_rom_size, info.chr_rom_size),
+            expected,
+            "byte 9 {:02X}",
+            msb
+        );
+    }
+}
+
+#[test]
+fn test_nes2_ram_sizes() {
+    // (byte 10, byte 11) => (prg ram, prg nvram, chr ram, chr nvram), 64 << shift or 0
+    let cases = [
+        ((0x00, 0x00), (0, 0, 0, 0)),
+        ((0x07, 0x00), (0x2000, 0, 0, 0)),
+        ((0x90, 0x00), (0, 0x8000, 0, 0)),
+        ((0x97, 0x00), (0x2000, 0x8000, 0, 0)),
+        ((0x00, 0x07), (0, 0, 0x2000, 0)),
+        ((0x00, 0x81), (0, 0, 0x80, 0x4000)),
+    ];
+    for &((prg, chr), expected) in cases.iter() {
+        let info = nes2_info(&[(10, prg), (11, chr)]);
+        let sizes = (
+            info.prg_ram_size,
+            info.prg_nvram_size,
+            info.chr_ram_size,
+            info.chr_nvram_size,
+        );
+        assert_eq!(sizes, expected, "bytes 10/11 {:02X} {:02X}", prg, chr);
+    }
+}
+
+#[test]
+fn test_nes2_timing() {
+    let cases = [
+        (0x00, Timing::Ntsc, Region::Ntsc),
+        (0x01, Timing::Pal, Region::Pal),
+        // Multi region carts get run as ntsc
+        (0x02, Timing::MultiRegion, Region::Ntsc),
+        (0x03, Timing::Dendy, Region::Dendy),
+        // Only the low 2 bits count
+        (0xFD, Timing::Pal, Region::Pal),
+    ];
+    for &(byte, timing, region) in cases.iter() {
+        let info = nes2_info(&[(12, byte)]);
+        assert_eq!(info.timing, timing, "byte 12 {:02X}", byte);
+        assert_eq!(Region::from(info.timing), region, "byte 12 {:02X}", byte);
+    }
+}
+
+#[test]
+fn test_nes2_mapper_id() {
+    // (byte 6, byte 7, byte 8) => (mapper, submapper)
+    let cases = [
+        ((0x10, 0x08, 0x00), (1, 0)),
+        ((0x40, 0x08, 0x10), (4, 1)),
+        ((0xF0, 0xF8, 0x00), (255, 0)),
+        // Byte 8's low nibble is bits 8-11, the high one the submapper
+        ((0x50, 0x48, 0x31), (0x145, 3)),
+        ((0xF0, 0xF8, 0xEF), (0xFFF, 14)),
+    ];
+    for &((flags6, flags7, byte8), expected) in cases.iter() {
+        let info = nes2_info(&[(6, flags6), (7, flags7), (8, byte8)]);
+        assert_eq!(info.format, HeaderFormat::Nes2);
+        assert_eq!(
+            (info.mapper_id, info.submapper_id),
+            expected,
+            "byte 8 {:02X}",
+            byte8
+        );
+    }
+}
+
+#[test]
+fn test_trainer_at_7000() {
+    let mut image = image(1, 1, 0x04, 0, 0);
+    image.extend((0..512).map(|i| i as u8 ^ 0xA5));
+    image.resize(image.len() + 0x6000, 0xEA);
+    let cart = load_ok(&image);
+
+    for i in 0..512 {
+        assert_eq!(
+            cart.cpu_peek(0x7000 + i),
+            Some(i as u8 ^ 0xA5),
+            "trainer byte {}",
+            i
+        );
+    }
+    // The rom starts after the trainer
+    assert_eq!(cart.cpu_peek(0x8000), Some(0xEA));
+}
+
+#[test]
+fn test_trainer_without_ram() {
+    // UxROM has nothing at $6000, the trainer still has to show up there
+    let mut image = image(2, 1, 0x24, 0, 0);
+    image.extend((0..512).map(|i| i as u8 ^ 0xA5));
+    image.resize(image.len() + 0xA000, 0xEA);
+    let mut cart = load_ok(&image);
+
+    assert_eq!(cart.cpu_peek(0x7000), Some(0xA5));
+    assert_eq!(cart.cpu_peek(0x71FF), Some(0xFF ^ 0xA5));
+    cart.cpu_write(0x6000, 0x42);
+    assert_eq!(cart.cpu_read(0x6000), Some(0x42));
+}
+
+#[test]
+fn test_trainer_over_save_file() {
+    let dir = env::temp_dir().join(format!("nes-rs-trainer-test-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let rom = dir.join("game.nes");
+    // Battery backed mapper 0 with a trainer, and a save from an earlier run
+    let mut image = image(1, 1, 0x06, 0, 0);
+    image.extend((0..512).map(|i| i as u8 ^ 0xA5));
+    image.resize(image.len() + 0x6000, 0xEA);
+    fs::write(&rom, image).unwrap();
+    fs::write(dir.join("game.sav"), vec![0x11; 0x2000]).unwrap();
+
+    let cart = Cartridge::new(rom.to_string_lossy().to_string());
+    fs::remove_dir_all(&dir).unwrap();
+    let cart = cart.unwrap_or_else(|e| panic!("{}", e));
+    assert_eq!(cart.cpu_peek(0x6FFF), Some(0x11));
+    for i in 0..512 {
+        assert_eq!(
+            cart.cpu_peek(0x7000 + i),
+            Some(i as u8 ^ 0xA5),
+            "trainer byte {}",
+            i
+        );
+    }
+    assert_eq!(cart.cpu_peek(0x7200), Some(0x11));
+}
+
+#[test]
+fn test_save_file_round_trip() {
+    let dir = env::temp_dir().join(format!("nes-rs-save-test-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let rom = dir.join("game.nes");
+    // Battery backed mapper 0
+    fs::write(&rom, image(1, 1, 0x02, 0, 0x6000)).unwrap();
+    let rom = rom.to_string_lossy().to_string();
+
+    let mut cart = Cartridge::new(rom.clone()).unwrap_or_else(|e| panic!("{}", e));
+    // Nothing written yet, nothing to save
+    cart.flush_save().unwrap();
+    assert!(!dir.join("game.sav").exists());
+
+    for i in 0..0x2000 {
+        cart.cpu_write(0x6000 + i, (i * 7) as u8);
+    }
+    cart.flush_save().unwrap();
+    let saved = fs::read(dir.join("game.sav")).unwrap();
+
+    let cart = Cartridge::new(rom).unwrap_or_else(|e| panic!("{}", e));
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(saved.len(), 0x2000);
+    for i in 0..0x2000 {
+        assert_eq!(saved[i as usize], (i * 7) as u8);
+        assert_eq!(
+            cart.cpu_peek(0x6000 + i),
+            Some((i * 7) as u8),
+            "prg ram byte {}",
+            i
+        );
+    }
+}
+
+/// A rom where every 8k of prg and 1k of chr is filled with its bank number
+fn banked_image(mapper: u8, prg_chunks: u8, chr_chunks: u8) -> Vec<u8> {
+    let mut image = image(prg_chunks, chr_chunks, mapper << 4, mapper & 0xF0, 0);
+    image.extend((0..prg_chunks as usize * 0x4000).map(|i| (i / 0x2000) as u8));
+    image.extend((0..chr_chunks as usize * 0x2000).map(|i| (i / 0x0400) as u8));
+    image
+}
+
+/// What shows up in every 1k of $6000-$FFFF and the pattern tables, and where the nametables go
+fn bank_mapping(cart: &Cartridge) -> (Vec<Option<u8>>, Vec<Option<u8>>, Vec<NametablePage>) {
+    let cpu = (0x6000..=0xFFFF)
+        .step_by(0x0400)
+        .map(|a| cart.cpu_peek(a))
+        .collect();
+    let ppu = (0x0000..0x2000)
+        .step_by(0x0400)
+        .map(|a| cart.ppu_read(a))
+        .collect();
+    let nametables = (0x2000..0x3000)
+        .step_by(0x0400)
+        .map(|a| cart.nametable_page(a))
+        .collect();
+    (cpu, ppu, nametables)
+}
+
+fn check_state_round_trip(image: &[u8], setup: &[(u16, u8)], mutate: &[(u16, u8)]) {
+    let mut cart = load_ok(image);
+    for &(addr, data) in setup {
+        cart.cpu_write(addr, data);
+    }
+    let mapping = bank_mapping(&cart);
+    let state = cart.save_state();
+
+    for &(addr, data) in mutate {
+        cart.cpu_write(addr, data);
+    }
+    assert_ne!(
+        bank_mapping(&cart),
+        mapping,
+        "mutating didn't change anything"
+    );
+    assert!(cart.load_state(&state));
+    assert_eq!(bank_mapping(&cart), mapping);
+}
+
+// Mmc1 registers are written a bit at a time, lsb first
+fn mmc1_writes(writes: &[(u16, u8)]) -> Vec<(u16, u8)> {
+    let mut serial = Vec::new();
+    for &(addr, data) in writes {
+        serial.extend((0..5).map(|bit| (addr, (data >> bit) & 0x01)));
+    }
+    serial
+}
+
+#[test]
+fn test_mmc1_state_round_trip() {
+    let mut setup = mmc1_writes(&[(0x8000, 0x1E), (0xA000, 3), (0xC000, 5), (0xE000, 2)]);
+    setup.push((0x6000, 0x42));
+    let mut mutate = mmc1_writes(&[(0x8000, 0x0F), (0xA000, 1), (0xE000, 4)]);
+    mutate.push((0x6000, 0x99));
+    check_state_round_trip(&banked_image(1, 8, 16), &setup, &mutate);
+}
+
+#[test]
+fn test_mmc3_state_round_trip() {
+    let mut setup = vec![(0xA000, 0x01), (0x6000, 0x42)];
+    for (r, &bank) in [2, 6, 9, 10, 11, 12, 3, 5].iter().enumerate() {
+        setup.extend_from_slice(&[(0x8000, r as u8), (0x8001, bank)]);
+    }
+    let mutate = [(0x8000, 0xC6), (0x8001, 9), (0xA000, 0x00), (0x6000, 0x99)];
+    check_state_round_trip(&banked_image(4, 8, 16), &setup, &mutate);
+}
+
+#[test]
+fn test_vrc6_state_round_trip() {
+    let mut setup = vec![(0xB003, 0x84), (0x8000, 3), (0xC000, 9), (0x6000, 0x42)];
+    let chr_registers = [
+        0xD000, 0xD001, 0xD002, 0xD003, 0xE000, 0xE001, 0xE002, 0xE003,
+    ];
+    for (i, &addr) in chr_registers.iter().enumerate() {
+        setup.push((addr, 0x10 + i as u8));
+    }
+    let mutate = [(0x8000, 1), (0xD001, 0x20), (0xB003, 0x88), (0x6000, 0x99)];
+    check_state_round_trip(&banked_image(24, 8, 16), &setup, &mutate);
+}
+
+#[test]
+fn test_state_from_another_board() {
+    let mmc1 = load_ok(&banked_image(1, 8, 16));
+    let mut mmc3 = load_ok(&banked_image(4, 8, 16));
+    assert!(!mmc3.load_state(&mmc1.save_state()));
+    // Right board, wrong sized ram
+    let mut state = mmc3.save_state();
+    state.pop();
+    assert!(!mmc3.load_state(&state));
+}
+
+fn write_all(cart: &mut Cartridge, writes: &[(u16, u8)]) {
+    for &(addr, data) in writes {
+        cart.cpu_write(addr, data);
+    }
+}
+
+#[test]
+fn test_mmc1_shift_register() {
+    let mut cart = load_ok(&banked_image(1, 8, 16));
+    // Powers on with the last 16k at $C000
+    assert_eq!(
+        (cart.cpu_peek(0x8000), cart.cpu_peek(0xC000)),
+        (Some(0), Some(14))
+    );
+
+    // Nothing happens until the 5th write
+    let serial = mmc1_writes(&[(0xE000, 3)]);
+    write_all(&mut cart, &serial[..4]);
+    assert_eq!(cart.cpu_peek(0x8000), Some(0));
+    write_all(&mut cart, &serial[4..]);
+    assert_eq!(cart.cpu_peek(0x8000), Some(6));
+
+    // Only the address of the 5th write picks the register
+    let mut serial = mmc1_writes(&[(0x8000, 0x12)]);
+    serial[4].0 = 0xA000;
+    write_all(&mut cart, &serial);
+    assert_eq!(cart.ppu_read(0x0000), Some(0x12 * 4));
+
+    // 4k chr banks and a fixed first prg bank
+    write_all(
+        &mut cart,
+        &mmc1_writes(&[(0x8000, 0x18), (0xA000, 3), (0xC000, 5)]),
+    );
+    assert_eq!(
+        (cart.cpu_peek(0x8000), cart.cpu_peek(0xC000)),
+        (Some(0), Some(6))
+    );
+    assert_eq!(
+        (cart.ppu_read(0x0000), cart.ppu_read(0x1000)),
+        (Some(12), Some(20))
+    );
+}
+
+#[test]
+fn test_mmc1_reset() {
+    let mut cart = load_ok(&banked_image(1, 8, 16));
+    // Switch to prg mode 2, first bank fixed at $8000
+    write_all(&mut cart, &mmc1_writes(&[(0x8000, 0x08), (0xE000, 3)]));
+    assert_eq!(
+        (cart.cpu_peek(0x8000), cart.cpu_peek(0xC000)),
+        (Some(0), Some(6))
+    );
+
+    // A reset halfway through throws away the bits so far and goes back to mode 3
+    write_all(&mut cart, &mmc1_writes(&[(0xE000, 0x1F)])[..3]);
+    cart.cpu_write(0x8000, 0x80);
+    assert_eq!(
+        (cart.cpu_peek(0x8000), cart.cpu_peek(0xC000)),
+        (Some(6), Some(14))
+    );
+    write_all(&mut cart, &mmc1_writes(&[(0xE000, 5)]));
+    assert_eq!(cart.cpu_peek(0x8000), Some(10));
+}
+
+fn banks(numbers: &[u8]) -> Vec<Option<u8>> {
+    numbers.iter().map(|&bank| Some(bank)).collect()
+}
+
+#[test]
+fn test_mmc3_banks() {
+    let mut cart = load_ok(&banked_image(4, 8, 16));
+    let mut writes = Vec::new();
+    for (r, &bank) in [2, 6, 9, 10, 11, 12, 3, 5].iter().enumerate() {
+        writes.extend_from_slice(&[(0x8000, r as u8), (0x8001, bank)]);
+    }
+    write_all(&mut cart, &writes);
+    let (cpu, ppu, _) = bank_mapping(&cart);
+    let prg: Vec<_> = cpu[8..].iter().step_by(8).copied().collect();
+    assert_eq!(prg, banks(&[3, 5, 14, 15]));
+    assert_eq!(ppu, banks(&[2, 3, 6, 7, 9, 10, 11, 12]));
+
+    // Prg mode 1 swaps $8000 and $C000, chr inversion swaps the pattern tables
+    cart.cpu_write(0x8000, 0xC0);
+    let (cpu, ppu, _) = bank_mapping(&cart);
+    let prg: Vec<_> = cpu[8..].iter().step_by(8).copied().collect();
+    assert_eq!(prg, banks(&[14, 5, 3, 15]));
+    assert_eq!(ppu, banks(&[9, 10, 11, 12, 2, 3, 6, 7]));
+}
+
+// Holds A12 low long enough to get past the filter, then raises it
+fn mmc3_a12_rise(cart: &mut Cartridge) {
+    cart.ppu_address(0x0000);
+    for _ in 0..3 {
+        cart.cpu_clock();
+    }
+    cart.ppu_address(0x1000);
+}
+
+#[test]
+fn test_mmc3_irq_counter() {
+    let mut cart = load_ok(&banked_image(4, 8, 16));
+    write_all(&mut cart, &[(0xC000, 2), (0xC001, 0), (0xE001, 0)]);
+    // The first rise reloads it, the irq goes off when it counts down to 0
+    for _ in 0..2 {
+        mmc3_a12_rise(&mut cart);
+        assert!(!cart.irq_state());
+    }
+    mmc3_a12_rise(&mut cart);
+    assert!(cart.irq_state());
+
+    // Acknowledged by $E000, then it reloads from 0 instead of going off again
+    write_all(&mut cart, &[(0xE000, 0), (0xE001, 0)]);
+    assert!(!cart.irq_state());
+    mmc3_a12_rise(&mut cart);
+    assert!(!cart.irq_state());
+
+    // A12 going straight back up doesn't count
+    cart.ppu_address(0x0000);
+    cart.ppu_address(0x1000);
+    mmc3_a12_rise(&mut cart);
+    assert!(!cart.irq_state());
+    mmc3_a12_rise(&mut cart);
+    assert!(cart.irq_state());
+}
+
+#[test]
+fn test_mmc3_irq_latch_zero() {
+    let mut cart = load_ok(&banked_image(4, 8, 16));
+    // Reloading with 0 goes off on every rise
+    write_all(&mut cart, &[(0xC000, 0), (0xC001, 0), (0xE001, 0)]);
+    for _ in 0..3 {
+        mmc3_a12_rise(&mut cart);
+        assert!(cart.irq_state());
+        write_all(&mut cart, &[(0xE000, 0), (0xE001, 0)]);
+    }
+    // Disabled, it still counts but doesn't go off
+    cart.cpu_write(0xE000, 0);
+    mmc3_a12_rise(&mut cart);
+    assert!(!cart.irq_state());
+}
+
+#[test]
+fn test_vrc4_address_lines() {
+    // Which cpu address lines each board wires to A0 and A1
+    let boards = [
+        (21, 0x02, 0x04),
+        (21, 0x40, 0x80),
+        (23, 0x01, 0x02),
+        (23, 0x04, 0x08),
+        (25, 0x02, 0x01),
+        (25, 0x08, 0x04),
+    ];
+    for &(mapper, a0, a1) in boards.iter() {
+        let mut cart = load_ok(&banked_image(mapper, 16, 16));
+        // Low then high bits of chr banks 0 and 1, then prg swap mode
+        let writes = [
+            (0xB000, 0x05),
+            (0xB000 | a0, 0x00),
+            (0xB000 | a1, 0x07),
+            (0xB000 | a1 | a0, 0x01),
+            (0x8000, 0x03),
+            (0x9000 | a1, 0x02),
+        ];
+        write_all(&mut cart, &writes);
+        let board = format!("mapper {} with A0/A1 on {:#04X}/{:#04X}", mapper, a0, a1);
+        assert_eq!(cart.ppu_read(0x0000), Some(0x05), "{}", board);
+        assert_eq!(cart.ppu_read(0x0400), Some(0x17), "{}", board);
+        assert_eq!(
+            (cart.cpu_peek(0x8000), cart.cpu_peek(0xC000)),
+            (Some(30), Some(3)),
+            "{}",
+            board
+        );
+    }
+}
+
+#[test]
+fn test_mmc5_multiplier() {
+    let mut cart = load_ok(&banked_image(5, 8, 16));
+    // Both start out as $FF
+    assert_eq!(
+        (cart.cpu_peek(0x5205), cart.cpu_peek(0x5206)),
+        (Some(0x01), Some(0xFE))
+    );
+    write_all(&mut cart, &[(0x5205, 200), (0x5206, 123)]);
+    // 200 * 123 = $6018, reading doesn't change anything
+    for _ in 0..2 {
+        assert_eq!(
+            (cart.cpu_read(0x5205), cart.cpu_read(0x5206)),
+            (Some(0x18), Some(0x60))
+        );
+    }
+}
+
+#[test]
+fn test_mmc5_irq_status() {
+    let mut cart = load_ok(&banked_image(5, 8, 16));
+    write_all(&mut cart, &[(0x5203, 2), (0x5204, 0x80)]);
+    for line in 0..2 {
+        cart.ppu_event(PpuEvent::ScanlineStart(line));
+        assert!(!cart.irq_state());
+    }
+    cart.ppu_event(PpuEvent::ScanlineStart(2));
+    assert!(cart.irq_state());
+
+    // Peeking at the status leaves the irq alone, reading it acknowledges it
+    assert_eq!(cart.cpu_peek(0x5204), Some(0xC0));
+    assert!(cart.irq_state());
+    assert_eq!(cart.cpu_read(0x5204), Some(0xC0));
+    assert!(!cart.irq_state());
+    assert_eq!(cart.cpu_read(0x5204), Some(0x40));
+
+    // Out of the frame once rendering stops
+    cart.ppu_event(PpuEvent::RenderingStopped);
+    assert_eq!(cart.cpu_read(0x5204), Some(0x00));
+}
+
+#[test]
+fn test_save_file_only_holds_nvram() {
+    let dir = env::temp_dir().join(format!("nes-rs-nvram-test-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let rom = dir.join("game.nes");
+    // NES 2.0 mmc5 with 8k of work ram and 8k of battery backed ram after it
+    let mut image = image(1, 1, 0x52, 0x08, 0x6000);
+    image[10] = 0x77;
+    fs::write(&rom, image).unwrap();
+    let rom = rom.to_string_lossy().to_string();
+
+    let mut cart = Cartridge::new(rom.clone()).unwrap_or_else(|e| panic!("{}", e));
+    let writes = [
+        (0x5102, 2),
+        (0x5103, 1),
+        (0x5113, 0),
+        (0x6000, 0xAA),
+        (0x5113, 1),
+        (0x6000, 0x55),
+    ];
+    write_all(&mut cart, &writes);
+    cart.flush_save().unwrap();
+    let saved = fs::read(dir.join("game.sav")).unwrap();
+
+    let mut cart = Cartridge::new(rom).unwrap_or_else(|e| panic!("{}", e));
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!((saved.len(), saved[0]), (0x2000, 0x55));
+    cart.cpu_write(0x5113, 0);
+    assert_eq!(cart.cpu_peek(0x6000), Some(0x00));
+    cart.cpu_write(0x5113, 1);
+    assert_eq!(cart.cpu_peek(0x6000), Some(0x55));
+}
+
+/// Turns an iNES header into NES 2.0 with `submapper`
+fn set_submapper(image: &mut [u8], submapper: u8) {
+    image[7] |= 0x08;
+    image[8] = submapper << 4;
+}
+
+#[test]
+fn test_uxrom_banks() {
+    let mut cart = load_ok(&banked_image(2, 8, 0));
+    assert_eq!(
+        (cart.cpu_peek(0x8000), cart.cpu_peek(0xC000)),
+        (Some(0), Some(14))
+    );
+    // The rom at $E000 holds $0F so nothing gets masked off
+    cart.cpu_write(0xE000, 3);
+    assert_eq!(
+        (cart.cpu_peek(0x8000), cart.cpu_peek(0xC000)),
+        (Some(6), Some(14))
+    );
+
+    // The rom at $C000 holds $0E, the bank written gets and-ed with it
+    cart.cpu_write(0xC000, 7);
+    assert_eq!(cart.cpu_peek(0x8000), Some(12));
+
+    // Submapper 1 has no bus conflicts
+    let mut image = banked_image(2, 8, 0);
+    set_submapper(&mut image, 1);
+    let mut cart = load_ok(&image);
+    cart.cpu_write(0xC000, 7);
+    assert_eq!(cart.cpu_peek(0x8000), Some(14));
+}
+
+#[test]
+fn test_cnrom_banks() {
+    let mut cart = load_ok(&banked_image(3, 2, 4));
+    assert_eq!(
+        (cart.cpu_peek(0x8000), cart.cpu_peek(0xE000)),
+        (Some(0), Some(3))
+    );
+    // $E000 holds 3, writing over it picks 8k chr bank 3
+    cart.cpu_write(0xE000, 3);
+    assert_eq!(
+        (cart.ppu_read(0x0000), cart.ppu_read(0x1C00)),
+        (Some(24), Some(31))
+    );
+    // $8000 holds 0, which is all that's left of the write
+    cart.cpu_write(0x8000, 2);
+    assert_eq!(cart.ppu_read(0x0000), Some(0));
+}
+
+#[test]
+fn test_axrom_banks() {
+    let mut cart = load_ok(&banked_image(7, 8, 0));
+    cart.cpu_write(0x8000, 0x12);
+    assert_eq!(
+        (cart.cpu_peek(0x8000), cart.cpu_peek(0xE000)),
+        (Some(8), Some(11))
+    );
+    // Bit 4 picks which nametable fills the whole screen
+    let pages: Vec<_> = (0..4)
+        .map(|i| cart.nametable_page(0x2000 + i * 0x400))
+        .collect();
+    assert_eq!(pages, [NametablePage::Ciram(1); 4]);
+    cart.cpu_write(0x8000, 0x02);
+    let pages: Vec<_> = (0..4)
+        .map(|i| cart.nametable_page(0x2000 + i * 0x400))
+        .collect();
+    assert_eq!(pages, [NametablePage::Ciram(0); 4]);
+
+    // Only submapper 2 has bus conflicts, $8000 holds 8 there
+    let mut image = banked_image(7, 8, 0);
+    set_submapper(&mut image, 2);
+    let mut cart = load_ok(&image);
+    cart.cpu_write(0x8000, 0x03);
+    assert_eq!(cart.cpu_peek(0x8000), Some(0));
+}
+
+#[test]
+fn test_color_dreams_banks() {
+    let mut image = banked_image(11, 4, 8);
+    // Somewhere to write where the rom doesn't get in the way
+    image[16 + 0x7FFF] = 0xFF;
+    let mut cart = load_ok(&image);
+    cart.cpu_write(0xFFFF, 0x21);
+    assert_eq!(
+        (cart.cpu_peek(0x8000), cart.ppu_read(0x0000)),
+        (Some(4), Some(16))
+    );
+    // $8000 holds 4 now, only bit 2 of the write gets through
+    cart.cpu_write(0x8000, 0xFF);
+    assert_eq!(
+        (cart.cpu_peek(0x8000), cart.ppu_read(0x0000)),
+        (Some(0), Some(0))
+    );
+}
+
+#[test]
+fn test_gxrom_banks() {
+    let mut image = banked_image(66, 8, 4);
+    image[16 + 0x7FFF] = 0xFF;
+    let mut cart = load_ok(&image);
+    cart.cpu_write(0xFFFF, 0x21);
+    assert_eq!(
+        (cart.cpu_peek(0x8000), cart.ppu_read(0x0000)),
+        (Some(8), Some(8))
+    );
+    // $8000 holds 8 now, which has neither of the bank bits
+    cart.cpu_write(0x8000, 0x33);
+    assert_eq!(
+        (cart.cpu_peek(0x8000), cart.ppu_read(0x0000)),
+        (Some(0), Some(0))
+    );
+}
+
+#[test]
+fn test_mapper_34_boards() {
+    // 8k of chr is BNROM, 32k prg banks switched at $8000 with bus conflicts
+    let mut cart = load_ok(&banked_image(34, 8, 1));
+    cart.cpu_write(0xE000, 0x03);
+    assert_eq!(
+        (cart.cpu_peek(0x8000), cart.ppu_read(0x0400)),
+        (Some(12), Some(1))
+    );
+    cart.cpu_write(0x8000, 0x01);
+    assert_eq!(cart.cpu_peek(0x8000), Some(0));
+    // and it ignores the NINA-001 registers
+    cart.cpu_write(0x7FFD, 0x01);
+    assert_eq!(cart.cpu_peek(0x8000), Some(0));
+
+    // Any more is NINA-001, prg at $7FFD and two 4k chr banks at $7FFE/$7FFF
+    let mut cart = load_ok(&banked_image(34, 4, 2));
+    write_all(&mut cart, &[(0x7FFD, 1), (0x7FFE, 2), (0x7FFF, 3)]);
+    assert_eq!(cart.cpu_peek(0x8000), Some(4));
+    assert_eq!(
+        (cart.ppu_read(0x0000), cart.ppu_read(0x1000)),
+        (Some(8), Some(12))
+    );
+    cart.cpu_write(0x8000, 0x00);
+    assert_eq!(cart.cpu_peek(0x8000), Some(4));
+
+    // The submapper wins over the chr size
+    let mut image = banked_image(34, 4, 1);
+    set_submapper(&mut image, 1);
+    let mut cart = load_ok(&image);
+    cart.cpu_write(0x7FFD, 1);
+    assert_eq!(cart.cpu_peek(0x8000), Some(4));
+}
+
+// Feeds `addr` to the mapper's latches and checks the 4k chr banks that show up after
+fn check_chr_latch(cart: &mut Cartridge, addr: u16, banks: (u8, u8)) {
+    cart.ppu_address(addr);
+    let shown = (cart.ppu_read(0x0000), cart.ppu_read(0x1000));
+    let expected = (Some(banks.0 * 4), Some(banks.1 * 4));
+    assert_eq!(shown, expected, "after {:04X}", addr);
+}
+
+#[test]
+fn test_chr_latches() {
+    // 4k chr banks 1 and 2 for latch 0 being $FD/$FE, 3 and 4 for latch 1
+    let writes = [(0xB000, 1), (0xC000, 2), (0xD000, 3), (0xE000, 4)];
+    // MMC2's latch 0 only flips on exactly $0FD8/$0FE8, MMC4's looks at the whole tile row
+    let mmc2 = [
+        (0x0FD9, (2, 4)),
+        (0x0FD8, (1, 4)),
+        (0x0FEF, (1, 4)),
+        (0x0FE8, (2, 4)),
+        (0x1FDF, (2, 3)),
+        (0x1FE8, (2, 4)),
+        (0x1FD7, (2, 4)),
+    ];
+    let mmc4 = [
+        (0x0FDF, (1, 4)),
+        (0x0FE9, (2, 4)),
+        (0x0FD7, (2, 4)),
+        (0x1FD8, (2, 3)),
+        (0x1FEF, (2, 4)),
+    ];
+    for &(mapper, triggers) in [(9, &mmc2[..]), (10, &mmc4[..])].iter() {
+        let mut cart = load_ok(&banked_image(mapper, 8, 16));
+        write_all(&mut cart, &writes);
+        // Both latches start out on $FE, other addresses leave them alone
+        check_chr_latch(&mut cart, 0x0000, (2, 4));
+        for &(addr, banks) in triggers {
+            check_chr_latch(&mut cart, addr, banks);
+        }
+    }
+}
+
+#[test]
+fn test_vrc2_or_vrc4() {
+    // 128k of prg or less can only be VRC2, with its latch at $6000
+    for &(mapper, prg_chunks, submapper, vrc2) in [
+        (23, 8, 0, true),
+        (23, 16, 0, false),
+        (25, 8, 0, true),
+        (25, 16, 0, false),
+        (23, 8, 2, false),
+        (25, 16, 3, true),
+    ]
+    .iter()
+    {
+        let mut image = banked_image(mapper, prg_chunks, 16);
+        if submapper > 0 {
+            set_submapper(&mut image, submapper);
+            // and 8k of ram
+            image[10] = 0x07;
+        }
+        let mut cart = load_ok(&image);
+        cart.cpu_write(0x6000, 0x01);
+        let expected = if vrc2 { 0x61 } else { 0x01 };
+        assert_eq!(
+            cart.cpu_peek(0x6000),
+            Some(expected),
+            "mapper {} with {} prg chunks, submapper {}",
+            mapper,
+            prg_chunks,
+            submapper
+        );
+    }
+}
+
+// Clocks the cartridge and gives back the expansion audio level after each cycle, in steps of `unit`
+fn audio_levels(cart: &mut Cartridge, cycles: usize, unit: f32) -> Vec<i32> {
+    (0..cycles)
+        .map(|_| {
+            cart.cpu_clock();
+            (cart.audio_output() / unit).round() as i32
+        })
+        .collect()
+}
+
+fn vrc6_levels(cart: &mut Cartridge, cycles: usize) -> Vec<i32> {
+    audio_levels(cart, cycles, 0.01)
+}
+
+#[test]
+fn test_vrc6_audio() {
+    let mut cart = load_ok(&banked_image(24, 8, 16));
+    // Pulse 1 at full volume with a 4/16 duty, stepping every cycle
+    write_all(&mut cart, &[(0x9000, 0x3F), (0x9001, 0x00), (0x9002, 0x80)]);
+    let levels = vrc6_levels(&mut cart, 16);
+    assert_eq!(levels.iter().filter(|&&l| l == 15).count(), 4);
+    assert_eq!(levels.iter().filter(|&&l| l == 0).count(), 12);
+    // Constant volume ignores the duty
+    cart.cpu_write(0x9000, 0x85);
+    assert_eq!(vrc6_levels(&mut cart, 16), [5; 16]);
+    // Clearing the enable bit silences it
+    cart.cpu_write(0x9002, 0x00);
+    assert_eq!(vrc6_levels(&mut cart, 4), [0; 4]);
+
+    // The saw adds its rate every other step and starts over on the 14th
+    write_all(&mut cart, &[(0xB000, 10), (0xB001, 0x00), (0xB002, 0x80)]);
+    let ramp = [0, 1, 1, 2, 2, 3, 3, 5, 5, 6, 6, 7, 7, 0];
+    assert_eq!(vrc6_levels(&mut cart, 14), ramp);
+
+    assert_eq!(vrc6_levels(&mut cart, 2), [0, 1]);
+
+    // Halting stops every channel where it is
+    cart.cpu_write(0x9003, 0x01);
+    assert_eq!(vrc6_levels(&mut cart, 4), [1; 4]);
+    // Periods shifted right by 4, the step after this one takes 1 + ($100 >> 4) cycles
+    write_all(&mut cart, &[(0xB002, 0x81), (0x9003, 0x02)]);
+    let levels = vrc6_levels(&mut cart, 18);
+    assert_eq!(levels[..17], [1; 17]);
+    assert_eq!(levels[17], 2);
+}
+
+#[test]
+fn test_fme7_banks() {
+    let mut cart = load_ok(&banked_image(69, 8, 16));
+    // Command at $8000, its parameter at $A000
+    let writes = [
+        (0x8000, 0x0),
+        (0xA000, 5),
+        (0x8000, 0x7),
+        (0xA000, 0x7F),
+        (0x8000, 0x9),
+        (0xA000, 3),
+        (0x8000, 0xA),
+        (0xA000, 0x44),
+        (0x8000, 0xB),
+        (0xA000, 12),
+        (0x8000, 0x8),
+        (0xA000, 2),
+    ];
+    write_all(&mut cart, &writes);
+    let (cpu, ppu, _) = bank_mapping(&cart);
+    let prg: Vec<_> = cpu.iter().step_by(8).copied().collect();
+    assert_eq!(prg, banks(&[2, 3, 4, 12, 15]));
+    assert_eq!(ppu[0], Some(5));
+    assert_eq!(ppu[7], Some(0x7F));
+
+    // Ram at $6000 once it's enabled, open bus while it's selected but disabled
+    write_all(&mut cart, &[(0x8000, 0x8), (0xA000, 0xC0), (0x6000, 0x55)]);
+    assert_eq!(cart.cpu_peek(0x6000), Some(0x55));
+    cart.cpu_write(0xA000, 0x40);
+    assert_eq!(cart.cpu_peek(0x6000), None);
+    cart.cpu_write(0xA000, 0xC0);
+    assert_eq!(cart.cpu_peek(0x6000), Some(0x55));
+}
+
+#[test]
+fn test_fme7_irq() {
+    let mut cart = load_ok(&banked_image(69, 8, 16));
+    let writes = [
+        (0x8000, 0xE),
+        (0xA000, 2),
+        (0x8000, 0xF),
+        (0xA000, 0),
+        (0x8000, 0xD),
+        (0xA000, 0x81),
+    ];
+    write_all(&mut cart, &writes);
+    // Counts down every cycle and goes off when it wraps past 0
+    for _ in 0..2 {
+        cart.cpu_clock();
+        assert!(!cart.irq_state());
+    }
+    cart.cpu_clock();
+    assert!(cart.irq_state());
+
+    // Any write to the control acknowledges it
+    cart.cpu_write(0xA000, 0x81);
+    assert!(!cart.irq_state());
+
+    // Counting without the irq enabled
+    write_all(
+        &mut cart,
+        &[(0x8000, 0xE), (0xA000, 0), (0x8000, 0xF), (0xA000, 0)],
+    );
+    write_all(&mut cart, &[(0x8000, 0xD), (0xA000, 0x80)]);
+    cart.cpu_clock();
+    assert!(!cart.irq_state());
+    // Or the irq without the counter
+    write_all(
+        &mut cart,
+        &[(0x8000, 0xE), (0xA000, 0), (0x8000, 0xF), (0xA000, 0)],
+    );
+    write_all(&mut cart, &[(0x8000, 0xD), (0xA000, 0x01)]);
+    for _ in 0..4 {
+        cart.cpu_clock();
+        assert!(!cart.irq_state());
+    }
+}
+
+#[test]
+fn test_sunsoft5b_audio() {
+    let mut cart = load_ok(&banked_image(69, 8, 16));
+    // Tone A alone with the shortest period at full volume, noise off everywhere
+    let registers = [(0x00, 1), (0x01, 0), (0x07, 0x3E), (0x08, 0x0F)];
+    for &(register, data) in registers.iter() {
+        write_all(&mut cart, &[(0xC000, register), (0xE000, data)]);
+    }
+    // The chip runs at a 16th of the cpu clock
+    let levels = audio_levels(&mut cart, 48, 0.15);
+    assert_eq!(levels[..15], [0; 15]);
+    assert_eq!(levels[15..31], [1; 16]);
+    assert_eq!(levels[31..47], [0; 16]);
+
+    // With the tone disabled in the mixer the channel is just on
+    write_all(&mut cart, &[(0xC000, 0x07), (0xE000, 0x3F)]);
+    assert_eq!(audio_levels(&mut cart, 32, 0.15), [1; 32]);
+    // Registers past $0F are ignored
+    write_all(&mut cart, &[(0xC000, 0x18), (0xE000, 0x00)]);
+    assert_eq!(audio_levels(&mut cart, 4, 0.15), [1; 4]);
+
+    // The envelope ramps down then holds at silence with shape 0
+    let registers = [(0x08, 0x10), (0x0B, 1), (0x0C, 0), (0x0D, 0x00)];
+    for &(register, data) in registers.iter() {
+        write_all(&mut cart, &[(0xC000, register), (0xE000, data)]);
+    }
+    let ramp = audio_levels(&mut cart, 32 * 16, 0.0001);
+    assert_eq!(ramp[0], 1500);
+    assert!(ramp.windows(2).all(|w| w[1] <= w[0]));
+    assert!(ramp[ramp.len() - 16] < ramp[0]);
+    assert_eq!(audio_levels(&mut cart, 64, 0.0001), [0; 64]);
+}
+
+#[test]
+fn test_n163_ram_port() {
+    let mut cart = load_ok(&banked_image(19, 8, 16));
+    // Auto increment from address 0
+    write_all(
+        &mut cart,
+        &[(0xF800, 0x80), (0x4800, 1), (0x4800, 2), (0x4800, 3)],
+    );
+    cart.cpu_write(0xF800, 0x80);
+    // Peeking doesn't move the address, reading does
+    assert_eq!(cart.cpu_peek(0x4800), Some(1));
+    assert_eq!(cart.cpu_peek(0x4800), Some(1));
+    assert_eq!(
+        [
+            cart.cpu_read(0x4800),
+            cart.cpu_read(0x4800),
+            cart.cpu_read(0x4800)
+        ],
+        [Some(1), Some(2), Some(3)]
+    );
+    // Without the increment bit it stays put
+    cart.cpu_write(0xF800, 0x01);
+    assert_eq!(
+        [cart.cpu_read(0x4800), cart.cpu_read(0x4800)],
+        [Some(2), Some(2)]
+    );
+    // The address wraps within the 128 bytes
+    write_all(&mut cart, &[(0xF800, 0xFF), (0x4800, 0xAA)]);
+    assert_eq!(cart.cpu_read(0x4800), Some(1));
+    cart.cpu_write(0xF800, 0x7F);
+    assert_eq!(cart.cpu_peek(0x4800), Some(0xAA));
+}
+
+#[test]
+fn test_n163_irq() {
+    let mut cart = load_ok(&banked_image(19, 8, 16));
+    // Not counting without the enable bit
+    write_all(&mut cart, &[(0x5000, 0xFD), (0x5800, 0x7F)]);
+    cart.cpu_clock();
+    assert_eq!(cart.cpu_peek(0x5000), Some(0xFD));
+
+    // Counts up and goes off at $7FFF, then stops there
+    cart.cpu_write(0x5800, 0xFF);
+    cart.cpu_clock();
+    assert!(!cart.irq_state());
+    cart.cpu_clock();
+    assert!(cart.irq_state());
+    cart.cpu_clock();
+    assert_eq!(
+        (cart.cpu_peek(0x5000), cart.cpu_peek(0x5800)),
+        (Some(0xFF), Some(0xFF))
+    );
+
+    // Writing either half acknowledges it
+    cart.cpu_write(0x5800, 0xFF);
+    assert!(!cart.irq_state());
+    write_all(&mut cart, &[(0x5000, 0xFE), (0x5800, 0xFF)]);
+    cart.cpu_clock();
+    assert!(cart.irq_state());
+    cart.cpu_write(0x5000, 0x00);
+    assert!(!cart.irq_state());
+}
+
+#[test]
+fn test_n163_audio() {
+    let mut cart = load_ok(&banked_image(19, 8, 16));
+    // A 4 sample wave of 8, 15, 0, 0 at address 0
+    write_all(&mut cart, &[(0xF800, 0x80), (0x4800, 0xF8), (0x4800, 0x00)]);
+    // Channel 8 on its own, one sample per update at full volume
+    cart.cpu_write(0xF800, 0xF8);
+    for &data in [0x00, 0x00, 0x00, 0x00, 0xFD, 0x00, 0x00, 0x0F].iter() {
+        cart.cpu_write(0x4800, data);
+    }
+    // It's updated every 15 cycles
+    let levels = audio_levels(&mut cart, 60, 0.15 / 105.0);
+    for (update, &level) in [105, -120, -120, 0].iter().enumerate() {
+        let cycles = &levels[update * 15..update * 15 + 15];
+        assert_eq!(cycles[..14], [cycles[0]; 14], "update {}", update);
+        assert_eq!(cycles[14], level, "update {}", update);
+    }
+
+    // Bit 6 of the $8000 bank silences it
+    cart.cpu_write(0xE000, 0x40);
+    assert_eq!(audio_levels(&mut cart, 15, 0.15 / 105.0), [0; 15]);
+}
+
+#[test]
+fn test_four_screen() {
+    for &mapper in [0, 4].iter() {
+        let mut image = banked_image(mapper, 2, 1);
+        image[6] |= 0x08;
+        let mut cart = load_ok(&image);
+        // MMC3's mirroring register doesn't apply with four screen vram
+        cart.cpu_write(0xA000, 0x01);
+        let pages: Vec<_> = (0..4)
+            .map(|i| cart.nametable_page(0x2000 + i * 0x400))
+            .collect();
+        let expected = [
+            NametablePage::Ciram(0),
+            NametablePage::Ciram(1),
+            NametablePage::CartVram(0),
+            NametablePage::CartVram(1),
+        ];
+        assert_eq!(pages, expected, "mapper {}", mapper);
+
+        // The two pages on the cartridge don't overlap
+        cart.vram_write(0, 0x2800, 0x11);
+        cart.vram_write(1, 0x2C00, 0x22);
+        assert_eq!(
+            (cart.vram_read(0, 0x2800), cart.vram_read(1, 0x2C00)),
+            (0x11, 0x22),
+            "mapper {}",
+            mapper
+        );
+    }
+}
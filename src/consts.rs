@@ -165,11 +165,11 @@ pub mod debug_consts {
     const PPU_SCREEN_Y: f32 = PADDING_SIZE;
     pub const PPU_SCREEN_POS: [f32; 2] = [PPU_SCREEN_X, PPU_SCREEN_Y];
 
-    const PPU_GAME_SCALE: f32 = 2.0f32;
-    const PPU_SCREEN_X_BASE_SIZE: f32 = NUM_CYCLES_PER_SCANLINE as f32;
+    pub const PPU_GAME_SCALE: f32 = 2.0f32; /* Default scale, can be overridden from the command line */
+    pub const PPU_SCREEN_X_BASE_SIZE: f32 = NUM_CYCLES_PER_SCANLINE as f32;
     const PPU_SCREEN_X_SIZE: f32 = PPU_SCREEN_X_BASE_SIZE * PPU_GAME_SCALE;
 
-    const PPU_SCREEN_Y_BASE_SIZE: f32 = NUM_SCANLINES_RENDERED as f32;
+    pub const PPU_SCREEN_Y_BASE_SIZE: f32 = NUM_SCANLINES_RENDERED as f32;
     const PPU_SCREEN_Y_SIZE: f32 = PPU_SCREEN_Y_BASE_SIZE * PPU_GAME_SCALE;
 
     pub const PPU_SCREEN_SIZE: [f32; 2] = [PPU_SCREEN_X_SIZE, PPU_SCREEN_Y_SIZE];
@@ -305,17 +305,18 @@ pub mod render_consts {
     pub const SCREEN_TEX_HEIGHT: usize = HEIGHT;
 }
 
-pub mod nes_consts {
-    use crate::cartridge::Rom;
-
-    pub const CART: Rom = Rom::Mario;
-}
-
 pub mod emulation_consts {
     use crate::emulator::FrameSync;
     pub const EMU_START_STATE: FrameSync = FrameSync::Stop;
     pub const EMU_DEBUG: bool = true;
-    pub const CPU_DEBUG: bool = false;
+    /* Where the debug window's trace button writes to */
+    pub const TRACE_FILE: &str = "trace.log";
+
+    /* How often battery backed ram gets flushed to the .sav file, ~10 seconds */
+    pub const SAVE_INTERVAL_FRAMES: i32 = 600;
+
+    /* Rate the apu output gets resampled to */
+    pub const AUDIO_SAMPLE_RATE: u32 = 48000;
 
     use glium::texture::ClientFormat;
     pub const COLOR_CHANNELS: usize = 3;
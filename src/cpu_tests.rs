@@ -0,0 +1,300 @@
+use crate::cartridge::{Cartridge, Rom};
+use crate::consts::emulation_consts::AUDIO_SAMPLE_RATE;
+use crate::nes::{Nes, Region};
+use crate::trace::{trace_line, TraceFilter};
+use std::{env, fs, thread};
+
+const NESTEST_LOG: &str = "test-roms/cpu/expected.txt";
+// The CYC column of the last line in the full nintendulator log
+const NESTEST_LAST_CYCLE: u32 = 26554;
+
+/// The columns the golden log actually has, it was stripped down to the
+/// pc, opcode bytes, mnemonic and registers (no P, PPU or CYC)
+fn golden_columns(line: &str) -> Vec<String> {
+    let mnemonic = line.get(15..).and_then(|x| x.split_whitespace().next());
+    let mut columns = vec![
+        line.get(0..4).unwrap_or("").to_string(),
+        line.get(6..14).unwrap_or("").trim().to_string(),
+        mnemonic.unwrap_or("").to_string(),
+    ];
+    for register in [" A:", " X:", " Y:", " SP:"].iter() {
+        let value = line
+            .find(register)
+            .and_then(|i| line.get(i + register.len()..i + register.len() + 2));
+        columns.push(format!("{}{}", register.trim(), value.unwrap_or("")));
+    }
+    columns
+}
+
+// Runs until the cpu is about to fetch its next opcode
+fn run_to_next_instruction(nes: &mut Nes) {
+    while !nes.cpu.between_instructions() {
+        nes.clock();
+    }
+}
+
+// The ppu's buffers make a Nes close to a megabyte, a debug build moves it
+// around on the stack enough to blow through the 2MB a test thread gets
+fn run_with_big_stack(f: fn()) {
+    thread::Builder::new()
+        .stack_size(64 * 1024 * 1024)
+        .spawn(f)
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
+#[test]
+fn test_nestest_golden_log() {
+    run_with_big_stack(nestest_golden_log);
+}
+
+fn nestest_golden_log() {
+    let expected = fs::read_to_string(NESTEST_LOG).unwrap();
+    let mut nes = Nes::new(Cartridge::from(Rom::NesTest).unwrap(), Region::Ntsc);
+    // Automated mode starts at $C000 instead of the reset vector
+    nes.cpu.reset(Some(0xC000));
+    // The golden log starts out with P:24, the stripped log has no P to check against
+    assert_eq!(nes.cpu.status, 0x24);
+
+    let lines = expected.lines().count();
+    for (i, want) in expected.lines().enumerate() {
+        run_to_next_instruction(&mut nes);
+        // There's no CYC column either, the full log puts the last instruction here
+        if i + 1 == lines {
+            assert_eq!(nes.cpu.clock_count, NESTEST_LAST_CYCLE);
+        }
+        let got = trace_line(&mut nes.cpu);
+        if golden_columns(want) != golden_columns(&got) {
+            panic!(
+                "nestest diverged at line {}\nexpected: {}\n     got: {}",
+                i + 1,
+                want,
+                got
+            );
+        }
+        nes.clock_one_instruction();
+    }
+
+    // nestest leaves the number of the first test that failed in $02 (official
+    // opcodes) and $03 (unofficial ones), that's where wrong flags show up
+    let official = nes.cpu.bus.cpu_read(0x0002, true);
+    let unofficial = nes.cpu.bus.cpu_read(0x0003, true);
+    assert_eq!((official, unofficial), (0x00, 0x00));
+}
+
+// Loads `program` into ram at $0300 and runs the cpu up to its first instruction
+fn boot_program(region: Region, program: &[u8]) -> Box<Nes> {
+    let mut nes = Box::new(Nes::new(Cartridge::from(Rom::NesTest).unwrap(), region));
+    for (i, &byte) in program.iter().enumerate() {
+        nes.cpu.bus.cpu_write(0x0300 + i as u16, byte);
+    }
+    nes.cpu.reset(Some(0x0300));
+    run_to_next_instruction(&mut nes);
+    nes
+}
+
+// Runs until the cpu gets to `pc`, returns how many cycles it spent stalled on the way
+fn stalled_cycles_until(nes: &mut Nes, pc: u16) -> usize {
+    let (cycles, executed) = (nes.cpu_cycle, nes.cpu.clock_count);
+    while nes.cpu.pc != pc || !nes.cpu.between_instructions() {
+        nes.clock();
+    }
+    (nes.cpu_cycle - cycles) - (nes.cpu.clock_count - executed) as usize
+}
+
+#[test]
+fn test_pal_oam_dma() {
+    run_with_big_stack(pal_oam_dma);
+}
+
+fn pal_oam_dma() {
+    let mut stalls = Vec::new();
+    // LDA $00 takes 3 cycles, it moves the write over to the other half of the apu cycle
+    for prelude in [&[][..], &[0xA5, 0x00][..]].iter() {
+        // LDA #$02, STA $4014, NOP, JMP *
+        let mut program = prelude.to_vec();
+        program.extend_from_slice(&[0xA9, 0x02, 0x8D, 0x14, 0x40, 0xEA, 0x4C]);
+        let end = 0x0300 + program.len() as u16 - 1;
+        program.extend_from_slice(&end.to_le_bytes());
+
+        let mut nes = boot_program(Region::Pal, &program);
+        for i in 0..=0xFF {
+            nes.cpu.bus.cpu_write(0x0200 + i, (i as u8) ^ 0x5A);
+        }
+        stalls.push(stalled_cycles_until(&mut nes, end));
+
+        for (i, entry) in nes.get_oam().iter().enumerate() {
+            let copied = [entry.y, entry.id, entry.attribute, entry.x];
+            for (j, &byte) in copied.iter().enumerate() {
+                assert_eq!(byte, ((i * 4 + j) as u8) ^ 0x5A, "oam byte {}", i * 4 + j);
+            }
+        }
+    }
+    // A halt cycle, one more if that landed on a get, then 256 get/put pairs
+    stalls.sort_unstable();
+    assert_eq!(stalls, [513, 514]);
+}
+
+// Runs the system clock to the end of the next cpu cycle
+fn clock_cpu_cycle(nes: &mut Nes) {
+    let cycle = nes.cpu_cycle;
+    while nes.cpu_cycle == cycle {
+        nes.clock();
+    }
+}
+
+#[test]
+fn test_dmc_halts_on_reads() {
+    run_with_big_stack(dmc_halts_on_reads);
+}
+
+fn dmc_halts_on_reads() {
+    let mut stalls = Vec::new();
+    for prelude in [&[][..], &[0xA5, 0x00][..]].iter() {
+        // JSR sub, JMP *, sub: JMP *
+        let jsr = 0x0300 + prelude.len() as u16;
+        let sub = jsr + 6;
+        let mut program = prelude.to_vec();
+        program.push(0x20);
+        program.extend_from_slice(&sub.to_le_bytes());
+        program.push(0x4C);
+        program.extend_from_slice(&(jsr + 3).to_le_bytes());
+        program.push(0x4C);
+        program.extend_from_slice(&sub.to_le_bytes());
+
+        let mut nes = boot_program(Region::Ntsc, &program);
+        // A one byte sample at $C000, at the fastest rate
+        nes.cpu.bus.cpu_write(0x4010, 0x0F);
+        nes.cpu.bus.cpu_write(0x4012, 0x00);
+        nes.cpu.bus.cpu_write(0x4013, 0x00);
+        stalled_cycles_until(&mut nes, jsr);
+
+        // Start the sample on JSR's first push, the second one is a write too
+        while nes.cpu.bus.cpu_read_addr.is_some() {
+            clock_cpu_cycle(&mut nes);
+        }
+        nes.cpu.bus.cpu_write(0x4015, 0x10);
+        clock_cpu_cycle(&mut nes);
+        assert!(nes.cpu.bus.cpu_read_addr.is_none());
+        assert_eq!(nes.cpu.bus.dmc_halt, None);
+        // Then it fetches the high byte of the address and gets halted
+        clock_cpu_cycle(&mut nes);
+        assert_eq!(nes.cpu.bus.dmc_halt, Some(0));
+
+        let (cycles, executed) = (nes.cpu_cycle, nes.cpu.clock_count);
+        while nes.cpu.bus.dmc_halt.is_some() {
+            clock_cpu_cycle(&mut nes);
+        }
+        assert_eq!(nes.cpu.clock_count, executed);
+        stalls.push(nes.cpu_cycle - cycles);
+        assert_eq!(nes.cpu.bus.apu.dmc_dma_address(), None);
+    }
+    // Halt, a dummy cycle, one more to get to a get cycle, then the fetch
+    stalls.sort_unstable();
+    assert_eq!(stalls, [3, 4]);
+}
+
+#[test]
+fn test_audio_frame_length() {
+    run_with_big_stack(audio_frame_length);
+}
+
+fn audio_frame_length() {
+    let mut nes = boot_program(Region::Ntsc, &[0x4C, 0x00, 0x03]);
+    // A frame is a little over 1/60th of a second, give or take a sample for
+    // the fraction carried over between frames
+    let expected = AUDIO_SAMPLE_RATE as f64 / 60.0988;
+    for _ in 0..60 {
+        nes.clock_one_frame();
+        let length = nes.audio_frame().len() as f64;
+        assert!((length - expected).abs() < 1.5, "{} samples", length);
+    }
+}
+
+// The start of the full nintendulator log. The golden one is missing the P, PPU and CYC
+// columns, and the ppu here starts out 2 dots later than nintendulator's so PPU is 2 more
+const NESTEST_TRACE: [&str; 9] = [
+    "C000  4C F5 C5  JMP $C5F5                       A:00 X:00 Y:00 P:24 SP:FD PPU:  0, 23 CYC:7",
+    "C5F5  A2 00     LDX #$00                        A:00 X:00 Y:00 P:24 SP:FD PPU:  0, 32 CYC:10",
+    "C5F7  86 00     STX $00 = 00                    A:00 X:00 Y:00 P:26 SP:FD PPU:  0, 38 CYC:12",
+    "C5F9  86 10     STX $10 = 00                    A:00 X:00 Y:00 P:26 SP:FD PPU:  0, 47 CYC:15",
+    "C5FB  86 11     STX $11 = 00                    A:00 X:00 Y:00 P:26 SP:FD PPU:  0, 56 CYC:18",
+    "C5FD  20 2D C7  JSR $C72D                       A:00 X:00 Y:00 P:26 SP:FD PPU:  0, 65 CYC:21",
+    "C72D  EA        NOP                             A:00 X:00 Y:00 P:26 SP:FB PPU:  0, 83 CYC:27",
+    "C72E  38        SEC                             A:00 X:00 Y:00 P:26 SP:FB PPU:  0, 89 CYC:29",
+    "C72F  B0 04     BCS $C735                       A:00 X:00 Y:00 P:27 SP:FB PPU:  0, 95 CYC:31",
+];
+
+fn trace_path(name: &str) -> String {
+    let path = env::temp_dir().join(format!("nes-rs-{}.log", name));
+    path.to_str().unwrap().to_string()
+}
+
+// Stops the trace and hands back what ended up in the file
+fn finish_trace(nes: &mut Nes, path: &str) -> Vec<String> {
+    nes.stop_trace();
+    let lines = fs::read_to_string(path).unwrap();
+    fs::remove_file(path).unwrap();
+    lines.lines().map(|x| x.to_string()).collect()
+}
+
+// Runs the start of nestest with a trace going
+fn trace_nestest(name: &str, filter: TraceFilter) -> Vec<String> {
+    let path = trace_path(name);
+    let mut nes = Box::new(Nes::new(
+        Cartridge::from(Rom::NesTest).unwrap(),
+        Region::Ntsc,
+    ));
+    nes.cpu.reset(Some(0xC000));
+    run_to_next_instruction(&mut nes);
+    nes.start_trace(&path, filter).unwrap();
+    for _ in 0..NESTEST_TRACE.len() {
+        nes.clock_one_instruction();
+    }
+    finish_trace(&mut nes, &path)
+}
+
+#[test]
+fn test_trace_log() {
+    run_with_big_stack(trace_log);
+}
+
+fn trace_log() {
+    let lines = trace_nestest("trace", TraceFilter::default());
+    assert_eq!(lines, NESTEST_TRACE);
+
+    // Only the subroutine
+    let filter = TraceFilter {
+        pc_range: Some(0xC72D..=0xC72F),
+        start_frame: 0,
+    };
+    assert_eq!(trace_nestest("trace-pc", filter), NESTEST_TRACE[6..]);
+}
+
+#[test]
+fn test_trace_start_frame() {
+    run_with_big_stack(trace_start_frame);
+}
+
+fn trace_start_frame() {
+    let path = trace_path("trace-frame");
+    let mut nes = boot_program(Region::Ntsc, &[0x4C, 0x00, 0x03]);
+    let filter = TraceFilter {
+        pc_range: None,
+        start_frame: 1,
+    };
+    nes.start_trace(&path, filter).unwrap();
+    // Nothing from the thousands of jumps in the first frame, everything after it
+    while nes.cpu.bus.ppu.frame_complete_count < 1 {
+        nes.clock_one_instruction();
+    }
+    for _ in 0..3 {
+        nes.clock_one_instruction();
+    }
+    let lines = finish_trace(&mut nes, &path);
+    assert_eq!(lines.len(), 3);
+    for line in lines.iter() {
+        assert!(line.starts_with("0300  4C 00 03  JMP $0300 "), "{}", line);
+    }
+}
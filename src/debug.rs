@@ -1,10 +1,12 @@
 use crate::consts::{
     debug_consts::*,
+    emulation_consts::TRACE_FILE,
     ppu_consts::*,
 };
-use crate::cpu::CPUFlags;
 use crate::emulator::{EmulationState, FrameSync};
 use crate::nes::Nes;
+use crate::trace::TraceFilter;
+use cpu6502::CPUFlags;
 
 use imgui::*;
 
@@ -19,7 +21,7 @@ use std::time::Instant;
 pub fn draw_debug(nes: &mut Nes, state: &mut EmulationState, ui: &Ui) {
     
     draw_ppu_buffer(state, ui);
-    emulation_control(state, ui);
+    emulation_control(nes, state, ui);
 
     if cfg!(debug_assertions) {
         draw_cpu(nes, ui);
@@ -40,7 +42,7 @@ pub fn draw_debug(nes: &mut Nes, state: &mut EmulationState, ui: &Ui) {
     }
 }
 
-fn emulation_control(state: &mut EmulationState, ui: &Ui) {
+fn emulation_control(nes: &mut Nes, state: &mut EmulationState, ui: &Ui) {
     ui.window("Emulation Control.")
         .position(EMULATION_CONTROLS_POS, Condition::Appearing)
         .build(|| {
@@ -115,6 +117,24 @@ fn emulation_control(state: &mut EmulationState, ui: &Ui) {
                 state.watch_addr = u16::from_str_radix(&watch_addr, 16).unwrap_or(0);
             };
 
+            ui.separator();
+            ui.text("Instruction trace");
+            if nes.is_tracing() {
+                if ui.button("Stop trace") {
+                    nes.stop_trace();
+                }
+                ui.same_line();
+                ui.text(format!("Writing {}", TRACE_FILE));
+            } else if ui.button("Start trace") {
+                state.trace_error = nes
+                    .start_trace(TRACE_FILE, TraceFilter::default())
+                    .err()
+                    .map(|e| format!("Could not create {}: {}", TRACE_FILE, e));
+            }
+            if let Some(error) = &state.trace_error {
+                ui.text_colored(debug_color::RED, error);
+            }
+
             ui.separator();
             if ui.button("Stop.") {
                 state.frame_sync = FrameSync::Stop;
@@ -292,15 +312,20 @@ fn draw_code(num_lines: usize, nes: &mut Nes, ui: &Ui) {
 }
 
 fn draw_ppu_buffer(state: &EmulationState, ui: &Ui) {
+    let screen_size = state.screen_size();
+    let window_size = [
+        screen_size[0] + (PADDING_SIZE * 2f32),
+        screen_size[1] + (PADDING_SIZE * 4f32),
+    ];
     ui.window("NES")
         .position(PPU_SCREEN_POS, PPU_SCREEN_POSITION_COND)
-        .size(PPU_GAME_WINDOW_SIZE, PPU_SCREEN_SIZE_COND)
+        .size(window_size, PPU_SCREEN_SIZE_COND)
         .resizable(PPU_SCREEN_RESIZABLE)
         .scroll_bar(PPU_SCREEN_SCROLLBAR)
         .collapsible(PPU_SCREEN_COLLAPSIBLE)
         .build(|| {
             if let Some(tex_id) = state.nes_texture_id {
-                Image::new(tex_id, PPU_SCREEN_SIZE).build(ui);
+                Image::new(tex_id, screen_size).build(ui);
             } else {
                 ui.text("DA MONKE AR WORG");
             }
@@ -1,32 +1,10 @@
-use crate::{
-    cartridge::Cartridge,
-    instructions::{
-        instruction::AddressingMode, 
-        instruction::AddressingMode::*,
-        instruction::INSTRUCTION_LOOKUP,
-    },
+use crate::cartridge::Cartridge;
+use cpu6502::instructions::instruction::{
+    AddressingMode,
+    AddressingMode::*,
+    INSTRUCTION_LOOKUP,
 };
-use std::{cell::RefCell, collections::HashMap, fmt, rc::Rc};
-
-impl fmt::Display for AddressingMode {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            IMP => write!(f, "IMP"),
-            IMM => write!(f, "IMM"),
-            ZP0 => write!(f, "ZP0"),
-            ZPX => write!(f, "ZPX"),
-            ZPY => write!(f, "ZPY"),
-            REL => write!(f, "REL"),
-            ABS => write!(f, "ABS"),
-            ABX => write!(f, "ABX"),
-            ABY => write!(f, "ABY"),
-            IND => write!(f, "IND"),
-            IZX => write!(f, "IZX"),
-            IZY => write!(f, "IZY"),
-            YYY => write!(f, "XXX"),
-        }
-    }
-}
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
 #[allow(unused)]
 pub fn decode_bytes_used(ins: AddressingMode) -> usize {
@@ -74,7 +52,7 @@ pub fn disassemble_rom(
     stop: u16,
     cart_rc: Rc<RefCell<Cartridge>>,
 ) -> HashMap<u16, String> {
-    let cart = cart_rc.borrow_mut();
+    let cart = cart_rc.borrow();
     let mut addr: u32 = start as u32;
     let (mut value, mut lo, mut hi): (u8, u8, u8);
 
@@ -84,7 +62,7 @@ pub fn disassemble_rom(
     while addr <= (stop as u32) {
         line_addr = addr as u16;
         let mut instruction_string = format!("$0x{:04X?} -> ", addr);
-        let opcode = cart.cpu_read(addr as u16).unwrap_or(0x00);
+        let opcode = cart.cpu_peek(addr as u16).unwrap_or(0x00);
         addr += 1;
         instruction_string
             .push_str(format!("{} ", INSTRUCTION_LOOKUP[opcode as usize].name).as_str());
@@ -96,16 +74,16 @@ pub fn disassemble_rom(
                 instruction_string.push_str(format!("               {{{}}}", addr_mode).as_str());
             }
             IMM | ZP0 | ZPX | ZPY | IZX | IZY => {
-                if let Ok(v) = cart.cpu_read(addr as u16) {
+                if let Some(v) = cart.cpu_peek(addr as u16) {
                     instruction_string
                         .push_str(format!("#${:02X?}           {{{}}}", v, addr_mode).as_str());
                 }
                 addr += 1;
             }
             ABS | ABX | ABY | IND => {
-                lo = cart.cpu_read(addr as u16).unwrap();
+                lo = cart.cpu_peek(addr as u16).unwrap_or(0x00);
                 addr += 1;
-                hi = cart.cpu_read(addr as u16).unwrap();
+                hi = cart.cpu_peek(addr as u16).unwrap_or(0x00);
                 addr += 1;
                 instruction_string.push_str(
                     format!(
@@ -117,7 +95,7 @@ pub fn disassemble_rom(
                 );
             }
             REL => {
-                if let Ok(value) = cart.cpu_read(addr as u16) {
+                if let Some(value) = cart.cpu_peek(addr as u16) {
                     addr += 1;
                     let (rel, _) = (addr as u16).overflowing_sub((value as u16) & 0xFF00);
                     instruction_string.push_str(
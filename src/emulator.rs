@@ -1,5 +1,5 @@
 use crate::consts::{
-    emulation_consts::*,
+    debug_consts::{PPU_SCREEN_X_BASE_SIZE, PPU_SCREEN_Y_BASE_SIZE},
     emulation_consts::{CLIENT_FORMAT, COLOR_CHANNELS},
     ppu_consts::SPR_PATTERN_TABLE_SIZE,
     render_consts::*,
@@ -44,21 +44,33 @@ pub struct EmulationState {
     pub last_frame_time: Instant,
     pub cycles: usize,
     pub watch_addr: u16,
+    pub scale: f32,
+    /// Why the last attempt to start a trace failed, shown in the emulation control window
+    pub trace_error: Option<String>,
 }
 
 impl EmulationState {
-    pub fn new() -> EmulationState {
+    pub fn new(frame_sync: FrameSync, scale: f32) -> EmulationState {
         EmulationState {
             nes_texture_id: None,
-            frame_sync: EMU_START_STATE,
+            frame_sync,
             palette_id: 0,
             debug_textures: None,
             last_frame_time: Instant::now(),
             cycles: 0,
             watch_addr: 0x0000,
+            scale,
+            trace_error: None,
         }
     }
 
+    pub fn screen_size(&self) -> [f32; 2] {
+        [
+            PPU_SCREEN_X_BASE_SIZE * self.scale,
+            PPU_SCREEN_Y_BASE_SIZE * self.scale,
+        ]
+    }
+
     fn reset(&mut self, nes: &mut Nes) {
         self.cycles = 0;
         self.watch_addr = 0;
@@ -1,27 +1,55 @@
 // #![allow(unused)]
 
+mod args;
 mod audio;
 mod bus;
 mod cartridge;
 mod consts;
-mod cpu;
 mod debug;
 mod disassembler;
 mod emulator;
-mod instructions;
 mod mapper;
 mod nes;
 mod ppu;
 mod renderer;
+mod trace;
 
+#[cfg(test)]
+mod cpu_tests;
+
+use args::{Args, USAGE};
 use glium::{backend::Facade};
 use nes::Nes;
 use renderer::*;
 
 fn main() {
-    let main_nes: Nes = Nes::new();
+    let args = match Args::parse() {
+        Ok(args) => args,
+        Err(e) => {
+            if !e.is_empty() {
+                eprintln!("{}\n", e);
+            }
+            eprintln!("{}", USAGE);
+            std::process::exit(1);
+        }
+    };
+
+    let mut main_nes: Nes = match Nes::from_file(args.rom.clone(), args.region) {
+        Ok(nes) => nes,
+        Err(e) => {
+            eprintln!("Could not load \"{}\": {}", args.rom, e);
+            std::process::exit(1);
+        }
+    };
+    main_nes.set_sample_rate(args.sample_rate);
+    if let Some(path) = &args.trace {
+        if let Err(e) = main_nes.start_trace(path, args.trace_filter.clone()) {
+            eprintln!("Could not create trace file \"{}\": {}", path, e);
+            std::process::exit(1);
+        }
+    }
     let mut system = init();
-    let mut emulation_state = emulator::EmulationState::new();
+    let mut emulation_state = emulator::EmulationState::new(args.start_state, args.scale);
     emulation_state
         .register_textures(system.display.get_context(), system.renderer.textures())
         .expect("Failed to register textures.");
@@ -0,0 +1,56 @@
+use super::{MappedAddr, MapperTrait};
+use crate::cartridge::MIRROR;
+
+// https://www.nesdev.org/wiki/NROM
+//
+// No registers, 16k roms are mirrored into both halves of $8000-$FFFF.
+
+pub struct Mapper000 {
+    prg_rom_size: usize,
+    prg_ram_size: usize,
+    mirror: MIRROR,
+}
+
+impl Mapper000 {
+    pub fn new(prg_rom_size: usize, prg_ram_size: usize, mirror: MIRROR) -> Self {
+        Self {
+            prg_rom_size,
+            prg_ram_size,
+            mirror,
+        }
+    }
+
+    fn map_prg(&self, addr: u16) -> Option<MappedAddr> {
+        if (0x6000..=0x7FFF).contains(&addr) && self.prg_ram_size > 0 {
+            return Some(MappedAddr::PrgRam(((addr & 0x1FFF) as usize % self.prg_ram_size) as u32));
+        }
+        if (0x8000..=0xFFFF).contains(&addr) {
+            return Some(MappedAddr::PrgRom(
+                (addr & (if self.prg_rom_size > 0x4000 { 0x7FFF } else { 0x3FFF })) as u32,
+            ));
+        }
+        None
+    }
+}
+
+impl MapperTrait for Mapper000 {
+    fn cpu_map_peek(&self, addr: u16) -> Option<MappedAddr> {
+        self.map_prg(addr)
+    }
+    fn cpu_map_write(&mut self, addr: u16, _data: u8) -> Option<MappedAddr> {
+        self.map_prg(addr)
+    }
+    fn ppu_map_read(&self, addr: u16) -> Option<u32> {
+        if (0x0000..=0x1FFF).contains(&addr) {
+            return Some(addr as u32);
+        }
+        None
+    }
+    fn ppu_map_write(&self, addr: u16) -> Option<u32> {
+        // Writes to chr rom get dropped by the cartridge
+        self.ppu_map_read(addr)
+    }
+    fn mirror(&self) -> MIRROR {
+        self.mirror
+    }
+}
@@ -0,0 +1,173 @@
+use super::{MappedAddr, MapperTrait};
+use crate::cartridge::MIRROR;
+
+// https://www.nesdev.org/wiki/MMC1
+//
+// Registers are written one bit at a time through a 5 bit shift register,
+// the 5th write copies it into the register picked by bits 13-14 of the address.
+//
+// $8000-$9FFF  Control       ---CPPMM  C: chr mode, P: prg mode, M: mirroring
+// $A000-$BFFF  CHR bank 0    ---CCCCC
+// $C000-$DFFF  CHR bank 1    ---CCCCC
+// $E000-$FFFF  PRG bank      ---RPPPP  R: prg ram disable
+
+const SHIFT_RESET: u8 = 0b1_0000;
+
+pub struct Mapper001 {
+    prg_rom_size: usize,
+    chr_size: usize,
+    prg_ram_size: usize,
+
+    shift_register: u8,
+    control: u8,
+    chr_bank_0: u8,
+    chr_bank_1: u8,
+    prg_bank: u8,
+}
+
+impl Mapper001 {
+    pub fn new(prg_rom_size: usize, chr_size: usize, prg_ram_size: usize) -> Self {
+        Self {
+            prg_rom_size,
+            chr_size,
+            prg_ram_size,
+            shift_register: SHIFT_RESET,
+            // Power on in prg mode 3 so the reset vector is in the last bank
+            control: 0x0C,
+            chr_bank_0: 0,
+            chr_bank_1: 0,
+            prg_bank: 0,
+        }
+    }
+
+    fn prg_ram_enabled(&self) -> bool {
+        self.prg_bank & 0x10 == 0 && self.prg_ram_size > 0
+    }
+
+    // SUROM and friends use bit 4 of the chr bank to pick which 256k half of prg is in use
+    fn prg_outer_bank(&self) -> usize {
+        if self.prg_rom_size > 0x40000 {
+            ((self.chr_bank_0 & 0x10) as usize) << 14
+        } else {
+            0
+        }
+    }
+
+    fn write_register(&mut self, addr: u16, data: u8) {
+        match addr {
+            0x8000..=0x9FFF => self.control = data,
+            0xA000..=0xBFFF => self.chr_bank_0 = data,
+            0xC000..=0xDFFF => self.chr_bank_1 = data,
+            0xE000..=0xFFFF => self.prg_bank = data,
+            _ => {}
+        }
+    }
+}
+
+impl MapperTrait for Mapper001 {
+    fn cpu_map_peek(&self, addr: u16) -> Option<MappedAddr> {
+        match addr {
+            0x6000..=0x7FFF if self.prg_ram_enabled() => Some(MappedAddr::PrgRam(
+                ((addr & 0x1FFF) as usize % self.prg_ram_size) as u32,
+            )),
+            0x8000..=0xFFFF => {
+                let bank = (self.prg_bank & 0x0F) as usize;
+                let last_bank = (0x40000.min(self.prg_rom_size) / 0x4000).saturating_sub(1);
+                let offset = (addr & 0x3FFF) as usize;
+                let bank = match ((self.control >> 2) & 0x03, addr) {
+                    // 32k mode, low bit of the bank is ignored
+                    (0 | 1, 0x8000..=0xBFFF) => bank & 0x0E,
+                    (0 | 1, _) => (bank & 0x0E) | 1,
+                    // First bank fixed at $8000, switch $C000
+                    (2, 0x8000..=0xBFFF) => 0,
+                    (2, _) => bank,
+                    // Switch $8000, last bank fixed at $C000
+                    (_, 0x8000..=0xBFFF) => bank,
+                    (_, _) => last_bank,
+                };
+                let mapped = self.prg_outer_bank() + bank * 0x4000 + offset;
+                Some(MappedAddr::PrgRom((mapped % self.prg_rom_size.max(1)) as u32))
+            }
+            _ => None,
+        }
+    }
+
+    fn cpu_map_write(&mut self, addr: u16, data: u8) -> Option<MappedAddr> {
+        match addr {
+            0x6000..=0x7FFF if self.prg_ram_enabled() => Some(MappedAddr::PrgRam(
+                ((addr & 0x1FFF) as usize % self.prg_ram_size) as u32,
+            )),
+            0x8000..=0xFFFF => {
+                if data & 0x80 > 0 {
+                    // Reset the shift register and lock prg into mode 3
+                    self.shift_register = SHIFT_RESET;
+                    self.control |= 0x0C;
+                } else {
+                    // The 1 we started with falls out of bit 0 on the 5th write
+                    let full = self.shift_register & 0x01 > 0;
+                    self.shift_register = (self.shift_register >> 1) | ((data & 0x01) << 4);
+                    if full {
+                        self.write_register(addr, self.shift_register);
+                        self.shift_register = SHIFT_RESET;
+                    }
+                }
+                Some(MappedAddr::Handled)
+            }
+            _ => None,
+        }
+    }
+
+    fn ppu_map_read(&self, addr: u16) -> Option<u32> {
+        if addr > 0x1FFF {
+            return None;
+        }
+        let offset = (addr & 0x0FFF) as usize;
+        let mapped = if self.control & 0x10 == 0 {
+            // 8k mode, low bit ignored
+            ((self.chr_bank_0 & 0x1E) as usize) * 0x1000 + (addr as usize)
+        } else if addr < 0x1000 {
+            (self.chr_bank_0 as usize) * 0x1000 + offset
+        } else {
+            (self.chr_bank_1 as usize) * 0x1000 + offset
+        };
+        Some((mapped % self.chr_size.max(1)) as u32)
+    }
+
+    fn ppu_map_write(&self, addr: u16) -> Option<u32> {
+        // Writes to chr rom get dropped by the cartridge
+        self.ppu_map_read(addr)
+    }
+
+    fn mirror(&self) -> MIRROR {
+        match self.control & 0x03 {
+            0 => MIRROR::OnescreenLo,
+            1 => MIRROR::OnescreenHi,
+            2 => MIRROR::VERTICAL,
+            _ => MIRROR::HORIZONTAL,
+        }
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        vec![
+            self.shift_register,
+            self.control,
+            self.chr_bank_0,
+            self.chr_bank_1,
+            self.prg_bank,
+        ]
+    }
+
+    fn load_state(&mut self, state: &[u8]) -> bool {
+        match *state {
+            [shift_register, control, chr_bank_0, chr_bank_1, prg_bank] => {
+                self.shift_register = shift_register;
+                self.control = control;
+                self.chr_bank_0 = chr_bank_0;
+                self.chr_bank_1 = chr_bank_1;
+                self.prg_bank = prg_bank;
+                true
+            }
+            _ => false,
+        }
+    }
+}
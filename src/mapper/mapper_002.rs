@@ -0,0 +1,77 @@
+use super::{MappedAddr, MapperTrait};
+use crate::cartridge::MIRROR;
+
+// https://www.nesdev.org/wiki/UxROM
+//
+// Any write to $8000-$FFFF picks the 16k bank at $8000, $C000 is fixed to the last bank.
+
+pub struct Mapper002 {
+    prg_rom_size: usize,
+    bus_conflicts: bool,
+    mirror: MIRROR,
+    prg_bank: u8,
+}
+
+impl Mapper002 {
+    pub fn new(prg_rom_size: usize, mirror: MIRROR, bus_conflicts: bool) -> Self {
+        Self {
+            prg_rom_size,
+            bus_conflicts,
+            mirror,
+            prg_bank: 0,
+        }
+    }
+}
+
+impl MapperTrait for Mapper002 {
+    fn cpu_map_peek(&self, addr: u16) -> Option<MappedAddr> {
+        let bank_count = (self.prg_rom_size / 0x4000).max(1);
+        let bank = match addr {
+            0x8000..=0xBFFF => self.prg_bank as usize % bank_count,
+            0xC000..=0xFFFF => bank_count - 1,
+            _ => return None,
+        };
+        Some(MappedAddr::PrgRom((bank * 0x4000 + (addr & 0x3FFF) as usize) as u32))
+    }
+
+    fn cpu_map_write(&mut self, addr: u16, data: u8) -> Option<MappedAddr> {
+        if addr < 0x8000 {
+            return None;
+        }
+        self.prg_bank = data;
+        Some(MappedAddr::Handled)
+    }
+
+    fn ppu_map_read(&self, addr: u16) -> Option<u32> {
+        if addr > 0x1FFF {
+            return None;
+        }
+        Some(addr as u32)
+    }
+
+    fn ppu_map_write(&self, addr: u16) -> Option<u32> {
+        self.ppu_map_read(addr)
+    }
+
+    fn mirror(&self) -> MIRROR {
+        self.mirror
+    }
+
+    fn bus_conflicts(&self) -> bool {
+        self.bus_conflicts
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        vec![self.prg_bank]
+    }
+
+    fn load_state(&mut self, state: &[u8]) -> bool {
+        match *state {
+            [prg_bank] => {
+                self.prg_bank = prg_bank;
+                true
+            }
+            _ => false,
+        }
+    }
+}
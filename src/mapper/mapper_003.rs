@@ -0,0 +1,80 @@
+use super::{MappedAddr, MapperTrait};
+use crate::cartridge::MIRROR;
+
+// https://www.nesdev.org/wiki/CNROM
+//
+// Prg is fixed like NROM, any write to $8000-$FFFF picks the 8k chr bank.
+
+pub struct Mapper003 {
+    prg_rom_size: usize,
+    chr_size: usize,
+    bus_conflicts: bool,
+    mirror: MIRROR,
+    chr_bank: u8,
+}
+
+impl Mapper003 {
+    pub fn new(prg_rom_size: usize, chr_size: usize, mirror: MIRROR, bus_conflicts: bool) -> Self {
+        Self {
+            prg_rom_size,
+            chr_size,
+            bus_conflicts,
+            mirror,
+            chr_bank: 0,
+        }
+    }
+}
+
+impl MapperTrait for Mapper003 {
+    fn cpu_map_peek(&self, addr: u16) -> Option<MappedAddr> {
+        if addr < 0x8000 {
+            return None;
+        }
+        // 16k roms are mirrored into both halves
+        Some(MappedAddr::PrgRom(
+            ((addr & 0x7FFF) as usize % self.prg_rom_size.max(1)) as u32,
+        ))
+    }
+
+    fn cpu_map_write(&mut self, addr: u16, data: u8) -> Option<MappedAddr> {
+        if addr < 0x8000 {
+            return None;
+        }
+        self.chr_bank = data;
+        Some(MappedAddr::Handled)
+    }
+
+    fn ppu_map_read(&self, addr: u16) -> Option<u32> {
+        if addr > 0x1FFF {
+            return None;
+        }
+        let mapped = (self.chr_bank as usize) * 0x2000 + addr as usize;
+        Some((mapped % self.chr_size.max(1)) as u32)
+    }
+
+    fn ppu_map_write(&self, addr: u16) -> Option<u32> {
+        self.ppu_map_read(addr)
+    }
+
+    fn mirror(&self) -> MIRROR {
+        self.mirror
+    }
+
+    fn bus_conflicts(&self) -> bool {
+        self.bus_conflicts
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        vec![self.chr_bank]
+    }
+
+    fn load_state(&mut self, state: &[u8]) -> bool {
+        match *state {
+            [chr_bank] => {
+                self.chr_bank = chr_bank;
+                true
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use super::{MappedAddr, MapperTrait};
+use crate::cartridge::MIRROR;
+
+// https://www.nesdev.org/wiki/MMC3
+//
+// Every register pair is picked by the address range and whether the address is even or odd.
+//
+// $8000 even  Bank select      CPMx xRRR  C: chr A12 inversion, P: prg mode, R: register
+// $8001 odd   Bank data        value for R0-R7
+// $A000 even  Mirroring        0: vertical, 1: horizontal
+// $A001 odd   PRG-RAM protect  EWxx xxxx  E: enable, W: deny writes
+// $C000 even  IRQ latch
+// $C001 odd   IRQ reload
+// $E000 even  IRQ disable (and acknowledge)
+// $E001 odd   IRQ enable
+
+/// How many cpu cycles A12 has to stay low before a rise counts as a new scanline
+const A12_FILTER_CYCLES: u8 = 3;
+
+pub struct Mapper004 {
+    prg_rom_size: usize,
+    chr_size: usize,
+    prg_ram_size: usize,
+    four_screen: bool,
+    header_mirror: MIRROR,
+
+    bank_select: u8,
+    registers: [u8; 8],
+    mirroring: u8,
+    prg_ram_protect: u8,
+
+    irq_latch: u8,
+    irq_counter: u8,
+    irq_reload: bool,
+    irq_enabled: bool,
+    irq_pending: bool,
+
+    a12_high: bool,
+    a12_low_cycles: u8,
+}
+
+impl Mapper004 {
+    pub fn new(
+        prg_rom_size: usize,
+        chr_size: usize,
+        prg_ram_size: usize,
+        four_screen: bool,
+        header_mirror: MIRROR,
+    ) -> Self {
+        Self {
+            prg_rom_size,
+            chr_size,
+            prg_ram_size,
+            four_screen,
+            header_mirror,
+            bank_select: 0,
+            registers: [0, 2, 4, 5, 6, 7, 0, 1],
+            mirroring: 0,
+            prg_ram_protect: 0x80,
+            irq_latch: 0,
+            irq_counter: 0,
+            irq_reload: false,
+            irq_enabled: false,
+            irq_pending: false,
+            a12_high: false,
+            a12_low_cycles: 0,
+        }
+    }
+
+    fn prg_ram_addr(&self, addr: u16) -> MappedAddr {
+        MappedAddr::PrgRam(((addr & 0x1FFF) as usize % self.prg_ram_size) as u32)
+    }
+
+    fn prg_ram_enabled(&self) -> bool {
+        self.prg_ram_protect & 0x80 > 0 && self.prg_ram_size > 0
+    }
+
+    fn prg_ram_writable(&self) -> bool {
+        self.prg_ram_enabled() && self.prg_ram_protect & 0x40 == 0
+    }
+
+    fn map_prg_rom(&self, addr: u16) -> MappedAddr {
+        let bank_count = (self.prg_rom_size / 0x2000).max(1);
+        let second_last = bank_count.saturating_sub(2);
+        let prg_mode = self.bank_select & 0x40 > 0;
+        let bank = match (addr, prg_mode) {
+            (0x8000..=0x9FFF, false) => self.registers[6] as usize,
+            (0x8000..=0x9FFF, true) => second_last,
+            (0xA000..=0xBFFF, _) => self.registers[7] as usize,
+            (0xC000..=0xDFFF, false) => second_last,
+            (0xC000..=0xDFFF, true) => self.registers[6] as usize,
+            _ => bank_count - 1,
+        };
+        let mapped = (bank % bank_count) * 0x2000 + (addr & 0x1FFF) as usize;
+        MappedAddr::PrgRom(mapped as u32)
+    }
+
+    // 2 2k banks and 4 1k banks, the inversion bit swaps which half gets which
+    fn map_chr(&self, addr: u16) -> u32 {
+        let addr = if self.bank_select & 0x80 > 0 {
+            addr ^ 0x1000
+        } else {
+            addr
+        };
+        let bank_1k = match addr {
+            0x0000..=0x07FF => (self.registers[0] & 0xFE) as usize + ((addr >> 10) & 0x01) as usize,
+            0x0800..=0x0FFF => (self.registers[1] & 0xFE) as usize + ((addr >> 10) & 0x01) as usize,
+            0x1000..=0x13FF => self.registers[2] as usize,
+            0x1400..=0x17FF => self.registers[3] as usize,
+            0x1800..=0x1BFF => self.registers[4] as usize,
+            _ => self.registers[5] as usize,
+        };
+        let mapped = bank_1k * 0x0400 + (addr & 0x03FF) as usize;
+        (mapped % self.chr_size.max(1)) as u32
+    }
+
+    fn clock_irq_counter(&mut self) {
+        if self.irq_counter == 0 || self.irq_reload {
+            self.irq_counter = self.irq_latch;
+            self.irq_reload = false;
+        } else {
+            self.irq_counter -= 1;
+        }
+        if self.irq_counter == 0 && self.irq_enabled {
+            self.irq_pending = true;
+        }
+    }
+}
+
+impl MapperTrait for Mapper004 {
+    fn cpu_map_peek(&self, addr: u16) -> Option<MappedAddr> {
+        match addr {
+            0x6000..=0x7FFF if self.prg_ram_enabled() => Some(self.prg_ram_addr(addr)),
+            0x8000..=0xFFFF => Some(self.map_prg_rom(addr)),
+            _ => None,
+        }
+    }
+
+    fn cpu_map_write(&mut self, addr: u16, data: u8) -> Option<MappedAddr> {
+        let even = addr & 0x01 == 0;
+        match addr {
+            0x6000..=0x7FFF if self.prg_ram_writable() => return Some(self.prg_ram_addr(addr)),
+            // Write protected, nothing else lives here so just drop it
+            0x6000..=0x7FFF if self.prg_ram_enabled() => return Some(MappedAddr::Handled),
+            0x8000..=0x9FFF if even => self.bank_select = data,
+            0x8000..=0x9FFF => self.registers[(self.bank_select & 0x07) as usize] = data,
+            0xA000..=0xBFFF if even => self.mirroring = data & 0x01,
+            0xA000..=0xBFFF => self.prg_ram_protect = data,
+            0xC000..=0xDFFF if even => self.irq_latch = data,
+            0xC000..=0xDFFF => {
+                self.irq_counter = 0;
+                self.irq_reload = true;
+            }
+            0xE000..=0xFFFF if even => {
+                self.irq_enabled = false;
+                self.irq_pending = false;
+            }
+            0xE000..=0xFFFF => self.irq_enabled = true,
+            _ => return None,
+        }
+        Some(MappedAddr::Handled)
+    }
+
+    fn ppu_map_read(&self, addr: u16) -> Option<u32> {
+        if addr > 0x1FFF {
+            return None;
+        }
+        Some(self.map_chr(addr))
+    }
+
+    fn ppu_map_write(&self, addr: u16) -> Option<u32> {
+        // Writes to chr rom get dropped by the cartridge
+        self.ppu_map_read(addr)
+    }
+
+    fn mirror(&self) -> MIRROR {
+        // Four screen boards have their own vram and ignore $A000
+        match (self.four_screen, self.mirroring) {
+            (true, _) => self.header_mirror,
+            (false, 0) => MIRROR::VERTICAL,
+            (false, _) => MIRROR::HORIZONTAL,
+        }
+    }
+
+    fn ppu_address(&mut self, addr: u16) {
+        let a12 = addr & 0x1000 > 0;
+        if a12 && !self.a12_high && self.a12_low_cycles >= A12_FILTER_CYCLES {
+            self.clock_irq_counter();
+        }
+        if !a12 && self.a12_high {
+            self.a12_low_cycles = 0;
+        }
+        self.a12_high = a12;
+    }
+
+    fn cpu_clock(&mut self) {
+        if !self.a12_high {
+            self.a12_low_cycles = self.a12_low_cycles.saturating_add(1);
+        }
+    }
+
+    fn irq_state(&self) -> bool {
+        self.irq_pending
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        let mut state = vec![self.bank_select];
+        state.extend_from_slice(&self.registers);
+        state.extend_from_slice(&[
+            self.mirroring,
+            self.prg_ram_protect,
+            self.irq_latch,
+            self.irq_counter,
+            self.irq_reload as u8,
+            self.irq_enabled as u8,
+            self.irq_pending as u8,
+            self.a12_high as u8,
+            self.a12_low_cycles,
+        ]);
+        state
+    }
+
+    fn load_state(&mut self, state: &[u8]) -> bool {
+        if state.len() != 18 {
+            return false;
+        }
+        self.bank_select = state[0];
+        self.registers.copy_from_slice(&state[1..9]);
+        self.mirroring = state[9];
+        self.prg_ram_protect = state[10];
+        self.irq_latch = state[11];
+        self.irq_counter = state[12];
+        self.irq_reload = state[13] > 0;
+        self.irq_enabled = state[14] > 0;
+        self.irq_pending = state[15] > 0;
+        self.a12_high = state[16] > 0;
+        self.a12_low_cycles = state[17];
+        true
+    }
+}
@@ -0,0 +1,470 @@
+use super::{MappedAddr, MapperTrait, NametablePage, PpuData, PpuEvent, PpuFetch};
+use crate::cartridge::MIRROR;
+
+// https://www.nesdev.org/wiki/MMC5
+//
+// $5100        PRG mode           0: 32k, 1: 16k+16k, 2: 16k+8k+8k, 3: 4x8k
+// $5101        CHR mode           0: 8k, 1: 4k, 2: 2k, 3: 1k
+// $5102/$5103  PRG-RAM protect    writable only when they hold 2 and 1
+// $5104        ExRAM mode         0: nametable, 1: extended attributes, 2: ram, 3: read only ram
+// $5105        Nametable mapping  2 bits per nametable, 0/1: ciram, 2: exram, 3: fill mode
+// $5106/$5107  Fill tile / color
+// $5113-$5117  PRG banks          $6000, $8000, $A000, $C000, $E000, bit 7 picks rom over ram
+// $5120-$5127  CHR set A          sprites (and everything in 8x8 mode, see below)
+// $5128-$512B  CHR set B          background in 8x16 mode
+// $5130        CHR upper bits
+// $5200-$5202  Vertical split     control, scroll, chr bank
+// $5203/$5204  Scanline IRQ       target / status
+// $5205/$5206  8x8 multiplier
+// $5C00-$5FFF  ExRAM
+
+const EXRAM_SIZE: usize = 0x400;
+
+pub struct Mapper005 {
+    prg_rom_size: usize,
+    chr_size: usize,
+    prg_ram_size: usize,
+
+    prg_mode: u8,
+    chr_mode: u8,
+    prg_ram_protect: [u8; 2],
+    exram_mode: u8,
+    nametable_mapping: u8,
+    fill_tile: u8,
+    fill_color: u8,
+    prg_ram_bank: u8,
+    prg_banks: [u8; 4],
+    chr_a: [u16; 8],
+    chr_b: [u16; 4],
+    chr_upper: u8,
+    // In 8x8 sprite mode whichever set was written last is used for all fetches
+    last_chr_set_b: bool,
+    large_sprites: bool,
+    exram: [u8; EXRAM_SIZE],
+
+    split_control: u8,
+    split_scroll: u8,
+    split_bank: u8,
+    split_row: usize,
+    next_split_row: usize,
+    split_tile: bool,
+    ex_attribute: u8,
+
+    irq_target: u8,
+    irq_enabled: bool,
+    irq_pending: bool,
+    in_frame: bool,
+    scanline_counter: u8,
+
+    multiplicand: u8,
+    multiplier: u8,
+}
+
+impl Mapper005 {
+    pub fn new(prg_rom_size: usize, chr_size: usize, prg_ram_size: usize) -> Self {
+        Self {
+            prg_rom_size,
+            chr_size,
+            prg_ram_size,
+            prg_mode: 3,
+            chr_mode: 3,
+            prg_ram_protect: [0, 0],
+            exram_mode: 0,
+            nametable_mapping: 0,
+            fill_tile: 0,
+            fill_color: 0,
+            prg_ram_bank: 0,
+            prg_banks: [0xFF; 4],
+            chr_a: [0; 8],
+            chr_b: [0; 4],
+            chr_upper: 0,
+            last_chr_set_b: false,
+            large_sprites: false,
+            exram: [0; EXRAM_SIZE],
+            split_control: 0,
+            split_scroll: 0,
+            split_bank: 0,
+            split_row: 0,
+            next_split_row: 0,
+            split_tile: false,
+            ex_attribute: 0,
+            irq_target: 0,
+            irq_enabled: false,
+            irq_pending: false,
+            in_frame: false,
+            scanline_counter: 0,
+            multiplicand: 0xFF,
+            multiplier: 0xFF,
+        }
+    }
+
+    fn prg_ram_writable(&self) -> bool {
+        self.prg_ram_protect == [0b10, 0b01]
+    }
+
+    /// Bank register (bit 7 set for rom) and bank size for an address in $6000-$FFFF
+    fn prg_bank(&self, addr: u16) -> (u8, usize) {
+        match (self.prg_mode, addr) {
+            (_, 0x6000..=0x7FFF) => (self.prg_ram_bank & 0x7F, 0x2000),
+            (0, _) => (self.prg_banks[3] | 0x80, 0x8000),
+            (1, 0x8000..=0xBFFF) | (2, 0x8000..=0xBFFF) => (self.prg_banks[1], 0x4000),
+            (1, _) => (self.prg_banks[3] | 0x80, 0x4000),
+            (2, 0xC000..=0xDFFF) => (self.prg_banks[2], 0x2000),
+            (_, 0xE000..=0xFFFF) => (self.prg_banks[3] | 0x80, 0x2000),
+            (_, _) => (self.prg_banks[((addr - 0x8000) / 0x2000) as usize], 0x2000),
+        }
+    }
+
+    fn map_prg(&self, addr: u16) -> MappedAddr {
+        let (bank, size) = self.prg_bank(addr);
+        // Bank numbers are always in 8k units, bigger banks ignore the low bits
+        let bank_8k = (bank & 0x7F) as usize & !(size / 0x2000 - 1);
+        let offset = (addr as usize) & (size - 1);
+        if bank & 0x80 > 0 {
+            let mapped = bank_8k * 0x2000 + offset;
+            MappedAddr::PrgRom((mapped % self.prg_rom_size.max(1)) as u32)
+        } else {
+            let mapped = (bank_8k & 0x07) * 0x2000 + offset;
+            MappedAddr::PrgRam((mapped % self.prg_ram_size.max(1)) as u32)
+        }
+    }
+
+    fn chr_addr(&self, addr: u16, set_b: bool) -> u32 {
+        let addr = addr as usize;
+        let (bank, size) = match (self.chr_mode, set_b) {
+            (0, false) => (self.chr_a[7], 0x2000),
+            (0, true) => (self.chr_b[3], 0x2000),
+            (1, false) => (self.chr_a[(addr / 0x1000) * 4 + 3], 0x1000),
+            (1, true) => (self.chr_b[3], 0x1000),
+            (2, false) => (self.chr_a[(addr / 0x0800) * 2 + 1], 0x0800),
+            (2, true) => (self.chr_b[((addr & 0x0FFF) / 0x0800) * 2 + 1], 0x0800),
+            (_, false) => (self.chr_a[addr / 0x0400], 0x0400),
+            (_, true) => (self.chr_b[(addr & 0x0FFF) / 0x0400], 0x0400),
+        };
+        let mapped = (bank as usize) * size + (addr & (size - 1));
+        (mapped % self.chr_size.max(1)) as u32
+    }
+
+    fn in_split(&self, tile: u8) -> bool {
+        if self.split_control & 0x80 == 0 || self.exram_mode > 1 || tile >= 34 {
+            return false;
+        }
+        let threshold = self.split_control & 0x1F;
+        if self.split_control & 0x40 > 0 {
+            tile >= threshold
+        } else {
+            tile < threshold
+        }
+    }
+
+    // Tiles 0 and 1 are fetched on the line before the one they are drawn on
+    fn split_row_for(&self, tile: u8) -> usize {
+        if tile < 2 {
+            self.next_split_row
+        } else {
+            self.split_row
+        }
+    }
+
+    fn status(&self) -> u8 {
+        ((self.irq_pending as u8) << 7) | ((self.in_frame as u8) << 6)
+    }
+}
+
+impl MapperTrait for Mapper005 {
+    fn cpu_map_peek(&self, addr: u16) -> Option<MappedAddr> {
+        match addr {
+            0x5204 => Some(MappedAddr::Data(self.status())),
+            0x5205 => {
+                let product = self.multiplicand as u16 * self.multiplier as u16;
+                Some(MappedAddr::Data(product as u8))
+            }
+            0x5206 => {
+                let product = self.multiplicand as u16 * self.multiplier as u16;
+                Some(MappedAddr::Data((product >> 8) as u8))
+            }
+            // Exram can only be read back in modes 2 and 3
+            0x5C00..=0x5FFF if self.exram_mode > 1 => {
+                Some(MappedAddr::Data(self.exram[(addr - 0x5C00) as usize]))
+            }
+            0x6000..=0x7FFF if self.prg_ram_size > 0 => Some(self.map_prg(addr)),
+            0x8000..=0xFFFF => Some(self.map_prg(addr)),
+            _ => None,
+        }
+    }
+
+    fn cpu_map_read(&mut self, addr: u16) -> Option<MappedAddr> {
+        let mapped = self.cpu_map_peek(addr);
+        // Reading the status acknowledges the irq
+        if addr == 0x5204 {
+            self.irq_pending = false;
+        }
+        mapped
+    }
+
+    fn cpu_map_write(&mut self, addr: u16, data: u8) -> Option<MappedAddr> {
+        match addr {
+            // The board watches the ppu's registers too, but they still need to reach the ppu
+            0x2000..=0x3FFF => {
+                match addr & 0x0007 {
+                    0 => self.large_sprites = data & 0x20 > 0,
+                    1 if data & 0x18 == 0 => self.in_frame = false,
+                    _ => {}
+                }
+                return None;
+            }
+            0x5100 => self.prg_mode = data & 0x03,
+            0x5101 => self.chr_mode = data & 0x03,
+            0x5102 => self.prg_ram_protect[0] = data & 0x03,
+            0x5103 => self.prg_ram_protect[1] = data & 0x03,
+            0x5104 => self.exram_mode = data & 0x03,
+            0x5105 => self.nametable_mapping = data,
+            0x5106 => self.fill_tile = data,
+            0x5107 => self.fill_color = data & 0x03,
+            0x5113 => self.prg_ram_bank = data,
+            0x5114..=0x5117 => self.prg_banks[(addr - 0x5114) as usize] = data,
+            0x5120..=0x5127 => {
+                self.chr_a[(addr - 0x5120) as usize] = data as u16 | ((self.chr_upper as u16) << 8);
+                self.last_chr_set_b = false;
+            }
+            0x5128..=0x512B => {
+                self.chr_b[(addr - 0x5128) as usize] = data as u16 | ((self.chr_upper as u16) << 8);
+                self.last_chr_set_b = true;
+            }
+            0x5130 => self.chr_upper = data & 0x03,
+            0x5200 => self.split_control = data,
+            0x5201 => self.split_scroll = data,
+            0x5202 => self.split_bank = data,
+            0x5203 => self.irq_target = data,
+            0x5204 => self.irq_enabled = data & 0x80 > 0,
+            0x5205 => self.multiplicand = data,
+            0x5206 => self.multiplier = data,
+            0x5C00..=0x5FFF => {
+                let exram_addr = (addr - 0x5C00) as usize;
+                match self.exram_mode {
+                    // Only writable while rendering, otherwise a 0 gets written
+                    0 | 1 => self.exram[exram_addr] = if self.in_frame { data } else { 0 },
+                    2 => self.exram[exram_addr] = data,
+                    _ => {}
+                }
+            }
+            0x6000..=0xFFFF => {
+                return match self.map_prg(addr) {
+                    MappedAddr::PrgRam(a) if self.prg_ram_writable() && self.prg_ram_size > 0 => {
+                        Some(MappedAddr::PrgRam(a))
+                    }
+                    MappedAddr::PrgRom(a) => Some(MappedAddr::PrgRom(a)),
+                    _ => Some(MappedAddr::Handled),
+                };
+            }
+            _ => return None,
+        }
+        Some(MappedAddr::Handled)
+    }
+
+    fn ppu_map_read(&self, addr: u16) -> Option<u32> {
+        if addr > 0x1FFF {
+            return None;
+        }
+        Some(self.chr_addr(addr, self.last_chr_set_b))
+    }
+
+    fn ppu_map_write(&self, addr: u16) -> Option<u32> {
+        // Writes to chr rom get dropped by the cartridge
+        self.ppu_map_read(addr)
+    }
+
+    // Only a guess for anything asking, the ppu goes through nametable_page
+    fn mirror(&self) -> MIRROR {
+        match self.nametable_mapping {
+            0x44 => MIRROR::VERTICAL,
+            0x00 => MIRROR::OnescreenLo,
+            0x55 => MIRROR::OnescreenHi,
+            _ => MIRROR::HORIZONTAL,
+        }
+    }
+
+    fn nametable_page(&self, addr: u16) -> NametablePage {
+        let quadrant = (addr >> 10) & 0x03;
+        match (self.nametable_mapping >> (quadrant * 2)) & 0x03 {
+            0 => NametablePage::Ciram(0),
+            1 => NametablePage::Ciram(1),
+            _ => NametablePage::Mapper,
+        }
+    }
+
+    fn nametable_read(&self, addr: u16) -> u8 {
+        let quadrant = (addr >> 10) & 0x03;
+        let offset = (addr & 0x03FF) as usize;
+        match (self.nametable_mapping >> (quadrant * 2)) & 0x03 {
+            2 if self.exram_mode < 2 => self.exram[offset],
+            2 => 0,
+            // Fill mode, the attribute bytes repeat the color for all 4 quadrants
+            _ if offset < 0x3C0 => self.fill_tile,
+            _ => self.fill_color * 0x55,
+        }
+    }
+
+    fn nametable_write(&mut self, addr: u16, data: u8) {
+        let quadrant = (addr >> 10) & 0x03;
+        if (self.nametable_mapping >> (quadrant * 2)) & 0x03 == 2 && self.exram_mode < 2 {
+            self.exram[(addr & 0x03FF) as usize] = data;
+        }
+    }
+
+    fn ppu_fetch(&mut self, addr: u16, kind: PpuFetch) -> Option<PpuData> {
+        match kind {
+            PpuFetch::Nametable(tile) => {
+                self.split_tile = self.in_split(tile);
+                if self.split_tile {
+                    let row = self.split_row_for(tile);
+                    let index = (row / 8) * 32 + (tile as usize & 0x1F);
+                    return Some(PpuData::Value(self.exram[index]));
+                }
+                self.ex_attribute = self.exram[(addr & 0x03FF) as usize];
+                None
+            }
+            PpuFetch::Attribute(tile) => {
+                // The ppu picks 2 bits out of the byte based on its own scroll,
+                // so hand back the palette in all 4 spots
+                if self.split_tile {
+                    let row = self.split_row_for(tile);
+                    let column = tile as usize & 0x1F;
+                    let attribute = self.exram[0x3C0 + (row / 32) * 8 + column / 4];
+                    let shift = ((row / 16) & 0x01) * 4 + ((column / 2) & 0x01) * 2;
+                    return Some(PpuData::Value(((attribute >> shift) & 0x03) * 0x55));
+                }
+                if self.exram_mode == 1 {
+                    return Some(PpuData::Value((self.ex_attribute >> 6) * 0x55));
+                }
+                None
+            }
+            PpuFetch::BackgroundPattern(tile) => {
+                let mapped = if self.split_tile {
+                    let row = self.split_row_for(tile);
+                    let fine_y = row & 0x07;
+                    (self.split_bank as usize) * 0x1000 + (addr as usize & 0x0FF8) + fine_y
+                } else if self.exram_mode == 1 {
+                    let bank = (self.ex_attribute & 0x3F) as usize | ((self.chr_upper as usize) << 6);
+                    bank * 0x1000 + (addr as usize & 0x0FFF)
+                } else {
+                    let set_b = self.large_sprites || self.last_chr_set_b;
+                    return Some(PpuData::Chr(self.chr_addr(addr, set_b)));
+                };
+                Some(PpuData::Chr((mapped % self.chr_size.max(1)) as u32))
+            }
+            PpuFetch::SpritePattern => {
+                let set_b = !self.large_sprites && self.last_chr_set_b;
+                Some(PpuData::Chr(self.chr_addr(addr, set_b)))
+            }
+        }
+    }
+
+    fn ppu_event(&mut self, event: PpuEvent) {
+        match event {
+            // Pre-render line, nothing to count yet but the split starts over
+            PpuEvent::ScanlineStart(line) if line >= 240 => {
+                self.in_frame = false;
+                self.next_split_row = self.split_scroll as usize % 240;
+            }
+            PpuEvent::ScanlineStart(_) => {
+                if self.in_frame {
+                    self.scanline_counter = self.scanline_counter.wrapping_add(1);
+                    if self.scanline_counter == self.irq_target && self.irq_target != 0 {
+                        self.irq_pending = true;
+                    }
+                } else {
+                    self.in_frame = true;
+                    self.scanline_counter = 0;
+                }
+                self.split_row = self.next_split_row;
+                self.next_split_row = (self.split_row + 1) % 240;
+            }
+            PpuEvent::RenderingStopped => {
+                self.in_frame = false;
+                self.scanline_counter = 0;
+            }
+        }
+    }
+
+    fn irq_state(&self) -> bool {
+        self.irq_pending && self.irq_enabled
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        let mut state = vec![
+            self.prg_mode,
+            self.chr_mode,
+            self.prg_ram_protect[0],
+            self.prg_ram_protect[1],
+            self.exram_mode,
+            self.nametable_mapping,
+            self.fill_tile,
+            self.fill_color,
+            self.prg_ram_bank,
+            self.chr_upper,
+            self.last_chr_set_b as u8,
+            self.large_sprites as u8,
+            self.split_control,
+            self.split_scroll,
+            self.split_bank,
+            self.split_row as u8,
+            self.next_split_row as u8,
+            self.irq_target,
+            self.irq_enabled as u8,
+            self.irq_pending as u8,
+            self.in_frame as u8,
+            self.scanline_counter,
+            self.multiplicand,
+            self.multiplier,
+        ];
+        state.extend_from_slice(&self.prg_banks);
+        for bank in self.chr_a.iter().chain(self.chr_b.iter()) {
+            state.extend_from_slice(&bank.to_le_bytes());
+        }
+        state.extend_from_slice(&self.exram);
+        state
+    }
+
+    fn load_state(&mut self, state: &[u8]) -> bool {
+        if state.len() != 24 + 4 + 12 * 2 + EXRAM_SIZE {
+            return false;
+        }
+        let (registers, rest) = state.split_at(24);
+        self.prg_mode = registers[0];
+        self.chr_mode = registers[1];
+        self.prg_ram_protect = [registers[2], registers[3]];
+        self.exram_mode = registers[4];
+        self.nametable_mapping = registers[5];
+        self.fill_tile = registers[6];
+        self.fill_color = registers[7];
+        self.prg_ram_bank = registers[8];
+        self.chr_upper = registers[9];
+        self.last_chr_set_b = registers[10] > 0;
+        self.large_sprites = registers[11] > 0;
+        self.split_control = registers[12];
+        self.split_scroll = registers[13];
+        self.split_bank = registers[14];
+        self.split_row = registers[15] as usize;
+        self.next_split_row = registers[16] as usize;
+        self.irq_target = registers[17];
+        self.irq_enabled = registers[18] > 0;
+        self.irq_pending = registers[19] > 0;
+        self.in_frame = registers[20] > 0;
+        self.scanline_counter = registers[21];
+        self.multiplicand = registers[22];
+        self.multiplier = registers[23];
+
+        let (prg_banks, rest) = rest.split_at(4);
+        self.prg_banks.copy_from_slice(prg_banks);
+        let (chr_banks, exram) = rest.split_at(12 * 2);
+        let mut banks = chr_banks
+            .chunks(2)
+            .map(|x| u16::from_le_bytes([x[0], x[1]]));
+        for bank in self.chr_a.iter_mut().chain(self.chr_b.iter_mut()) {
+            *bank = banks.next().unwrap_or(0);
+        }
+        self.exram.copy_from_slice(exram);
+        true
+    }
+}
@@ -0,0 +1,77 @@
+use super::{MappedAddr, MapperTrait};
+use crate::cartridge::MIRROR;
+
+// https://www.nesdev.org/wiki/AxROM
+//
+// $8000-$FFFF  ---M-PPP  M: which nametable is used for the whole screen, P: 32k prg bank
+
+pub struct Mapper007 {
+    prg_rom_size: usize,
+    bus_conflicts: bool,
+    register: u8,
+}
+
+impl Mapper007 {
+    pub fn new(prg_rom_size: usize, bus_conflicts: bool) -> Self {
+        Self {
+            prg_rom_size,
+            bus_conflicts,
+            register: 0,
+        }
+    }
+}
+
+impl MapperTrait for Mapper007 {
+    fn cpu_map_peek(&self, addr: u16) -> Option<MappedAddr> {
+        if addr < 0x8000 {
+            return None;
+        }
+        let mapped = ((self.register & 0x07) as usize) * 0x8000 + (addr & 0x7FFF) as usize;
+        Some(MappedAddr::PrgRom((mapped % self.prg_rom_size.max(1)) as u32))
+    }
+
+    fn cpu_map_write(&mut self, addr: u16, data: u8) -> Option<MappedAddr> {
+        if addr < 0x8000 {
+            return None;
+        }
+        self.register = data;
+        Some(MappedAddr::Handled)
+    }
+
+    fn ppu_map_read(&self, addr: u16) -> Option<u32> {
+        if addr > 0x1FFF {
+            return None;
+        }
+        Some(addr as u32)
+    }
+
+    fn ppu_map_write(&self, addr: u16) -> Option<u32> {
+        self.ppu_map_read(addr)
+    }
+
+    fn mirror(&self) -> MIRROR {
+        if self.register & 0x10 > 0 {
+            MIRROR::OnescreenHi
+        } else {
+            MIRROR::OnescreenLo
+        }
+    }
+
+    fn bus_conflicts(&self) -> bool {
+        self.bus_conflicts
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        vec![self.register]
+    }
+
+    fn load_state(&mut self, state: &[u8]) -> bool {
+        match *state {
+            [register] => {
+                self.register = register;
+                true
+            }
+            _ => false,
+        }
+    }
+}
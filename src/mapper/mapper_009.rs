@@ -0,0 +1,126 @@
+use super::{MappedAddr, MapperTrait};
+use crate::cartridge::MIRROR;
+
+// https://www.nesdev.org/wiki/MMC2
+//
+// $A000  8k prg bank at $8000, the last three 8k banks are fixed
+// $B000  4k chr bank at $0000 when latch 0 is $FD
+// $C000  4k chr bank at $0000 when latch 0 is $FE
+// $D000  4k chr bank at $1000 when latch 1 is $FD
+// $E000  4k chr bank at $1000 when latch 1 is $FE
+// $F000  Mirroring, 0: vertical, 1: horizontal
+//
+// The latches flip after the ppu reads the tile $FD or $FE:
+// $0FD8, $0FE8 for latch 0 and $1FD8-$1FDF, $1FE8-$1FEF for latch 1.
+
+pub struct Mapper009 {
+    prg_rom_size: usize,
+    chr_size: usize,
+    prg_bank: u8,
+    // [latch][0 for $FD, 1 for $FE]
+    chr_banks: [[u8; 2]; 2],
+    latches: [usize; 2],
+    mirroring: u8,
+}
+
+impl Mapper009 {
+    pub fn new(prg_rom_size: usize, chr_size: usize) -> Self {
+        Self {
+            prg_rom_size,
+            chr_size,
+            prg_bank: 0,
+            chr_banks: [[0; 2]; 2],
+            latches: [1, 1],
+            mirroring: 0,
+        }
+    }
+}
+
+impl MapperTrait for Mapper009 {
+    fn cpu_map_peek(&self, addr: u16) -> Option<MappedAddr> {
+        if addr < 0x8000 {
+            return None;
+        }
+        let bank_count = (self.prg_rom_size / 0x2000).max(1);
+        let bank = match addr {
+            0x8000..=0x9FFF => self.prg_bank as usize,
+            // The fixed banks are the last three
+            _ => bank_count.saturating_sub(4) + ((addr - 0x8000) / 0x2000) as usize,
+        };
+        let mapped = (bank % bank_count) * 0x2000 + (addr & 0x1FFF) as usize;
+        Some(MappedAddr::PrgRom(mapped as u32))
+    }
+
+    fn cpu_map_write(&mut self, addr: u16, data: u8) -> Option<MappedAddr> {
+        match addr {
+            0xA000..=0xAFFF => self.prg_bank = data & 0x0F,
+            0xB000..=0xBFFF => self.chr_banks[0][0] = data & 0x1F,
+            0xC000..=0xCFFF => self.chr_banks[0][1] = data & 0x1F,
+            0xD000..=0xDFFF => self.chr_banks[1][0] = data & 0x1F,
+            0xE000..=0xEFFF => self.chr_banks[1][1] = data & 0x1F,
+            0xF000..=0xFFFF => self.mirroring = data & 0x01,
+            0x8000..=0x9FFF => {}
+            _ => return None,
+        }
+        Some(MappedAddr::Handled)
+    }
+
+    fn ppu_map_read(&self, addr: u16) -> Option<u32> {
+        if addr > 0x1FFF {
+            return None;
+        }
+        let half = (addr >> 12) as usize;
+        let bank = self.chr_banks[half][self.latches[half]] as usize;
+        let mapped = bank * 0x1000 + (addr & 0x0FFF) as usize;
+        Some((mapped % self.chr_size.max(1)) as u32)
+    }
+
+    fn ppu_map_write(&self, addr: u16) -> Option<u32> {
+        // Writes to chr rom get dropped by the cartridge
+        self.ppu_map_read(addr)
+    }
+
+    fn mirror(&self) -> MIRROR {
+        if self.mirroring == 0 {
+            MIRROR::VERTICAL
+        } else {
+            MIRROR::HORIZONTAL
+        }
+    }
+
+    fn ppu_address(&mut self, addr: u16) {
+        match addr {
+            0x0FD8 => self.latches[0] = 0,
+            0x0FE8 => self.latches[0] = 1,
+            0x1FD8..=0x1FDF => self.latches[1] = 0,
+            0x1FE8..=0x1FEF => self.latches[1] = 1,
+            _ => {}
+        }
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        vec![
+            self.prg_bank,
+            self.chr_banks[0][0],
+            self.chr_banks[0][1],
+            self.chr_banks[1][0],
+            self.chr_banks[1][1],
+            self.latches[0] as u8,
+            self.latches[1] as u8,
+            self.mirroring,
+        ]
+    }
+
+    fn load_state(&mut self, state: &[u8]) -> bool {
+        match *state {
+            [prg_bank, fd_0, fe_0, fd_1, fe_1, latch_0, latch_1, mirroring] => {
+                self.prg_bank = prg_bank;
+                self.chr_banks = [[fd_0, fe_0], [fd_1, fe_1]];
+                self.latches = [(latch_0 & 0x01) as usize, (latch_1 & 0x01) as usize];
+                self.mirroring = mirroring;
+                true
+            }
+            _ => false,
+        }
+    }
+}
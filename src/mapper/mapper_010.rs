@@ -0,0 +1,136 @@
+use super::{MappedAddr, MapperTrait};
+use crate::cartridge::MIRROR;
+
+// https://www.nesdev.org/wiki/MMC4
+//
+// Same chr latches as MMC2 (see mapper_009) but with 16k prg banking and prg ram.
+//
+// $A000  16k prg bank at $8000, the last 16k bank is fixed at $C000
+// $B000  4k chr bank at $0000 when latch 0 is $FD
+// $C000  4k chr bank at $0000 when latch 0 is $FE
+// $D000  4k chr bank at $1000 when latch 1 is $FD
+// $E000  4k chr bank at $1000 when latch 1 is $FE
+// $F000  Mirroring, 0: vertical, 1: horizontal
+//
+// Unlike MMC2 both latches look at a range: $xFD8-$xFDF and $xFE8-$xFEF.
+
+pub struct Mapper010 {
+    prg_rom_size: usize,
+    chr_size: usize,
+    prg_ram_size: usize,
+    prg_bank: u8,
+    // [latch][0 for $FD, 1 for $FE]
+    chr_banks: [[u8; 2]; 2],
+    latches: [usize; 2],
+    mirroring: u8,
+}
+
+impl Mapper010 {
+    pub fn new(prg_rom_size: usize, chr_size: usize, prg_ram_size: usize) -> Self {
+        Self {
+            prg_rom_size,
+            chr_size,
+            prg_ram_size,
+            prg_bank: 0,
+            chr_banks: [[0; 2]; 2],
+            latches: [1, 1],
+            mirroring: 0,
+        }
+    }
+
+    fn prg_ram_addr(&self, addr: u16) -> Option<MappedAddr> {
+        if self.prg_ram_size == 0 {
+            return None;
+        }
+        Some(MappedAddr::PrgRam(((addr & 0x1FFF) as usize % self.prg_ram_size) as u32))
+    }
+}
+
+impl MapperTrait for Mapper010 {
+    fn cpu_map_peek(&self, addr: u16) -> Option<MappedAddr> {
+        let bank_count = (self.prg_rom_size / 0x4000).max(1);
+        let bank = match addr {
+            0x6000..=0x7FFF => return self.prg_ram_addr(addr),
+            0x8000..=0xBFFF => self.prg_bank as usize % bank_count,
+            0xC000..=0xFFFF => bank_count - 1,
+            _ => return None,
+        };
+        Some(MappedAddr::PrgRom((bank * 0x4000 + (addr & 0x3FFF) as usize) as u32))
+    }
+
+    fn cpu_map_write(&mut self, addr: u16, data: u8) -> Option<MappedAddr> {
+        match addr {
+            0x6000..=0x7FFF => return self.prg_ram_addr(addr),
+            0xA000..=0xAFFF => self.prg_bank = data & 0x0F,
+            0xB000..=0xBFFF => self.chr_banks[0][0] = data & 0x1F,
+            0xC000..=0xCFFF => self.chr_banks[0][1] = data & 0x1F,
+            0xD000..=0xDFFF => self.chr_banks[1][0] = data & 0x1F,
+            0xE000..=0xEFFF => self.chr_banks[1][1] = data & 0x1F,
+            0xF000..=0xFFFF => self.mirroring = data & 0x01,
+            0x8000..=0x9FFF => {}
+            _ => return None,
+        }
+        Some(MappedAddr::Handled)
+    }
+
+    fn ppu_map_read(&self, addr: u16) -> Option<u32> {
+        if addr > 0x1FFF {
+            return None;
+        }
+        let half = (addr >> 12) as usize;
+        let bank = self.chr_banks[half][self.latches[half]] as usize;
+        let mapped = bank * 0x1000 + (addr & 0x0FFF) as usize;
+        Some((mapped % self.chr_size.max(1)) as u32)
+    }
+
+    fn ppu_map_write(&self, addr: u16) -> Option<u32> {
+        // Writes to chr rom get dropped by the cartridge
+        self.ppu_map_read(addr)
+    }
+
+    fn mirror(&self) -> MIRROR {
+        if self.mirroring == 0 {
+            MIRROR::VERTICAL
+        } else {
+            MIRROR::HORIZONTAL
+        }
+    }
+
+    fn ppu_address(&mut self, addr: u16) {
+        if addr > 0x1FFF {
+            return;
+        }
+        let half = (addr >> 12) as usize;
+        match addr & 0x0FF8 {
+            0x0FD8 => self.latches[half] = 0,
+            0x0FE8 => self.latches[half] = 1,
+            _ => {}
+        }
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        vec![
+            self.prg_bank,
+            self.chr_banks[0][0],
+            self.chr_banks[0][1],
+            self.chr_banks[1][0],
+            self.chr_banks[1][1],
+            self.latches[0] as u8,
+            self.latches[1] as u8,
+            self.mirroring,
+        ]
+    }
+
+    fn load_state(&mut self, state: &[u8]) -> bool {
+        match *state {
+            [prg_bank, fd_0, fe_0, fd_1, fe_1, latch_0, latch_1, mirroring] => {
+                self.prg_bank = prg_bank;
+                self.chr_banks = [[fd_0, fe_0], [fd_1, fe_1]];
+                self.latches = [(latch_0 & 0x01) as usize, (latch_1 & 0x01) as usize];
+                self.mirroring = mirroring;
+                true
+            }
+            _ => false,
+        }
+    }
+}
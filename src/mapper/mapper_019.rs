@@ -0,0 +1,227 @@
+use super::n163_audio::N163Audio;
+use super::{MappedAddr, MapperTrait, NametablePage};
+use crate::cartridge::MIRROR;
+
+// https://www.nesdev.org/wiki/INES_Mapper_019
+//
+// Namco 163, the nametables can be pointed at chr rom as well as the console's vram.
+// Chr banks $E0-$FF are meant to select vram in the pattern tables too, nothing uses
+// it in practice so the pattern tables always read chr here.
+//
+// $4800-$4FFF  Internal ram data port, see n163_audio.rs
+// $5000-$57FF  IRQ counter low    (readable, writing acknowledges)
+// $5800-$5FFF  IRQ counter high   EHHH HHHH  E: count up every cpu cycle, irq at $7FFF
+// $8000-$BFFF  1k chr banks, one per $800
+// $C000-$DFFF  Nametables, one per $800. $E0 and up pick a vram page, anything lower is chr rom
+// $E000-$E7FF  8k prg at $8000    -SPP PPPP  S: silence audio
+// $E800-$EFFF  8k prg at $A000
+// $F000-$F7FF  8k prg at $C000
+// $F800-$FFFF  Internal ram address  IAAA AAAA  I: increment after every access.
+//              Also the prg ram write protect, $4x enables writes and bits 0-3 lock 2k pages
+
+pub struct Mapper019 {
+    prg_rom_size: usize,
+    chr_size: usize,
+    prg_ram_size: usize,
+
+    chr_banks: [u8; 8],
+    nametable_banks: [u8; 4],
+    prg_banks: [u8; 3],
+    write_protect: u8,
+    ram_addr: u8,
+    irq_counter: u16,
+    irq_pending: bool,
+    audio: N163Audio,
+}
+
+impl Mapper019 {
+    pub fn new(prg_rom_size: usize, chr_size: usize, prg_ram_size: usize) -> Self {
+        Self {
+            prg_rom_size,
+            chr_size,
+            prg_ram_size,
+            chr_banks: [0; 8],
+            nametable_banks: [0xE0, 0xE1, 0xE0, 0xE1],
+            prg_banks: [0; 3],
+            write_protect: 0,
+            ram_addr: 0,
+            irq_counter: 0,
+            irq_pending: false,
+            audio: N163Audio::new(),
+        }
+    }
+
+    fn prg_ram_addr(&self, addr: u16) -> MappedAddr {
+        MappedAddr::PrgRam(((addr & 0x1FFF) as usize % self.prg_ram_size) as u32)
+    }
+
+    fn prg_ram_writable(&self, addr: u16) -> bool {
+        let page = (addr & 0x1FFF) >> 11;
+        self.write_protect & 0xF0 == 0x40 && self.write_protect & (0x01 << page) == 0
+    }
+
+    /// Hands back the internal ram address and moves on if auto increment is set
+    fn next_ram_addr(&mut self) -> u8 {
+        let addr = self.ram_addr;
+        if addr & 0x80 > 0 {
+            self.ram_addr = 0x80 | (addr.wrapping_add(1) & 0x7F);
+        }
+        addr & 0x7F
+    }
+
+    fn audio_enabled(&self) -> bool {
+        self.prg_banks[0] & 0x40 == 0
+    }
+
+    fn chr_addr(&self, bank: u8, addr: u16) -> u32 {
+        let mapped = (bank as usize) * 0x0400 + (addr & 0x03FF) as usize;
+        (mapped % self.chr_size.max(1)) as u32
+    }
+}
+
+impl MapperTrait for Mapper019 {
+    fn cpu_map_peek(&self, addr: u16) -> Option<MappedAddr> {
+        match addr {
+            0x4800..=0x4FFF => Some(MappedAddr::Data(self.audio.read(self.ram_addr & 0x7F))),
+            0x5000..=0x57FF => Some(MappedAddr::Data(self.irq_counter as u8)),
+            0x5800..=0x5FFF => Some(MappedAddr::Data((self.irq_counter >> 8) as u8)),
+            0x6000..=0x7FFF if self.prg_ram_size > 0 => Some(self.prg_ram_addr(addr)),
+            0x8000..=0xFFFF => {
+                let bank_count = (self.prg_rom_size / 0x2000).max(1);
+                let bank = match addr {
+                    0x8000..=0xDFFF => {
+                        (self.prg_banks[((addr - 0x8000) >> 13) as usize] & 0x3F) as usize
+                    }
+                    _ => bank_count - 1,
+                };
+                let mapped = (bank % bank_count) * 0x2000 + (addr & 0x1FFF) as usize;
+                Some(MappedAddr::PrgRom(mapped as u32))
+            }
+            _ => None,
+        }
+    }
+
+    fn cpu_map_read(&mut self, addr: u16) -> Option<MappedAddr> {
+        let mapped = self.cpu_map_peek(addr);
+        if (0x4800..=0x4FFF).contains(&addr) {
+            self.next_ram_addr();
+        }
+        mapped
+    }
+
+    fn cpu_map_write(&mut self, addr: u16, data: u8) -> Option<MappedAddr> {
+        match addr {
+            0x4800..=0x4FFF => {
+                let ram_addr = self.next_ram_addr();
+                self.audio.write(ram_addr, data);
+            }
+            0x5000..=0x57FF => {
+                self.irq_counter = (self.irq_counter & 0xFF00) | data as u16;
+                self.irq_pending = false;
+            }
+            0x5800..=0x5FFF => {
+                self.irq_counter = (self.irq_counter & 0x00FF) | ((data as u16) << 8);
+                self.irq_pending = false;
+            }
+            0x6000..=0x7FFF if self.prg_ram_size > 0 && self.prg_ram_writable(addr) => {
+                return Some(self.prg_ram_addr(addr))
+            }
+            0x6000..=0x7FFF if self.prg_ram_size > 0 => {}
+            0x8000..=0xBFFF => self.chr_banks[((addr - 0x8000) >> 11) as usize] = data,
+            0xC000..=0xDFFF => self.nametable_banks[((addr - 0xC000) >> 11) as usize] = data,
+            0xE000..=0xF7FF => self.prg_banks[((addr - 0xE000) >> 11) as usize] = data,
+            0xF800..=0xFFFF => {
+                self.write_protect = data;
+                self.ram_addr = data;
+            }
+            _ => return None,
+        }
+        Some(MappedAddr::Handled)
+    }
+
+    fn ppu_map_read(&self, addr: u16) -> Option<u32> {
+        match addr {
+            0x0000..=0x1FFF => Some(self.chr_addr(self.chr_banks[(addr >> 10) as usize], addr)),
+            0x2000..=0x3EFF => {
+                let bank = self.nametable_banks[((addr >> 10) & 0x03) as usize];
+                // Vram pages are handed out by nametable_page
+                if bank >= 0xE0 {
+                    None
+                } else {
+                    Some(self.chr_addr(bank, addr))
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn ppu_map_write(&self, addr: u16) -> Option<u32> {
+        // Writes to chr rom get dropped by the cartridge
+        self.ppu_map_read(addr)
+    }
+
+    fn mirror(&self) -> MIRROR {
+        let page = |quadrant: usize| self.nametable_banks[quadrant] & 0x01;
+        match (page(0), page(1), page(2), page(3)) {
+            (0, 1, 0, 1) => MIRROR::VERTICAL,
+            (0, 0, 0, 0) => MIRROR::OnescreenLo,
+            (1, 1, 1, 1) => MIRROR::OnescreenHi,
+            _ => MIRROR::HORIZONTAL,
+        }
+    }
+
+    fn nametable_page(&self, addr: u16) -> NametablePage {
+        let bank = self.nametable_banks[((addr >> 10) & 0x03) as usize];
+        NametablePage::Ciram((bank & 0x01) as usize)
+    }
+
+    fn cpu_clock(&mut self) {
+        if self.irq_counter & 0x8000 > 0 && self.irq_counter & 0x7FFF != 0x7FFF {
+            self.irq_counter += 1;
+            if self.irq_counter & 0x7FFF == 0x7FFF {
+                self.irq_pending = true;
+            }
+        }
+        if self.audio_enabled() {
+            self.audio.clock();
+        }
+    }
+
+    fn irq_state(&self) -> bool {
+        self.irq_pending
+    }
+
+    fn audio_output(&self) -> f32 {
+        if self.audio_enabled() {
+            self.audio.output()
+        } else {
+            0.0
+        }
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        let mut state = self.chr_banks.to_vec();
+        state.extend_from_slice(&self.nametable_banks);
+        state.extend_from_slice(&self.prg_banks);
+        state.extend_from_slice(&[self.write_protect, self.ram_addr]);
+        state.extend_from_slice(&self.irq_counter.to_le_bytes());
+        state.push(self.irq_pending as u8);
+        state.extend_from_slice(&self.audio.save_state());
+        state
+    }
+
+    fn load_state(&mut self, state: &[u8]) -> bool {
+        if state.len() != 20 + N163Audio::STATE_SIZE {
+            return false;
+        }
+        let (registers, audio) = state.split_at(20);
+        self.chr_banks.copy_from_slice(&registers[0..8]);
+        self.nametable_banks.copy_from_slice(&registers[8..12]);
+        self.prg_banks.copy_from_slice(&registers[12..15]);
+        self.write_protect = registers[15];
+        self.ram_addr = registers[16];
+        self.irq_counter = u16::from_le_bytes([registers[17], registers[18]]);
+        self.irq_pending = registers[19] > 0;
+        self.audio.load_state(audio)
+    }
+}
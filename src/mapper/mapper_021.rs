@@ -0,0 +1,211 @@
+use super::vrc_irq::VrcIrq;
+use super::{MappedAddr, MapperTrait};
+use crate::cartridge::MIRROR;
+
+// https://www.nesdev.org/wiki/VRC2_and_VRC4
+//
+// Mappers 21, 22, 23 and 25 are all VRC2 or VRC4, the only difference is which
+// cpu address lines are wired to the chip's A0/A1 register select pins.
+//
+// $8000-$8003  8k prg bank at $8000 (or $C000 in swap mode)
+// $9000        Mirroring, VRC4 has 2 bits for one screen as well
+// $9002        VRC4: prg swap mode in bit 1
+// $A000-$A003  8k prg bank at $A000
+// $B000-$E003  1k chr banks, two per register group: low nibble then high bits
+// $F000-$F003  VRC4: irq latch low, latch high, control, acknowledge
+
+pub struct Mapper021 {
+    prg_rom_size: usize,
+    chr_size: usize,
+    prg_ram_size: usize,
+    // Address lines that end up on A0 and A1
+    a0_lines: u16,
+    a1_lines: u16,
+    vrc4: bool,
+    // VRC2a drops the low bit of every chr bank
+    chr_shift: u8,
+
+    prg_banks: [u8; 2],
+    prg_swap: bool,
+    mirroring: u8,
+    chr_banks: [u16; 8],
+    // VRC2 has a single bit latch at $6000-$6FFF where VRC4 has ram
+    vrc2_latch: u8,
+    irq: VrcIrq,
+}
+
+impl Mapper021 {
+    pub fn new(
+        mapper_id: u16,
+        submapper: u8,
+        prg_rom_size: usize,
+        chr_size: usize,
+        prg_ram_size: usize,
+    ) -> Self {
+        // Each mapper number covers a couple of boards and the submapper says
+        // which. Without one, or-ing their lines together works since a game
+        // only ever uses its own, and VRC2 can't address more than 128k of prg.
+        let vrc4 = prg_rom_size > 0x20000;
+        let (a0_lines, a1_lines, vrc4) = match (mapper_id, submapper) {
+            (21, 1) => (0x02, 0x04, true),
+            (21, 2) => (0x40, 0x80, true),
+            (21, _) => (0x02 | 0x40, 0x04 | 0x80, true),
+            (22, _) => (0x02, 0x01, false),
+            (23, 1) => (0x01, 0x02, true),
+            (23, 2) => (0x04, 0x08, true),
+            (23, 3) => (0x01, 0x02, false),
+            (23, _) => (0x01 | 0x04, 0x02 | 0x08, vrc4),
+            (_, 1) => (0x02, 0x01, true),
+            (_, 2) => (0x08, 0x04, true),
+            (_, 3) => (0x02, 0x01, false),
+            (_, _) => (0x02 | 0x08, 0x01 | 0x04, vrc4),
+        };
+        let chr_shift = if mapper_id == 22 { 1 } else { 0 };
+        Self {
+            prg_rom_size,
+            chr_size,
+            prg_ram_size,
+            a0_lines,
+            a1_lines,
+            vrc4,
+            chr_shift,
+            prg_banks: [0, 0],
+            prg_swap: false,
+            mirroring: 0,
+            chr_banks: [0; 8],
+            vrc2_latch: 0,
+            irq: VrcIrq::new(),
+        }
+    }
+
+    /// Turns a cpu address into $x000-$x003
+    fn register(&self, addr: u16) -> u16 {
+        let a0 = (addr & self.a0_lines > 0) as u16;
+        let a1 = (addr & self.a1_lines > 0) as u16;
+        (addr & 0xF000) | (a1 << 1) | a0
+    }
+
+    fn prg_ram_addr(&self, addr: u16) -> MappedAddr {
+        MappedAddr::PrgRam(((addr & 0x1FFF) as usize % self.prg_ram_size) as u32)
+    }
+
+    fn write_chr_bank(&mut self, register: u16, data: u8) {
+        // $B000/$B001 is bank 0, $B002/$B003 is bank 1, $C000 starts bank 2...
+        let bank = (((register >> 12) - 0xB) * 2 + ((register >> 1) & 0x01)) as usize;
+        let value = self.chr_banks[bank];
+        self.chr_banks[bank] = if register & 0x01 == 0 {
+            (value & 0x1F0) | (data & 0x0F) as u16
+        } else {
+            (value & 0x00F) | (((data & 0x1F) as u16) << 4)
+        };
+    }
+}
+
+impl MapperTrait for Mapper021 {
+    fn cpu_map_peek(&self, addr: u16) -> Option<MappedAddr> {
+        match addr {
+            // iNES headers always claim some ram, VRC2's latch has to win over it
+            0x6000..=0x6FFF if !self.vrc4 => Some(MappedAddr::Data(0x60 | self.vrc2_latch)),
+            0x6000..=0x7FFF if self.prg_ram_size > 0 => Some(self.prg_ram_addr(addr)),
+            0x8000..=0xFFFF => {
+                let bank_count = (self.prg_rom_size / 0x2000).max(1);
+                let second_last = bank_count.saturating_sub(2);
+                let bank = match (addr, self.prg_swap) {
+                    (0x8000..=0x9FFF, false) => self.prg_banks[0] as usize,
+                    (0x8000..=0x9FFF, true) => second_last,
+                    (0xA000..=0xBFFF, _) => self.prg_banks[1] as usize,
+                    (0xC000..=0xDFFF, false) => second_last,
+                    (0xC000..=0xDFFF, true) => self.prg_banks[0] as usize,
+                    _ => bank_count - 1,
+                };
+                let mapped = (bank % bank_count) * 0x2000 + (addr & 0x1FFF) as usize;
+                Some(MappedAddr::PrgRom(mapped as u32))
+            }
+            _ => None,
+        }
+    }
+
+    fn cpu_map_write(&mut self, addr: u16, data: u8) -> Option<MappedAddr> {
+        match addr {
+            0x6000..=0x6FFF if !self.vrc4 => self.vrc2_latch = data & 0x01,
+            0x6000..=0x7FFF if self.prg_ram_size > 0 => return Some(self.prg_ram_addr(addr)),
+            0x8000..=0xFFFF => match self.register(addr) {
+                0x8000..=0x8003 => self.prg_banks[0] = data & 0x1F,
+                0x9000..=0x9001 if self.vrc4 => self.mirroring = data & 0x03,
+                0x9002..=0x9003 if self.vrc4 => self.prg_swap = data & 0x02 > 0,
+                0x9000..=0x9003 => self.mirroring = data & 0x01,
+                0xA000..=0xA003 => self.prg_banks[1] = data & 0x1F,
+                r @ 0xB000..=0xEFFF => self.write_chr_bank(r, data),
+                0xF000 if self.vrc4 => self.irq.write_latch_lo(data),
+                0xF001 if self.vrc4 => self.irq.write_latch_hi(data),
+                0xF002 if self.vrc4 => self.irq.write_control(data),
+                0xF003 if self.vrc4 => self.irq.acknowledge(),
+                _ => {}
+            },
+            _ => return None,
+        }
+        Some(MappedAddr::Handled)
+    }
+
+    fn ppu_map_read(&self, addr: u16) -> Option<u32> {
+        if addr > 0x1FFF {
+            return None;
+        }
+        let bank = (self.chr_banks[(addr >> 10) as usize] >> self.chr_shift) as usize;
+        let mapped = bank * 0x0400 + (addr & 0x03FF) as usize;
+        Some((mapped % self.chr_size.max(1)) as u32)
+    }
+
+    fn ppu_map_write(&self, addr: u16) -> Option<u32> {
+        // Writes to chr rom get dropped by the cartridge
+        self.ppu_map_read(addr)
+    }
+
+    fn mirror(&self) -> MIRROR {
+        match self.mirroring {
+            0 => MIRROR::VERTICAL,
+            1 => MIRROR::HORIZONTAL,
+            2 => MIRROR::OnescreenLo,
+            _ => MIRROR::OnescreenHi,
+        }
+    }
+
+    fn cpu_clock(&mut self) {
+        self.irq.clock();
+    }
+
+    fn irq_state(&self) -> bool {
+        self.irq.pending()
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        let mut state = vec![
+            self.prg_banks[0],
+            self.prg_banks[1],
+            self.prg_swap as u8,
+            self.mirroring,
+            self.vrc2_latch,
+        ];
+        for bank in self.chr_banks.iter() {
+            state.extend_from_slice(&bank.to_le_bytes());
+        }
+        state.extend_from_slice(&self.irq.save_state());
+        state
+    }
+
+    fn load_state(&mut self, state: &[u8]) -> bool {
+        if state.len() != 5 + 8 * 2 + VrcIrq::STATE_SIZE {
+            return false;
+        }
+        let (registers, rest) = state.split_at(5);
+        let (chr_banks, irq) = rest.split_at(8 * 2);
+        self.prg_banks = [registers[0], registers[1]];
+        self.prg_swap = registers[2] > 0;
+        self.mirroring = registers[3];
+        self.vrc2_latch = registers[4];
+        for (bank, x) in self.chr_banks.iter_mut().zip(chr_banks.chunks(2)) {
+            *bank = u16::from_le_bytes([x[0], x[1]]);
+        }
+        self.irq.load_state(irq)
+    }
+}
@@ -0,0 +1,166 @@
+use super::vrc6_audio::Vrc6Audio;
+use super::vrc_irq::VrcIrq;
+use super::{MappedAddr, MapperTrait};
+use crate::cartridge::MIRROR;
+
+// https://www.nesdev.org/wiki/VRC6
+//
+// Mapper 26 is the same board with A0 and A1 swapped.
+//
+// $8000-$8003  16k prg bank at $8000
+// $9000-$B002  Expansion audio, see vrc6_audio.rs
+// $B003        W--- MM--  W: prg ram enable, M: mirroring
+// $C000-$C003  8k prg bank at $C000
+// $D000-$E003  1k chr banks R0-R7
+// $F000        IRQ latch
+// $F001        IRQ control
+// $F002        IRQ acknowledge
+
+pub struct Mapper024 {
+    prg_rom_size: usize,
+    chr_size: usize,
+    prg_ram_size: usize,
+    swap_lines: bool,
+
+    prg_bank_16k: u8,
+    prg_bank_8k: u8,
+    control: u8,
+    chr_banks: [u8; 8],
+    irq: VrcIrq,
+    audio: Vrc6Audio,
+}
+
+impl Mapper024 {
+    pub fn new(prg_rom_size: usize, chr_size: usize, prg_ram_size: usize, swap_lines: bool) -> Self {
+        Self {
+            prg_rom_size,
+            chr_size,
+            prg_ram_size,
+            swap_lines,
+            prg_bank_16k: 0,
+            prg_bank_8k: 0,
+            control: 0,
+            chr_banks: [0; 8],
+            irq: VrcIrq::new(),
+            audio: Vrc6Audio::default(),
+        }
+    }
+
+    /// Turns a cpu address into $x000-$x003
+    fn register(&self, addr: u16) -> u16 {
+        let lines = if self.swap_lines {
+            ((addr & 0x01) << 1) | ((addr & 0x02) >> 1)
+        } else {
+            addr & 0x03
+        };
+        (addr & 0xF000) | lines
+    }
+
+    fn prg_ram_enabled(&self) -> bool {
+        self.control & 0x80 > 0 && self.prg_ram_size > 0
+    }
+
+    fn prg_ram_addr(&self, addr: u16) -> MappedAddr {
+        MappedAddr::PrgRam(((addr & 0x1FFF) as usize % self.prg_ram_size) as u32)
+    }
+}
+
+impl MapperTrait for Mapper024 {
+    fn cpu_map_peek(&self, addr: u16) -> Option<MappedAddr> {
+        match addr {
+            0x6000..=0x7FFF if self.prg_ram_enabled() => Some(self.prg_ram_addr(addr)),
+            0x8000..=0xFFFF => {
+                let bank_count = (self.prg_rom_size / 0x2000).max(1);
+                let bank = match addr {
+                    0x8000..=0xBFFF => {
+                        (self.prg_bank_16k as usize) * 2 + ((addr >> 13) & 0x01) as usize
+                    }
+                    0xC000..=0xDFFF => self.prg_bank_8k as usize,
+                    _ => bank_count - 1,
+                };
+                let mapped = (bank % bank_count) * 0x2000 + (addr & 0x1FFF) as usize;
+                Some(MappedAddr::PrgRom(mapped as u32))
+            }
+            _ => None,
+        }
+    }
+
+    fn cpu_map_write(&mut self, addr: u16, data: u8) -> Option<MappedAddr> {
+        match addr {
+            0x6000..=0x7FFF if self.prg_ram_enabled() => return Some(self.prg_ram_addr(addr)),
+            0x8000..=0xFFFF => match self.register(addr) {
+                0x8000..=0x8003 => self.prg_bank_16k = data & 0x0F,
+                0xB003 => self.control = data,
+                r @ 0x9000..=0xB002 => self.audio.write(r, data),
+                0xC000..=0xC003 => self.prg_bank_8k = data & 0x1F,
+                r @ 0xD000..=0xE003 => {
+                    let bank = (((r >> 12) - 0xD) * 4 + (r & 0x03)) as usize;
+                    self.chr_banks[bank] = data;
+                }
+                0xF000 => self.irq.write_latch(data),
+                0xF001 => self.irq.write_control(data),
+                0xF002 => self.irq.acknowledge(),
+                _ => {}
+            },
+            _ => return None,
+        }
+        Some(MappedAddr::Handled)
+    }
+
+    fn ppu_map_read(&self, addr: u16) -> Option<u32> {
+        if addr > 0x1FFF {
+            return None;
+        }
+        let bank = self.chr_banks[(addr >> 10) as usize] as usize;
+        let mapped = bank * 0x0400 + (addr & 0x03FF) as usize;
+        Some((mapped % self.chr_size.max(1)) as u32)
+    }
+
+    fn ppu_map_write(&self, addr: u16) -> Option<u32> {
+        // Writes to chr rom get dropped by the cartridge
+        self.ppu_map_read(addr)
+    }
+
+    fn mirror(&self) -> MIRROR {
+        match (self.control >> 2) & 0x03 {
+            0 => MIRROR::VERTICAL,
+            1 => MIRROR::HORIZONTAL,
+            2 => MIRROR::OnescreenLo,
+            _ => MIRROR::OnescreenHi,
+        }
+    }
+
+    fn cpu_clock(&mut self) {
+        self.irq.clock();
+        self.audio.clock();
+    }
+
+    fn irq_state(&self) -> bool {
+        self.irq.pending()
+    }
+
+    fn audio_output(&self) -> f32 {
+        self.audio.output()
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        let mut state = vec![self.prg_bank_16k, self.prg_bank_8k, self.control];
+        state.extend_from_slice(&self.chr_banks);
+        state.extend_from_slice(&self.irq.save_state());
+        state.extend_from_slice(&self.audio.save_state());
+        state
+    }
+
+    fn load_state(&mut self, state: &[u8]) -> bool {
+        if state.len() != 11 + VrcIrq::STATE_SIZE + Vrc6Audio::STATE_SIZE {
+            return false;
+        }
+        let (registers, rest) = state.split_at(11);
+        let (irq, audio) = rest.split_at(VrcIrq::STATE_SIZE);
+        self.prg_bank_16k = registers[0];
+        self.prg_bank_8k = registers[1];
+        self.control = registers[2];
+        self.chr_banks.copy_from_slice(&registers[3..11]);
+        self.irq.load_state(irq) && self.audio.load_state(audio)
+    }
+}
@@ -0,0 +1,138 @@
+use super::{MappedAddr, MapperTrait};
+use crate::cartridge::MIRROR;
+
+// Mapper 34 is two unrelated boards that ended up sharing a number.
+//
+// https://www.nesdev.org/wiki/BNROM
+// $8000-$FFFF  32k prg bank, has bus conflicts
+//
+// https://www.nesdev.org/wiki/NINA-001
+// $7FFD  32k prg bank
+// $7FFE  4k chr bank at $0000
+// $7FFF  4k chr bank at $1000
+// The registers sit on top of prg ram so the writes land in ram as well.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Board34 {
+    Bnrom,
+    Nina001,
+}
+
+pub struct Mapper034 {
+    board: Board34,
+    prg_rom_size: usize,
+    chr_size: usize,
+    prg_ram_size: usize,
+    mirror: MIRROR,
+    prg_bank: u8,
+    chr_banks: [u8; 2],
+}
+
+impl Mapper034 {
+    /// Submapper 1 is NINA-001 and 2 is BNROM, old iNES headers leave it at 0
+    /// so fall back to NINA-001 when there is more chr than BNROM can address
+    pub fn new(
+        submapper: u8,
+        prg_rom_size: usize,
+        chr_size: usize,
+        prg_ram_size: usize,
+        mirror: MIRROR,
+    ) -> Self {
+        let board = match submapper {
+            1 => Board34::Nina001,
+            2 => Board34::Bnrom,
+            _ if chr_size > 0x2000 => Board34::Nina001,
+            _ => Board34::Bnrom,
+        };
+        Self {
+            board,
+            prg_rom_size,
+            chr_size,
+            prg_ram_size,
+            mirror,
+            prg_bank: 0,
+            chr_banks: [0, 1],
+        }
+    }
+
+    fn prg_ram_addr(&self, addr: u16) -> Option<MappedAddr> {
+        if self.board == Board34::Nina001 && self.prg_ram_size > 0 {
+            return Some(MappedAddr::PrgRam(((addr & 0x1FFF) as usize % self.prg_ram_size) as u32));
+        }
+        None
+    }
+}
+
+impl MapperTrait for Mapper034 {
+    fn cpu_map_peek(&self, addr: u16) -> Option<MappedAddr> {
+        match addr {
+            0x6000..=0x7FFF => self.prg_ram_addr(addr),
+            0x8000..=0xFFFF => {
+                let mapped = (self.prg_bank as usize) * 0x8000 + (addr & 0x7FFF) as usize;
+                Some(MappedAddr::PrgRom((mapped % self.prg_rom_size.max(1)) as u32))
+            }
+            _ => None,
+        }
+    }
+
+    fn cpu_map_write(&mut self, addr: u16, data: u8) -> Option<MappedAddr> {
+        match (self.board, addr) {
+            (Board34::Bnrom, 0x8000..=0xFFFF) => {
+                self.prg_bank = data;
+                Some(MappedAddr::Handled)
+            }
+            (Board34::Nina001, 0x6000..=0x7FFF) => {
+                match addr {
+                    0x7FFD => self.prg_bank = data & 0x01,
+                    0x7FFE => self.chr_banks[0] = data & 0x0F,
+                    0x7FFF => self.chr_banks[1] = data & 0x0F,
+                    _ => {}
+                }
+                self.prg_ram_addr(addr).or(Some(MappedAddr::Handled))
+            }
+            (_, 0x8000..=0xFFFF) => Some(MappedAddr::Handled),
+            _ => None,
+        }
+    }
+
+    fn ppu_map_read(&self, addr: u16) -> Option<u32> {
+        if addr > 0x1FFF {
+            return None;
+        }
+        let mapped = match self.board {
+            Board34::Bnrom => addr as usize,
+            Board34::Nina001 => {
+                let bank = self.chr_banks[(addr >> 12) as usize] as usize;
+                bank * 0x1000 + (addr & 0x0FFF) as usize
+            }
+        };
+        Some((mapped % self.chr_size.max(1)) as u32)
+    }
+
+    fn ppu_map_write(&self, addr: u16) -> Option<u32> {
+        self.ppu_map_read(addr)
+    }
+
+    fn mirror(&self) -> MIRROR {
+        self.mirror
+    }
+
+    fn bus_conflicts(&self) -> bool {
+        self.board == Board34::Bnrom
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        vec![self.prg_bank, self.chr_banks[0], self.chr_banks[1]]
+    }
+
+    fn load_state(&mut self, state: &[u8]) -> bool {
+        match *state {
+            [prg_bank, chr_bank_0, chr_bank_1] => {
+                self.prg_bank = prg_bank;
+                self.chr_banks = [chr_bank_0, chr_bank_1];
+                true
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use super::{MappedAddr, MapperTrait};
+use crate::cartridge::MIRROR;
+
+// https://www.nesdev.org/wiki/GxROM
+//
+// $8000-$FFFF  --PP--CC  P: 32k prg bank, C: 8k chr bank
+
+pub struct Mapper066 {
+    prg_rom_size: usize,
+    chr_size: usize,
+    mirror: MIRROR,
+    register: u8,
+}
+
+impl Mapper066 {
+    pub fn new(prg_rom_size: usize, chr_size: usize, mirror: MIRROR) -> Self {
+        Self {
+            prg_rom_size,
+            chr_size,
+            mirror,
+            register: 0,
+        }
+    }
+}
+
+impl MapperTrait for Mapper066 {
+    fn cpu_map_peek(&self, addr: u16) -> Option<MappedAddr> {
+        if addr < 0x8000 {
+            return None;
+        }
+        let mapped = (((self.register >> 4) & 0x03) as usize) * 0x8000 + (addr & 0x7FFF) as usize;
+        Some(MappedAddr::PrgRom((mapped % self.prg_rom_size.max(1)) as u32))
+    }
+
+    fn cpu_map_write(&mut self, addr: u16, data: u8) -> Option<MappedAddr> {
+        if addr < 0x8000 {
+            return None;
+        }
+        self.register = data;
+        Some(MappedAddr::Handled)
+    }
+
+    fn ppu_map_read(&self, addr: u16) -> Option<u32> {
+        if addr > 0x1FFF {
+            return None;
+        }
+        let mapped = ((self.register & 0x03) as usize) * 0x2000 + addr as usize;
+        Some((mapped % self.chr_size.max(1)) as u32)
+    }
+
+    fn ppu_map_write(&self, addr: u16) -> Option<u32> {
+        self.ppu_map_read(addr)
+    }
+
+    fn bus_conflicts(&self) -> bool {
+        true
+    }
+
+    fn mirror(&self) -> MIRROR {
+        self.mirror
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        vec![self.register]
+    }
+
+    fn load_state(&mut self, state: &[u8]) -> bool {
+        match *state {
+            [register] => {
+                self.register = register;
+                true
+            }
+            _ => false,
+        }
+    }
+}
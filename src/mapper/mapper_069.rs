@@ -0,0 +1,183 @@
+use super::sunsoft5b_audio::Sunsoft5bAudio;
+use super::{MappedAddr, MapperTrait};
+use crate::cartridge::MIRROR;
+
+// https://www.nesdev.org/wiki/Sunsoft_FME-7
+//
+// Everything goes through a command/parameter pair, the 5B adds its audio on top.
+//
+// $8000-$9FFF  Command    ---- CCCC
+// $A000-$BFFF  Parameter  for the last command
+// $C000-$DFFF  5B audio register select
+// $E000-$FFFF  5B audio register write
+//
+// $0-$7  1k chr banks
+// $8     $6000 bank  ERBB BBBB  E: ram enable, R: ram instead of rom
+// $9-$B  8k prg banks at $8000, $A000, $C000
+// $C     Mirroring   0: vertical, 1: horizontal, 2/3: one screen
+// $D     IRQ control C--- ---T  C: count down every cpu cycle, T: irq when it wraps. Acknowledges
+// $E/$F  IRQ counter low/high
+
+pub struct Mapper069 {
+    prg_rom_size: usize,
+    chr_size: usize,
+    prg_ram_size: usize,
+
+    command: u8,
+    chr_banks: [u8; 8],
+    prg_banks: [u8; 4],
+    mirroring: u8,
+    irq_control: u8,
+    irq_counter: u16,
+    irq_pending: bool,
+    audio: Sunsoft5bAudio,
+}
+
+impl Mapper069 {
+    pub fn new(prg_rom_size: usize, chr_size: usize, prg_ram_size: usize) -> Self {
+        Self {
+            prg_rom_size,
+            chr_size,
+            prg_ram_size,
+            command: 0,
+            chr_banks: [0; 8],
+            prg_banks: [0; 4],
+            mirroring: 0,
+            irq_control: 0,
+            irq_counter: 0,
+            irq_pending: false,
+            audio: Sunsoft5bAudio::new(),
+        }
+    }
+
+    fn prg_rom_addr(&self, bank: usize, addr: u16) -> MappedAddr {
+        let bank_count = (self.prg_rom_size / 0x2000).max(1);
+        let mapped = (bank % bank_count) * 0x2000 + (addr & 0x1FFF) as usize;
+        MappedAddr::PrgRom(mapped as u32)
+    }
+
+    /// What's at $6000, rom or ram depending on command 8
+    fn map_low_bank(&self, addr: u16) -> Option<MappedAddr> {
+        let bank = self.prg_banks[0];
+        match (bank & 0x40 > 0, bank & 0x80 > 0) {
+            (false, _) => Some(self.prg_rom_addr((bank & 0x3F) as usize, addr)),
+            (true, true) if self.prg_ram_size > 0 => {
+                let mapped = ((bank & 0x3F) as usize) * 0x2000 + (addr & 0x1FFF) as usize;
+                Some(MappedAddr::PrgRam((mapped % self.prg_ram_size) as u32))
+            }
+            // Ram disabled, open bus
+            _ => None,
+        }
+    }
+
+    fn write_parameter(&mut self, data: u8) {
+        match self.command {
+            c @ 0x0..=0x7 => self.chr_banks[c as usize] = data,
+            c @ 0x8..=0xB => self.prg_banks[(c - 0x8) as usize] = data,
+            0xC => self.mirroring = data & 0x03,
+            0xD => {
+                self.irq_control = data;
+                self.irq_pending = false;
+            }
+            0xE => self.irq_counter = (self.irq_counter & 0xFF00) | data as u16,
+            _ => self.irq_counter = (self.irq_counter & 0x00FF) | ((data as u16) << 8),
+        }
+    }
+}
+
+impl MapperTrait for Mapper069 {
+    fn cpu_map_peek(&self, addr: u16) -> Option<MappedAddr> {
+        match addr {
+            0x6000..=0x7FFF => self.map_low_bank(addr),
+            0x8000..=0xDFFF => {
+                let bank = self.prg_banks[(((addr - 0x8000) >> 13) + 1) as usize] & 0x3F;
+                Some(self.prg_rom_addr(bank as usize, addr))
+            }
+            0xE000..=0xFFFF => {
+                let last_bank = (self.prg_rom_size / 0x2000).saturating_sub(1);
+                Some(self.prg_rom_addr(last_bank, addr))
+            }
+            _ => None,
+        }
+    }
+
+    fn cpu_map_write(&mut self, addr: u16, data: u8) -> Option<MappedAddr> {
+        match addr {
+            0x6000..=0x7FFF => return self.map_low_bank(addr),
+            0x8000..=0x9FFF => self.command = data & 0x0F,
+            0xA000..=0xBFFF => self.write_parameter(data),
+            0xC000..=0xDFFF => self.audio.select(data),
+            0xE000..=0xFFFF => self.audio.write(data),
+            _ => return None,
+        }
+        Some(MappedAddr::Handled)
+    }
+
+    fn ppu_map_read(&self, addr: u16) -> Option<u32> {
+        if addr > 0x1FFF {
+            return None;
+        }
+        let bank = self.chr_banks[(addr >> 10) as usize] as usize;
+        let mapped = bank * 0x0400 + (addr & 0x03FF) as usize;
+        Some((mapped % self.chr_size.max(1)) as u32)
+    }
+
+    fn ppu_map_write(&self, addr: u16) -> Option<u32> {
+        // Writes to chr rom get dropped by the cartridge
+        self.ppu_map_read(addr)
+    }
+
+    fn mirror(&self) -> MIRROR {
+        match self.mirroring {
+            0 => MIRROR::VERTICAL,
+            1 => MIRROR::HORIZONTAL,
+            2 => MIRROR::OnescreenLo,
+            _ => MIRROR::OnescreenHi,
+        }
+    }
+
+    fn cpu_clock(&mut self) {
+        if self.irq_control & 0x80 > 0 {
+            let (counter, wrapped) = self.irq_counter.overflowing_sub(1);
+            self.irq_counter = counter;
+            if wrapped && self.irq_control & 0x01 > 0 {
+                self.irq_pending = true;
+            }
+        }
+        self.audio.clock();
+    }
+
+    fn irq_state(&self) -> bool {
+        self.irq_pending
+    }
+
+    fn audio_output(&self) -> f32 {
+        self.audio.output()
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        let mut state = vec![self.command];
+        state.extend_from_slice(&self.chr_banks);
+        state.extend_from_slice(&self.prg_banks);
+        state.extend_from_slice(&[self.mirroring, self.irq_control]);
+        state.extend_from_slice(&self.irq_counter.to_le_bytes());
+        state.push(self.irq_pending as u8);
+        state.extend_from_slice(&self.audio.save_state());
+        state
+    }
+
+    fn load_state(&mut self, state: &[u8]) -> bool {
+        if state.len() != 18 + Sunsoft5bAudio::STATE_SIZE {
+            return false;
+        }
+        let (registers, audio) = state.split_at(18);
+        self.command = registers[0];
+        self.chr_banks.copy_from_slice(&registers[1..9]);
+        self.prg_banks.copy_from_slice(&registers[9..13]);
+        self.mirroring = registers[13];
+        self.irq_control = registers[14];
+        self.irq_counter = u16::from_le_bytes([registers[15], registers[16]]);
+        self.irq_pending = registers[17] > 0;
+        self.audio.load_state(audio)
+    }
+}
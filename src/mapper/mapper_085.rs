@@ -0,0 +1,147 @@
+use super::vrc_irq::VrcIrq;
+use super::{MappedAddr, MapperTrait};
+use crate::cartridge::MIRROR;
+
+// https://www.nesdev.org/wiki/VRC7
+//
+// VRC7a selects the second register of a pair with A4, VRC7b with A3, both are decoded here.
+// The FM synth at $9010/$9030 isn't emulated, writes to it are dropped.
+//
+// $8000        8k prg bank at $8000
+// $8010        8k prg bank at $A000
+// $9000        8k prg bank at $C000
+// $A000-$D010  1k chr banks, two per $x000/$x010 pair
+// $E000        WS-- --MM  W: prg ram enable, S: silence audio, M: mirroring
+// $E010        IRQ latch
+// $F000        IRQ control
+// $F010        IRQ acknowledge
+
+pub struct Mapper085 {
+    prg_rom_size: usize,
+    chr_size: usize,
+    prg_ram_size: usize,
+
+    prg_banks: [u8; 3],
+    chr_banks: [u8; 8],
+    control: u8,
+    irq: VrcIrq,
+}
+
+impl Mapper085 {
+    pub fn new(prg_rom_size: usize, chr_size: usize, prg_ram_size: usize) -> Self {
+        Self {
+            prg_rom_size,
+            chr_size,
+            prg_ram_size,
+            prg_banks: [0; 3],
+            chr_banks: [0; 8],
+            control: 0,
+            irq: VrcIrq::new(),
+        }
+    }
+
+    /// Turns a cpu address into $x000 or $x010
+    fn register(addr: u16) -> u16 {
+        let odd = if addr & 0x18 > 0 { 0x10 } else { 0 };
+        (addr & 0xF000) | odd
+    }
+
+    fn prg_ram_enabled(&self) -> bool {
+        self.control & 0x80 > 0 && self.prg_ram_size > 0
+    }
+
+    fn prg_ram_addr(&self, addr: u16) -> MappedAddr {
+        MappedAddr::PrgRam(((addr & 0x1FFF) as usize % self.prg_ram_size) as u32)
+    }
+}
+
+impl MapperTrait for Mapper085 {
+    fn cpu_map_peek(&self, addr: u16) -> Option<MappedAddr> {
+        match addr {
+            0x6000..=0x7FFF if self.prg_ram_enabled() => Some(self.prg_ram_addr(addr)),
+            0x8000..=0xFFFF => {
+                let bank_count = (self.prg_rom_size / 0x2000).max(1);
+                let bank = match addr {
+                    0x8000..=0xDFFF => self.prg_banks[((addr - 0x8000) >> 13) as usize] as usize,
+                    _ => bank_count - 1,
+                };
+                let mapped = (bank % bank_count) * 0x2000 + (addr & 0x1FFF) as usize;
+                Some(MappedAddr::PrgRom(mapped as u32))
+            }
+            _ => None,
+        }
+    }
+
+    fn cpu_map_write(&mut self, addr: u16, data: u8) -> Option<MappedAddr> {
+        match addr {
+            0x6000..=0x7FFF if self.prg_ram_enabled() => return Some(self.prg_ram_addr(addr)),
+            0x8000..=0xFFFF => match Self::register(addr) {
+                0x8000 => self.prg_banks[0] = data & 0x3F,
+                0x8010 => self.prg_banks[1] = data & 0x3F,
+                0x9000 => self.prg_banks[2] = data & 0x3F,
+                r @ 0xA000..=0xD010 => {
+                    let bank = (((r >> 12) - 0xA) * 2 + ((r >> 4) & 0x01)) as usize;
+                    self.chr_banks[bank] = data;
+                }
+                0xE000 => self.control = data,
+                0xE010 => self.irq.write_latch(data),
+                0xF000 => self.irq.write_control(data),
+                0xF010 => self.irq.acknowledge(),
+                // Audio
+                _ => {}
+            },
+            _ => return None,
+        }
+        Some(MappedAddr::Handled)
+    }
+
+    fn ppu_map_read(&self, addr: u16) -> Option<u32> {
+        if addr > 0x1FFF {
+            return None;
+        }
+        let bank = self.chr_banks[(addr >> 10) as usize] as usize;
+        let mapped = bank * 0x0400 + (addr & 0x03FF) as usize;
+        Some((mapped % self.chr_size.max(1)) as u32)
+    }
+
+    fn ppu_map_write(&self, addr: u16) -> Option<u32> {
+        // Writes to chr rom get dropped by the cartridge
+        self.ppu_map_read(addr)
+    }
+
+    fn mirror(&self) -> MIRROR {
+        match self.control & 0x03 {
+            0 => MIRROR::VERTICAL,
+            1 => MIRROR::HORIZONTAL,
+            2 => MIRROR::OnescreenLo,
+            _ => MIRROR::OnescreenHi,
+        }
+    }
+
+    fn cpu_clock(&mut self) {
+        self.irq.clock();
+    }
+
+    fn irq_state(&self) -> bool {
+        self.irq.pending()
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        let mut state = self.prg_banks.to_vec();
+        state.extend_from_slice(&self.chr_banks);
+        state.push(self.control);
+        state.extend_from_slice(&self.irq.save_state());
+        state
+    }
+
+    fn load_state(&mut self, state: &[u8]) -> bool {
+        if state.len() != 12 + VrcIrq::STATE_SIZE {
+            return false;
+        }
+        let (registers, irq) = state.split_at(12);
+        self.prg_banks.copy_from_slice(&registers[0..3]);
+        self.chr_banks.copy_from_slice(&registers[3..11]);
+        self.control = registers[11];
+        self.irq.load_state(irq)
+    }
+}
@@ -0,0 +1,172 @@
+mod mapper_000;
+mod mapper_001;
+mod mapper_002;
+mod mapper_003;
+mod mapper_004;
+mod mapper_005;
+mod mapper_007;
+mod mapper_009;
+mod mapper_010;
+mod mapper_011;
+mod mapper_019;
+mod mapper_021;
+mod mapper_024;
+mod mapper_034;
+mod mapper_066;
+mod mapper_069;
+mod mapper_085;
+mod n163_audio;
+mod sunsoft5b_audio;
+mod vrc6_audio;
+mod vrc_irq;
+
+pub use mapper_000::Mapper000;
+pub use mapper_001::Mapper001;
+pub use mapper_002::Mapper002;
+pub use mapper_003::Mapper003;
+pub use mapper_004::Mapper004;
+pub use mapper_005::Mapper005;
+pub use mapper_007::Mapper007;
+pub use mapper_009::Mapper009;
+pub use mapper_010::Mapper010;
+pub use mapper_011::Mapper011;
+pub use mapper_019::Mapper019;
+pub use mapper_021::Mapper021;
+pub use mapper_024::Mapper024;
+pub use mapper_034::Mapper034;
+pub use mapper_066::Mapper066;
+pub use mapper_069::Mapper069;
+pub use mapper_085::Mapper085;
+
+use crate::cartridge::MIRROR;
+
+/// Where a cpu address ended up after going through the mapper
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MappedAddr {
+    PrgRom(u32),
+    PrgRam(u32),
+    /// The write went to one of the mapper's own registers
+    Handled,
+    /// The mapper answered the read itself (status registers, on board ram...)
+    Data(u8),
+}
+
+/// Where a nametable address ($2000-$2FFF) is stored
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NametablePage {
+    /// One of the two 1k pages of vram inside the console
+    Ciram(usize),
+    /// A 1k page of vram on the cartridge
+    CartVram(usize),
+    /// Memory on the cartridge, goes through `nametable_read`/`nametable_write`
+    Mapper,
+}
+
+/// Which part of rendering a ppu fetch is for. Background fetches carry the
+/// tile of the scanline they belong to, 0 and 1 are fetched at the end of the line before.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PpuFetch {
+    Nametable(u8),
+    Attribute(u8),
+    BackgroundPattern(u8),
+    SpritePattern,
+}
+
+/// What a mapper gives back when it takes over a rendering fetch
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PpuData {
+    /// Read chr memory at this address instead
+    Chr(u32),
+    Value(u8),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PpuEvent {
+    /// Dot 1 of a visible or pre-render scanline with rendering switched on
+    ScanlineStart(usize),
+    /// Vblank started or rendering was switched off
+    RenderingStopped,
+}
+
+/// Everything the board does between the cartridge edge and its memories.
+/// The `Cartridge` owns the rom/ram itself and asks the mapper where an
+/// address goes, the mapper owns all of its registers.
+pub trait MapperTrait {
+    /// Where a cpu read would go, without any of the side effects reading has.
+    /// The trace, the debugger and bus conflicts look through this
+    fn cpu_map_peek(&self, addr: u16) -> Option<MappedAddr>;
+    /// A real read by the cpu, only boards with registers that change when
+    /// read (irq acknowledge, auto increment) need more than `cpu_map_peek`
+    fn cpu_map_read(&mut self, addr: u16) -> Option<MappedAddr> {
+        self.cpu_map_peek(addr)
+    }
+    fn ppu_map_read(&self, addr: u16) -> Option<u32>;
+    /// Gets the byte being written since most boards latch it into a register
+    fn cpu_map_write(&mut self, addr: u16, data: u8) -> Option<MappedAddr>;
+    fn ppu_map_write(&self, addr: u16) -> Option<u32>;
+
+    /// Current nametable layout, boards with hard wired mirroring
+    /// just hand back what the header said
+    fn mirror(&self) -> MIRROR;
+
+    /// Which page a nametable address lands in, by default this is just `mirror()`
+    fn nametable_page(&self, addr: u16) -> NametablePage {
+        let quadrant = ((addr >> 10) & 0x03) as usize;
+        match self.mirror() {
+            MIRROR::OnescreenLo => NametablePage::Ciram(0),
+            MIRROR::OnescreenHi => NametablePage::Ciram(1),
+            MIRROR::VERTICAL => NametablePage::Ciram(quadrant & 0x01),
+            MIRROR::HORIZONTAL => NametablePage::Ciram(quadrant >> 1),
+            MIRROR::FourScreen if quadrant < 2 => NametablePage::Ciram(quadrant),
+            MIRROR::FourScreen => NametablePage::CartVram(quadrant - 2),
+        }
+    }
+
+    /// Only called for addresses where `nametable_page` said `Mapper`
+    fn nametable_read(&self, _addr: u16) -> u8 {
+        0
+    }
+
+    fn nametable_write(&mut self, _addr: u16, _data: u8) {}
+
+    /// Gets first go at every rendering fetch, None carries on with the normal lookup
+    fn ppu_fetch(&mut self, _addr: u16, _kind: PpuFetch) -> Option<PpuData> {
+        None
+    }
+
+    fn ppu_event(&mut self, _event: PpuEvent) {}
+
+    /// The ppu put `addr` on its address bus, boards that watch the
+    /// address lines (mmc3's A12 scanline counter) hook in here
+    fn ppu_address(&mut self, _addr: u16) {}
+
+    /// Called once every cpu cycle (M2)
+    fn cpu_clock(&mut self) {}
+
+    /// True while the board is pulling the cpu's irq line low
+    fn irq_state(&self) -> bool {
+        false
+    }
+
+    /// Expansion audio level, mixed in with the apu's output
+    fn audio_output(&self) -> f32 {
+        0.0
+    }
+
+    /// Boards that don't disable the rom during register writes see the
+    /// rom and the cpu drive the bus at the same time, the result is
+    /// the two values and-ed together
+    fn bus_conflicts(&self) -> bool {
+        false
+    }
+
+    /// Register state for save states, rom and ram are saved by the cartridge
+    fn save_state(&self) -> Vec<u8> {
+        Vec::new()
+    }
+
+    /// Returns false if `state` didn't come from this kind of mapper
+    fn load_state(&mut self, state: &[u8]) -> bool {
+        state.is_empty()
+    }
+}
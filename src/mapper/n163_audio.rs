@@ -0,0 +1,119 @@
+// https://www.nesdev.org/wiki/Namco_163_audio
+//
+// Up to 8 wavetable channels that live in the chip's 128 bytes of internal ram,
+// the same ram the cpu reaches through $4800. Only one channel is updated every
+// 15 cpu cycles so the more channels are on the quieter (and buzzier) each one gets.
+//
+// Channel registers, channel 1 at $40 through channel 8 at $78:
+// +0 +2 +4  Frequency, 18 bits (low 2 bits of +4)
+// +1 +3 +5  Phase, 24 bits
+// +4        Wave length  LLLL LL--  256 - L samples
+// +6        Wave address, in 4 bit samples
+// +7        Volume       ---- VVVV  $7F also holds the channel count in bits 4-6
+
+const RAM_SIZE: usize = 0x80;
+const CYCLES_PER_UPDATE: u8 = 15;
+/// A lone channel at full volume is about as loud as a full volume apu pulse
+const OUTPUT_SCALE: f32 = 0.15 / 105.0;
+
+pub(super) struct N163Audio {
+    ram: [u8; RAM_SIZE],
+    channel: usize,
+    cycles: u8,
+    outputs: [i8; 8],
+}
+
+impl N163Audio {
+    pub(super) const STATE_SIZE: usize = RAM_SIZE + 2 + 8;
+
+    pub(super) fn new() -> Self {
+        Self {
+            ram: [0; RAM_SIZE],
+            channel: 7,
+            cycles: 0,
+            outputs: [0; 8],
+        }
+    }
+
+    pub(super) fn read(&self, addr: u8) -> u8 {
+        self.ram[addr as usize % RAM_SIZE]
+    }
+
+    pub(super) fn write(&mut self, addr: u8, data: u8) {
+        self.ram[addr as usize % RAM_SIZE] = data;
+    }
+
+    /// The active channels are always the last ones, channel 8 on its own up to all 8
+    fn first_channel(&self) -> usize {
+        7 - ((self.ram[0x7F] >> 4) & 0x07) as usize
+    }
+
+    /// Once per cpu cycle
+    pub(super) fn clock(&mut self) {
+        self.cycles += 1;
+        if self.cycles < CYCLES_PER_UPDATE {
+            return;
+        }
+        self.cycles = 0;
+        if self.channel < self.first_channel() {
+            self.channel = 7;
+        }
+        self.update_channel(self.channel);
+        self.channel = if self.channel == self.first_channel() {
+            7
+        } else {
+            self.channel - 1
+        };
+    }
+
+    fn update_channel(&mut self, channel: usize) {
+        let base = 0x40 + channel * 8;
+        let registers = &mut self.ram[base..base + 8];
+        let frequency = registers[0] as u32
+            | (registers[2] as u32) << 8
+            | ((registers[4] & 0x03) as u32) << 16;
+        let phase = registers[1] as u32 | (registers[3] as u32) << 8 | (registers[5] as u32) << 16;
+        let length = 256 - (registers[4] & 0xFC) as u32;
+        let phase = (phase + frequency) % (length << 16);
+        registers[1] = phase as u8;
+        registers[3] = (phase >> 8) as u8;
+        registers[5] = (phase >> 16) as u8;
+
+        let volume = (registers[7] & 0x0F) as i8;
+        let sample_index = ((phase >> 16) + registers[6] as u32) & 0xFF;
+        let byte = self.ram[(sample_index >> 1) as usize];
+        let sample = if sample_index & 0x01 == 0 {
+            byte & 0x0F
+        } else {
+            byte >> 4
+        };
+        self.outputs[channel] = (sample as i8 - 8) * volume;
+    }
+
+    pub(super) fn output(&self) -> f32 {
+        let active = &self.outputs[self.first_channel()..];
+        let total: i32 = active.iter().map(|&x| x as i32).sum();
+        total as f32 / active.len() as f32 * OUTPUT_SCALE
+    }
+
+    pub(super) fn save_state(&self) -> Vec<u8> {
+        let mut state = self.ram.to_vec();
+        state.extend_from_slice(&[self.channel as u8, self.cycles]);
+        state.extend(self.outputs.iter().map(|&x| x as u8));
+        state
+    }
+
+    pub(super) fn load_state(&mut self, state: &[u8]) -> bool {
+        if state.len() != Self::STATE_SIZE {
+            return false;
+        }
+        let (ram, rest) = state.split_at(RAM_SIZE);
+        self.ram.copy_from_slice(ram);
+        self.channel = (rest[0] & 0x07) as usize;
+        self.cycles = rest[1];
+        for (output, &x) in self.outputs.iter_mut().zip(&rest[2..]) {
+            *output = x as i8;
+        }
+        true
+    }
+}
@@ -0,0 +1,267 @@
+// https://www.nesdev.org/wiki/Sunsoft_5B_audio
+//
+// A YM2149F (AY-3-8910) with three square channels, one noise generator and one envelope
+// shared by all of them. Registers are picked with $C000 and written through $E000.
+//
+// $00-$05  Tone periods, 12 bits across a low/high register pair per channel
+// $06      Noise period  ---P PPPP
+// $07      Mixer         --CB Acba  capitals disable noise, lower case disable tone
+// $08-$0A  Volume        ---E VVVV  E: use the envelope instead of V
+// $0B-$0C  Envelope period low/high
+// $0D      Envelope shape ---- CAaH  C: continue, A: attack, a: alternate, H: hold
+
+/// Everything inside the chip runs off the cpu clock divided by 16
+const CLOCK_DIVIDER: u8 = 16;
+/// Per channel, a full volume channel is about as loud as a full volume apu pulse
+const OUTPUT_SCALE: f32 = 0.15;
+
+#[derive(Default)]
+struct Tone {
+    period: u16,
+    counter: u16,
+    output: bool,
+}
+
+impl Tone {
+    fn clock(&mut self) {
+        self.counter += 1;
+        if self.counter >= self.period.max(1) {
+            self.counter = 0;
+            self.output = !self.output;
+        }
+    }
+}
+
+struct Noise {
+    period: u8,
+    counter: u8,
+    shift: u32,
+}
+
+impl Noise {
+    fn clock(&mut self) {
+        self.counter += 1;
+        if self.counter >= self.period.max(1) {
+            self.counter = 0;
+            let bit = (self.shift ^ (self.shift >> 3)) & 0x01;
+            self.shift = (self.shift >> 1) | (bit << 16);
+        }
+    }
+
+    fn output(&self) -> bool {
+        self.shift & 0x01 > 0
+    }
+}
+
+#[derive(Default)]
+struct Envelope {
+    period: u16,
+    counter: u16,
+    shape: u8,
+    step: u8,
+    attack: bool,
+    holding: bool,
+}
+
+impl Envelope {
+    fn write_shape(&mut self, data: u8) {
+        self.shape = data & 0x0F;
+        self.attack = data & 0x04 > 0;
+        self.step = 0;
+        self.counter = 0;
+        self.holding = false;
+    }
+
+    fn clock(&mut self) {
+        if self.holding {
+            return;
+        }
+        self.counter += 1;
+        if self.counter < self.period.max(1) {
+            return;
+        }
+        self.counter = 0;
+        if self.step < 31 {
+            self.step += 1;
+            return;
+        }
+        // End of a ramp, the shape decides what happens next
+        let continues = self.shape & 0x08 > 0;
+        let alternate = self.shape & 0x02 > 0;
+        let hold = self.shape & 0x01 > 0;
+        if !continues {
+            self.attack = false;
+            self.step = 31;
+            self.holding = true;
+        } else if hold {
+            if alternate {
+                self.attack = !self.attack;
+            }
+            self.step = 31;
+            self.holding = true;
+        } else {
+            if alternate {
+                self.attack = !self.attack;
+            }
+            self.step = 0;
+        }
+    }
+
+    /// 5 bit level, 31 is the loudest
+    fn level(&self) -> u8 {
+        if self.attack {
+            self.step
+        } else {
+            31 - self.step
+        }
+    }
+}
+
+pub(super) struct Sunsoft5bAudio {
+    register: u8,
+    tones: [Tone; 3],
+    noise: Noise,
+    envelope: Envelope,
+    mixer: u8,
+    volumes: [u8; 3],
+    divider: u8,
+}
+
+impl Sunsoft5bAudio {
+    pub(super) const STATE_SIZE: usize = 1 + 3 * 5 + 6 + 9 + 3 + 1;
+
+    pub(super) fn new() -> Self {
+        Self {
+            register: 0,
+            tones: Default::default(),
+            noise: Noise {
+                period: 0,
+                counter: 0,
+                shift: 1,
+            },
+            envelope: Envelope::default(),
+            mixer: 0,
+            volumes: [0; 3],
+            divider: 0,
+        }
+    }
+
+    /// $C000-$DFFF, only the low 4 bits pick a register,
+    /// anything in the high bits disables writes until the next select
+    pub(super) fn select(&mut self, data: u8) {
+        self.register = data;
+    }
+
+    /// $E000-$FFFF
+    pub(super) fn write(&mut self, data: u8) {
+        match self.register {
+            r @ 0x00..=0x05 => {
+                let tone = &mut self.tones[(r >> 1) as usize];
+                tone.period = if r & 0x01 == 0 {
+                    (tone.period & 0x0F00) | data as u16
+                } else {
+                    (tone.period & 0x00FF) | (((data & 0x0F) as u16) << 8)
+                };
+            }
+            0x06 => self.noise.period = data & 0x1F,
+            0x07 => self.mixer = data,
+            r @ 0x08..=0x0A => self.volumes[(r - 0x08) as usize] = data & 0x1F,
+            0x0B => self.envelope.period = (self.envelope.period & 0xFF00) | data as u16,
+            0x0C => self.envelope.period = (self.envelope.period & 0x00FF) | ((data as u16) << 8),
+            0x0D => self.envelope.write_shape(data),
+            _ => {}
+        }
+    }
+
+    /// Once per cpu cycle
+    pub(super) fn clock(&mut self) {
+        self.divider += 1;
+        if self.divider < CLOCK_DIVIDER {
+            return;
+        }
+        self.divider = 0;
+        for tone in self.tones.iter_mut() {
+            tone.clock();
+        }
+        self.noise.clock();
+        self.envelope.clock();
+    }
+
+    pub(super) fn output(&self) -> f32 {
+        let mut total = 0.0;
+        for (i, tone) in self.tones.iter().enumerate() {
+            let tone_on = tone.output || self.mixer & (0x01 << i) > 0;
+            let noise_on = self.noise.output() || self.mixer & (0x08 << i) > 0;
+            if !(tone_on && noise_on) {
+                continue;
+            }
+            // Fixed volumes line up with every other envelope step
+            let level = match self.volumes[i] {
+                v if v & 0x10 > 0 => self.envelope.level(),
+                0 => 0,
+                v => v * 2 + 1,
+            };
+            total += amplitude(level);
+        }
+        total * OUTPUT_SCALE
+    }
+
+    pub(super) fn save_state(&self) -> Vec<u8> {
+        let mut state = vec![self.register];
+        for tone in self.tones.iter() {
+            state.extend_from_slice(&tone.period.to_le_bytes());
+            state.extend_from_slice(&tone.counter.to_le_bytes());
+            state.push(tone.output as u8);
+        }
+        state.extend_from_slice(&[self.noise.period, self.noise.counter]);
+        state.extend_from_slice(&self.noise.shift.to_le_bytes());
+        state.extend_from_slice(&self.envelope.period.to_le_bytes());
+        state.extend_from_slice(&self.envelope.counter.to_le_bytes());
+        state.extend_from_slice(&[
+            self.envelope.shape,
+            self.envelope.step,
+            self.envelope.attack as u8,
+            self.envelope.holding as u8,
+            self.mixer,
+        ]);
+        state.extend_from_slice(&self.volumes);
+        state.push(self.divider);
+        state
+    }
+
+    pub(super) fn load_state(&mut self, state: &[u8]) -> bool {
+        if state.len() != Self::STATE_SIZE {
+            return false;
+        }
+        let word = |i: usize| u16::from_le_bytes([state[i], state[i + 1]]);
+        self.register = state[0];
+        for (i, tone) in self.tones.iter_mut().enumerate() {
+            let base = 1 + i * 5;
+            tone.period = word(base);
+            tone.counter = word(base + 2);
+            tone.output = state[base + 4] > 0;
+        }
+        self.noise.period = state[16];
+        self.noise.counter = state[17];
+        self.noise.shift = u32::from_le_bytes([state[18], state[19], state[20], state[21]]);
+        self.envelope.period = word(22);
+        self.envelope.counter = word(24);
+        self.envelope.shape = state[26];
+        self.envelope.step = state[27];
+        self.envelope.attack = state[28] > 0;
+        self.envelope.holding = state[29] > 0;
+        self.mixer = state[30];
+        self.volumes.copy_from_slice(&state[31..34]);
+        self.divider = state[34];
+        true
+    }
+}
+
+/// The volume steps are logarithmic, 1.5db each
+fn amplitude(level: u8) -> f32 {
+    if level == 0 {
+        0.0
+    } else {
+        10f32.powf((level as f32 - 31.0) * 1.5 / 20.0)
+    }
+}
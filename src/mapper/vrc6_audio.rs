@@ -0,0 +1,207 @@
+// https://www.nesdev.org/wiki/VRC6_audio
+//
+// Two pulse channels with 8 duty settings and a sawtooth, all driven by 12 bit timers off the cpu clock.
+//
+// $9000/$A000  Pulse control  MDDD VVVV  M: ignore duty (constant volume), D: duty, V: volume
+// $9001/$A001  Period low
+// $9002/$A002  Period high    E--- PPPP  E: enable
+// $9003        Frequency      ---- -ABH  H: halt all, B: periods >> 8, A: periods >> 4
+// $B000        Saw rate       --AA AAAA
+// $B001        Period low
+// $B002        Period high    E--- PPPP
+
+/// A full volume vrc6 pulse is about as loud as a full volume apu pulse
+const OUTPUT_SCALE: f32 = 0.15 / 15.0;
+
+#[derive(Default)]
+struct Timer {
+    period: u16,
+    counter: u16,
+    enabled: bool,
+}
+
+impl Timer {
+    fn write_low(&mut self, data: u8) {
+        self.period = (self.period & 0x0F00) | data as u16;
+    }
+
+    fn write_high(&mut self, data: u8) {
+        self.period = (self.period & 0x00FF) | (((data & 0x0F) as u16) << 8);
+        self.enabled = data & 0x80 > 0;
+    }
+
+    /// True when the timer ran out and the channel should step
+    fn clock(&mut self, shift: u8) -> bool {
+        if self.counter == 0 {
+            self.counter = self.period >> shift;
+            true
+        } else {
+            self.counter -= 1;
+            false
+        }
+    }
+}
+
+#[derive(Default)]
+struct Pulse {
+    timer: Timer,
+    control: u8,
+    step: u8,
+}
+
+impl Pulse {
+    fn write(&mut self, register: u16, data: u8) {
+        match register {
+            0 => self.control = data,
+            1 => self.timer.write_low(data),
+            _ => {
+                self.timer.write_high(data);
+                if !self.timer.enabled {
+                    self.step = 15;
+                }
+            }
+        }
+    }
+
+    fn clock(&mut self, shift: u8) {
+        if self.timer.enabled && self.timer.clock(shift) {
+            self.step = self.step.wrapping_sub(1) & 0x0F;
+        }
+    }
+
+    fn output(&self) -> u8 {
+        let duty = (self.control >> 4) & 0x07;
+        let constant = self.control & 0x80 > 0;
+        if self.timer.enabled && (constant || self.step <= duty) {
+            self.control & 0x0F
+        } else {
+            0
+        }
+    }
+}
+
+#[derive(Default)]
+struct Sawtooth {
+    timer: Timer,
+    rate: u8,
+    step: u8,
+    accumulator: u8,
+}
+
+impl Sawtooth {
+    fn write(&mut self, register: u16, data: u8) {
+        match register {
+            0 => self.rate = data & 0x3F,
+            1 => self.timer.write_low(data),
+            _ => {
+                self.timer.write_high(data);
+                if !self.timer.enabled {
+                    self.step = 0;
+                    self.accumulator = 0;
+                }
+            }
+        }
+    }
+
+    // The rate gets added every other step, the 14th step starts the ramp over
+    fn clock(&mut self, shift: u8) {
+        if !self.timer.enabled || !self.timer.clock(shift) {
+            return;
+        }
+        self.step += 1;
+        if self.step == 14 {
+            self.step = 0;
+            self.accumulator = 0;
+        } else if self.step.is_multiple_of(2) {
+            self.accumulator = self.accumulator.wrapping_add(self.rate);
+        }
+    }
+
+    fn output(&self) -> u8 {
+        self.accumulator >> 3
+    }
+}
+
+#[derive(Default)]
+pub(super) struct Vrc6Audio {
+    pulse: [Pulse; 2],
+    saw: Sawtooth,
+    frequency_control: u8,
+}
+
+impl Vrc6Audio {
+    pub(super) const STATE_SIZE: usize = 1 + 7 * 2 + 8;
+
+    /// `addr` has already had the board's A0/A1 swap applied
+    pub(super) fn write(&mut self, addr: u16, data: u8) {
+        let register = addr & 0x03;
+        match (addr & 0xF000, register) {
+            (0x9000, 3) => self.frequency_control = data,
+            (0x9000, _) => self.pulse[0].write(register, data),
+            (0xA000, _) => self.pulse[1].write(register, data),
+            (0xB000, _) => self.saw.write(register, data),
+            _ => {}
+        }
+    }
+
+    /// Once per cpu cycle
+    pub(super) fn clock(&mut self) {
+        if self.frequency_control & 0x01 > 0 {
+            return;
+        }
+        let shift = match self.frequency_control & 0x06 {
+            0 => 0,
+            0x02 => 4,
+            _ => 8,
+        };
+        self.pulse[0].clock(shift);
+        self.pulse[1].clock(shift);
+        self.saw.clock(shift);
+    }
+
+    pub(super) fn output(&self) -> f32 {
+        let total = self.pulse[0].output() + self.pulse[1].output() + self.saw.output();
+        total as f32 * OUTPUT_SCALE
+    }
+
+    pub(super) fn save_state(&self) -> Vec<u8> {
+        let mut state = vec![self.frequency_control];
+        for pulse in self.pulse.iter() {
+            state.extend_from_slice(&pulse.timer.period.to_le_bytes());
+            state.extend_from_slice(&pulse.timer.counter.to_le_bytes());
+            state.extend_from_slice(&[pulse.timer.enabled as u8, pulse.control, pulse.step]);
+        }
+        state.extend_from_slice(&self.saw.timer.period.to_le_bytes());
+        state.extend_from_slice(&self.saw.timer.counter.to_le_bytes());
+        state.extend_from_slice(&[
+            self.saw.timer.enabled as u8,
+            self.saw.rate,
+            self.saw.step,
+            self.saw.accumulator,
+        ]);
+        state
+    }
+
+    pub(super) fn load_state(&mut self, state: &[u8]) -> bool {
+        if state.len() != Self::STATE_SIZE {
+            return false;
+        }
+        let word = |i: usize| u16::from_le_bytes([state[i], state[i + 1]]);
+        self.frequency_control = state[0];
+        for (i, pulse) in self.pulse.iter_mut().enumerate() {
+            let base = 1 + i * 7;
+            pulse.timer.period = word(base);
+            pulse.timer.counter = word(base + 2);
+            pulse.timer.enabled = state[base + 4] > 0;
+            pulse.control = state[base + 5];
+            pulse.step = state[base + 6];
+        }
+        self.saw.timer.period = word(15);
+        self.saw.timer.counter = word(17);
+        self.saw.timer.enabled = state[19] > 0;
+        self.saw.rate = state[20];
+        self.saw.step = state[21];
+        self.saw.accumulator = state[22];
+        true
+    }
+}
@@ -0,0 +1,121 @@
+// https://www.nesdev.org/wiki/VRC_IRQ
+//
+// The irq counter shared by VRC4, VRC6 and VRC7. It counts up from the latch
+// and fires on overflow, either every cpu cycle or once per scanline where a
+// scanline is faked with a prescaler (341 ppu dots is 113.667 cpu cycles).
+
+const PRESCALER_RELOAD: i16 = 341;
+
+pub(super) struct VrcIrq {
+    latch: u8,
+    counter: u8,
+    prescaler: i16,
+    enabled: bool,
+    enable_after_ack: bool,
+    cycle_mode: bool,
+    pending: bool,
+}
+
+impl VrcIrq {
+    pub(super) const STATE_SIZE: usize = 8;
+
+    pub(super) fn new() -> Self {
+        Self {
+            latch: 0,
+            counter: 0,
+            prescaler: PRESCALER_RELOAD,
+            enabled: false,
+            enable_after_ack: false,
+            cycle_mode: false,
+            pending: false,
+        }
+    }
+
+    pub(super) fn write_latch(&mut self, data: u8) {
+        self.latch = data;
+    }
+
+    /// VRC4 splits the latch across two registers
+    pub(super) fn write_latch_lo(&mut self, data: u8) {
+        self.latch = (self.latch & 0xF0) | (data & 0x0F);
+    }
+
+    pub(super) fn write_latch_hi(&mut self, data: u8) {
+        self.latch = (self.latch & 0x0F) | ((data & 0x0F) << 4);
+    }
+
+    pub(super) fn write_control(&mut self, data: u8) {
+        self.enable_after_ack = data & 0x01 > 0;
+        self.enabled = data & 0x02 > 0;
+        self.cycle_mode = data & 0x04 > 0;
+        self.pending = false;
+        if self.enabled {
+            self.counter = self.latch;
+            self.prescaler = PRESCALER_RELOAD;
+        }
+    }
+
+    pub(super) fn acknowledge(&mut self) {
+        self.pending = false;
+        self.enabled = self.enable_after_ack;
+    }
+
+    /// Once per cpu cycle
+    pub(super) fn clock(&mut self) {
+        if !self.enabled {
+            return;
+        }
+        if self.cycle_mode {
+            self.clock_counter();
+        } else {
+            self.prescaler -= 3;
+            if self.prescaler <= 0 {
+                self.prescaler += PRESCALER_RELOAD;
+                self.clock_counter();
+            }
+        }
+    }
+
+    fn clock_counter(&mut self) {
+        if self.counter == 0xFF {
+            self.counter = self.latch;
+            self.pending = true;
+        } else {
+            self.counter += 1;
+        }
+    }
+
+    pub(super) fn pending(&self) -> bool {
+        self.pending
+    }
+
+    pub(super) fn save_state(&self) -> Vec<u8> {
+        let [prescaler_lo, prescaler_hi] = self.prescaler.to_le_bytes();
+        vec![
+            self.latch,
+            self.counter,
+            prescaler_lo,
+            prescaler_hi,
+            self.enabled as u8,
+            self.enable_after_ack as u8,
+            self.cycle_mode as u8,
+            self.pending as u8,
+        ]
+    }
+
+    pub(super) fn load_state(&mut self, state: &[u8]) -> bool {
+        match *state {
+            [latch, counter, prescaler_lo, prescaler_hi, enabled, enable_after_ack, cycle_mode, pending] => {
+                self.latch = latch;
+                self.counter = counter;
+                self.prescaler = i16::from_le_bytes([prescaler_lo, prescaler_hi]);
+                self.enabled = enabled > 0;
+                self.enable_after_ack = enable_after_ack > 0;
+                self.cycle_mode = cycle_mode > 0;
+                self.pending = pending > 0;
+                true
+            }
+            _ => false,
+        }
+    }
+}
@@ -1,71 +1,148 @@
-use crate::bus::Bus;
-use crate::cartridge::Cartridge;
+use crate::audio::AudioOutput;
+use crate::bus::{Bus, IrqSource};
+use crate::cartridge::{Cartridge, CartridgeError, Timing};
 use crate::consts::{
-    emulation_consts::CPU_DEBUG,
-    nes_consts::CART,
+    emulation_consts::{AUDIO_SAMPLE_RATE, SAVE_INTERVAL_FRAMES},
     ppu_consts,
 };
-use crate::cpu::Cpu6502;
 use crate::disassembler::disassemble_rom;
 use crate::ppu::{
     helpers::set_oam_field,
     structures::ObjectAttributeEntry,
 };
+use crate::trace::{TraceFilter, TraceLogger};
+use cpu6502::Cpu6502;
 
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
 
+/// The console's video timing, this decides how many scanlines make up a
+/// frame and how many ppu dots pass for every cpu cycle.
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Region {
+    Ntsc,
+    Pal,
+    Dendy,
+}
+
+impl Region {
+    pub fn scanlines_per_frame(&self) -> usize {
+        match self {
+            Region::Ntsc => 262,
+            Region::Pal | Region::Dendy => 312,
+        }
+    }
+
+    /// Cpu cycles per second, the master clock divided down
+    pub fn cpu_clock_rate(&self) -> f64 {
+        match self {
+            Region::Ntsc => 21_477_272.0 / 12.0,
+            Region::Pal => 26_601_712.0 / 16.0,
+            Region::Dendy => 26_601_712.0 / 15.0,
+        }
+    }
+
+    /// Ntsc and dendy run the cpu every 3rd ppu dot,
+    /// pal runs it 5 times every 16 dots (3.2 dots per cpu cycle)
+    pub fn is_cpu_cycle(&self, ppu_clock: usize) -> bool {
+        match self {
+            Region::Ntsc | Region::Dendy => ppu_clock % 3 == 0,
+            Region::Pal => matches!(ppu_clock % 16, 0 | 3 | 6 | 10 | 13),
+        }
+    }
+}
+
+impl From<Timing> for Region {
+    fn from(timing: Timing) -> Self {
+        match timing {
+            Timing::Ntsc | Timing::MultiRegion => Region::Ntsc,
+            Timing::Pal => Region::Pal,
+            Timing::Dendy => Region::Dendy,
+        }
+    }
+}
+
 pub struct Nes {
-    pub cpu: Cpu6502,
+    pub cpu: Cpu6502<Bus>,
     pub decoded_rom: HashMap<u16, String>,
+    pub region: Region,
+    cart: Rc<RefCell<Cartridge>>,
     system_clock: usize,
+    /// Cpu cycles since power on, dma alternates get and put cycles with it
+    pub(crate) cpu_cycle: usize,
+    last_save_frame: i32,
+    tracer: Option<TraceLogger>,
+    audio: AudioOutput,
+    /// The samples for the last frame the ppu finished
+    audio_frame: Vec<f32>,
+    last_audio_frame: i32,
 }
 
 impl Nes {
-    pub fn new() -> Self {
-        match Cartridge::from(CART) {
-            Ok(cart) => {
-                let cart_rc = Rc::new(RefCell::new(cart));
-                let bus = Bus::new(cart_rc.clone());
-                let decoded_rom = disassemble_rom(0x0000, 0xFFFF, cart_rc.clone());
-                let mut cpu = Cpu6502::new(bus);
-                match CART {
-                    // Rom::NesTest => {
-                    //     cpu.reset(Some(0xC000));
-                    // }
-                    _ => {
-                        cpu.reset(None);
-                    }
-                }
+    pub fn new(cart: Cartridge, region: Region) -> Self {
+        let cart_rc = Rc::new(RefCell::new(cart));
+        let bus = Bus::new(cart_rc.clone(), region);
+        let decoded_rom = disassemble_rom(0x0000, 0xFFFF, cart_rc.clone());
+        let mut cpu = Cpu6502::new(bus);
+        cpu.reset(None);
 
-                return Self {
-                    cpu,
-                    decoded_rom,
-                    system_clock: 0,
-                };
-            }
-            Err(x) => {
-                println!("{:?}", x);
-                panic!()
-            }
+        Self {
+            cpu,
+            decoded_rom,
+            region,
+            cart: cart_rc,
+            system_clock: 0,
+            cpu_cycle: 0,
+            last_save_frame: 0,
+            tracer: None,
+            audio: AudioOutput::new(region.cpu_clock_rate(), AUDIO_SAMPLE_RATE),
+            audio_frame: Vec::new(),
+            last_audio_frame: 0,
         }
     }
 
+    /// If no region is given the one from the rom header is used
+    pub fn from_file(file_name: String, region: Option<Region>) -> Result<Self, CartridgeError> {
+        let cart = Cartridge::new(file_name)?;
+        let region = region.unwrap_or_else(|| Region::from(cart.info.timing));
+        Ok(Nes::new(cart, region))
+    }
+
     pub fn clock(&mut self) {
         self.cpu.bus.ppu.clock();
 
-        if self.system_clock % 3 == 0 {
-            if self.cpu.bus.dma_transfer {
+        if self.region.is_cpu_cycle(self.system_clock) {
+            self.cart.borrow_mut().cpu_clock();
+            let mapper_irq = self.cart.borrow().irq_state();
+            self.cpu.bus.set_irq(IrqSource::MAPPER, mapper_irq);
+            self.cpu.bus.apu.clock();
+            let frame_irq = self.cpu.bus.apu.frame_irq();
+            self.cpu.bus.set_irq(IrqSource::FRAME_COUNTER, frame_irq);
+            let dmc_irq = self.cpu.bus.apu.dmc_irq();
+            self.cpu.bus.set_irq(IrqSource::DMC, dmc_irq);
+            let level = self.audio_sample();
+            self.audio.clock(level);
+
+            if let Some(halted) = self.cpu.bus.dmc_halt {
+                self.dmc_dma_cycle(halted);
+            } else if self.cpu.bus.dma_transfer {
                 if self.cpu.bus.dma_dummy {
-                    if self.system_clock % 2 == 1 {
+                    if !self.is_get_cycle() {
                         self.cpu.bus.dma_dummy = false;
                     }
                 } else {
-                    if self.system_clock % 2 == 0 {
-                        let addr =
-                            ((self.cpu.bus.dma_page as u16) << 8) | self.cpu.bus.dma_addr as u16;
-                        self.cpu.bus.dma_data = self.cpu.bus.cpu_read(addr, true)
+                    if self.is_get_cycle() {
+                        if self.dmc_dma_fetch() {
+                            // The dmc took this get cycle, the sprite dma
+                            // needs another one to line back up
+                            self.cpu.bus.dma_dummy = true;
+                        } else {
+                            let addr = ((self.cpu.bus.dma_page as u16) << 8)
+                                | self.cpu.bus.dma_addr as u16;
+                            self.cpu.bus.dma_data = self.cpu.bus.cpu_read(addr, false)
+                        }
                     } else {
                         set_oam_field(
                             &mut self.cpu.bus.ppu.oam,
@@ -81,21 +158,126 @@ impl Nes {
                     }
                 }
             } else {
+                // The ppu flags the start of vblank once, holding the nmi line
+                // for a single cycle is enough for the cpu to see the edge
+                self.cpu.nmi_line = std::mem::take(&mut self.cpu.bus.ppu.nmi);
+                self.cpu.irq_line = self.cpu.bus.irq();
+                if self.cpu.between_instructions() && !self.cpu.interrupt_pending() {
+                    self.trace_instruction();
+                }
                 self.cpu.clock();
-                if CPU_DEBUG {
-                    /* add cpu state logging? */
-                } 
+
+                // The dmc can only halt the cpu on a read
+                let dmc_waiting = self.cpu.bus.apu.dmc_dma_address().is_some();
+                if dmc_waiting && self.cpu.bus.cpu_read_addr.is_some() {
+                    self.cpu.bus.dmc_halt = Some(0);
+                }
             }
+            self.cpu_cycle += 1;
         }
 
-        if self.cpu.bus.ppu.nmi {
-            self.cpu.bus.ppu.nmi = false;
-            self.cpu.nmi();
+        let frame = self.cpu.bus.ppu.frame_complete_count;
+        if frame != self.last_audio_frame {
+            self.last_audio_frame = frame;
+            self.audio.end_frame(&mut self.audio_frame);
+        }
+        if frame != self.last_save_frame && frame % SAVE_INTERVAL_FRAMES == 0 {
+            self.last_save_frame = frame;
+            self.flush_save();
         }
         self.system_clock += 1;
     }
 
-    
+    // The halted cpu keeps repeating the read it stopped on, the sample byte
+    // gets fetched once it's been halted for 2 cycles and it's a get cycle.
+    // Repeating the read is what double clocks $2007 and the controllers.
+    fn dmc_dma_cycle(&mut self, halted: u8) {
+        if halted >= 2 && self.is_get_cycle() {
+            self.dmc_dma_fetch();
+            self.cpu.bus.dmc_halt = None;
+        } else {
+            if let Some(addr) = self.cpu.bus.cpu_read_addr {
+                self.cpu.bus.cpu_read(addr, false);
+            }
+            self.cpu.bus.dmc_halt = Some(halted + 1);
+        }
+    }
+
+    // Dma reads on get cycles and writes on put cycles. They alternate every
+    // cpu cycle, which on pal isn't a fixed number of ppu dots.
+    fn is_get_cycle(&self) -> bool {
+        self.cpu_cycle % 2 == 0
+    }
+
+    /// Hands the dmc its next sample byte, false if it didn't need one
+    fn dmc_dma_fetch(&mut self) -> bool {
+        match self.cpu.bus.apu.dmc_dma_address() {
+            Some(addr) => {
+                let data = self.cpu.bus.cpu_read(addr, false);
+                self.cpu.bus.apu.dmc_dma_fill(data);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Write battery backed ram to disk, called every few seconds and on exit
+    pub fn flush_save(&mut self) {
+        if let Err(e) = self.cart.borrow_mut().flush_save() {
+            eprintln!("Could not write save file: {}", e);
+        }
+    }
+
+    /// Start writing every executed instruction to `path`, replacing any
+    /// trace that was already running
+    pub fn start_trace(&mut self, path: &str, filter: TraceFilter) -> std::io::Result<()> {
+        self.stop_trace();
+        self.tracer = Some(TraceLogger::new(path, filter)?);
+        Ok(())
+    }
+
+    pub fn stop_trace(&mut self) {
+        if let Some(mut tracer) = self.tracer.take() {
+            if let Err(e) = tracer.flush() {
+                eprintln!("Could not write trace file: {}", e);
+            }
+        }
+    }
+
+    pub fn is_tracing(&self) -> bool {
+        self.tracer.is_some()
+    }
+
+    fn trace_instruction(&mut self) {
+        if let Some(tracer) = self.tracer.as_mut() {
+            let frame = self.cpu.bus.ppu.frame_complete_count;
+            if let Err(e) = tracer.log(&mut self.cpu, frame) {
+                // Don't keep failing on every instruction, a full disk won't fix itself
+                eprintln!("Could not write trace file, tracing stopped: {}", e);
+                self.tracer = None;
+            }
+        }
+    }
+
+    /// Current level of the audio output, the apu mixed with the cartridge's expansion audio
+    pub fn audio_sample(&self) -> f32 {
+        self.cpu.bus.apu.output() + self.cart.borrow().audio_output()
+    }
+
+    /// Resampled audio for the last frame the ppu finished, mono samples at
+    /// the output sample rate. The frontend has no audio device to hand
+    /// these to yet, only the tests read them for now.
+    #[allow(unused)]
+    pub fn audio_frame(&self) -> &[f32] {
+        &self.audio_frame
+    }
+
+    /// Usually 44100 or 48000, whatever the audio device wants
+    pub fn set_sample_rate(&mut self, sample_rate: u32) {
+        self.audio = AudioOutput::new(self.region.cpu_clock_rate(), sample_rate);
+        self.audio_frame.clear();
+    }
+
     pub fn get_frame_status(&self) -> bool {
         self.cpu.bus.ppu.frame_complete
     }
@@ -106,6 +288,7 @@ impl Nes {
     pub fn reset(&mut self) {
         self.cpu.reset(None);
         self.cpu.bus.ppu.reset();
+        self.cpu.bus.apu.reset();
     }
 
     pub fn get_pattern_table(
@@ -10,6 +10,8 @@ mod write;
 use structures::*;
 use helpers::*;
 use crate::cartridge::Cartridge;
+use crate::mapper::{PpuEvent, PpuFetch};
+use crate::nes::Region;
 use crate::consts::{
     emulation_consts::COLOR_CHANNELS,
     ppu_consts::*,
@@ -20,6 +22,7 @@ use std::{cell::RefCell, rc::Rc};
 
 pub struct PPU {
     cart: Rc<RefCell<Cartridge>>,
+    region: Region,
     pub name_table: NameTableT,
     pub pattern_table: PatternTableT,
     pub palette: PaletteT,
@@ -75,9 +78,10 @@ pub struct PPU {
 }
 
 impl PPU {
-    pub fn new(cart: Rc<RefCell<Cartridge>>) -> Self {
+    pub fn new(cart: Rc<RefCell<Cartridge>>, region: Region) -> Self {
         Self {
             cart: cart.clone(),
+            region,
             name_table: [[0; 1024], [0; 1024]],
             pattern_table: [[0; 4096], [0; 4096]],
             palette: [0; 32],
@@ -140,10 +144,11 @@ impl PPU {
             let a: u16 = (ppu.ctrl.pattern_background.get_as_value() as u16) << 12;
             let b: u16 = (ppu.bg_next_tile_id as u16) << 4;
             let c: u16 = ppu.vram_addr.fine_y.get_as_value();
+            let tile = ppu.fetch_tile();
             if lsb {
-                ppu.bg_next_tile_lsb = ppu.ppu_read(a + b + c + 0x0000);
+                ppu.bg_next_tile_lsb = ppu.fetch(a + b + c + 0x0000, PpuFetch::BackgroundPattern(tile));
             } else {
-                ppu.bg_next_tile_msb = ppu.ppu_read(a + b + c + 0x0008);
+                ppu.bg_next_tile_msb = ppu.fetch(a + b + c + 0x0008, PpuFetch::BackgroundPattern(tile));
             };
         }
 
@@ -153,7 +158,8 @@ impl PPU {
             let c: u16 = (ppu.vram_addr.coarse_y.get_as_value() >> 2) << 3;
             let d: u16 = ppu.vram_addr.coarse_x.get_as_value() >> 2;
             let addr = 0x23C0 | a | b | c | d;
-            ppu.bg_next_tile_attrib = ppu.ppu_read(addr);
+            let tile = ppu.fetch_tile();
+            ppu.bg_next_tile_attrib = ppu.fetch(addr, PpuFetch::Attribute(tile));
 
             if ppu.vram_addr.coarse_y.get_as_value() & 0x02 > 0 {
                 ppu.bg_next_tile_attrib >>= 4
@@ -171,8 +177,8 @@ impl PPU {
                 // Fetch nametable byte
                 0 => {
                     self.load_background_shifters();
-                    self.bg_next_tile_id =
-                        self.ppu_read(0x2000 | (self.vram_addr.get_register() & 0x0FFF));
+                    let addr = 0x2000 | (self.vram_addr.get_register() & 0x0FFF);
+                    self.bg_next_tile_id = self.fetch(addr, PpuFetch::Nametable(self.fetch_tile()));
                 }
 
                 // Fetch attribute byte
@@ -213,28 +219,36 @@ impl PPU {
         }
 
         if self.cycle == 338 || self.cycle == 340 {
-            self.bg_next_tile_id = self.ppu_read(0x2000 | (self.vram_addr.get_register() & 0x0FFF))
+            let addr = 0x2000 | (self.vram_addr.get_register() & 0x0FFF);
+            self.bg_next_tile_id = self.fetch(addr, PpuFetch::Nametable(self.fetch_tile()))
         }
     }
 
     pub fn clock(&mut self) {
         // 0..=239    => Render screen
         // 240        => Post Render
-        // 241..=260  => Vblank
-        // 261        => pre-render scanline
+        // 241..=260  => Vblank (241..=310 on pal/dendy)
+        // 261        => pre-render scanline (311 on pal/dendy)
 
         if self.scanline == 128 {
             self.nmi = self.nmi;
         }
 
-        match LineState::from(self.scanline) {
+        match LineState::from(self.scanline, self.region) {
             LineState::Visible => {
                 if self.scanline == 0 && self.cycle == 0 {
                     self.cycle = 1;
                 }
+                if self.cycle == 1 {
+                    self.scanline_event();
+                }
                 self.process_visible_cycle();
             }
-            LineState::PostRender => {}
+            LineState::PostRender => {
+                if self.cycle == 1 {
+                    self.cart.borrow_mut().ppu_event(PpuEvent::RenderingStopped);
+                }
+            }
             LineState::VBlank => {
                 if self.scanline == 241 && self.cycle == 1 {
                     self.status.vertical_blank.one();
@@ -250,6 +264,7 @@ impl PPU {
                     self.status.sprite_zero_hit.zero();
                     self.sprite_shifter_pattern_lo = [0; 8];
                     self.sprite_shifter_pattern_hi = [0; 8]; 
+                    self.scanline_event();
                 }
 
                 self.process_visible_cycle();
@@ -262,71 +277,42 @@ impl PPU {
         }
 
         // Sprite logic
-        if self.cycle == 257 && self.scanline != 261 {
-            self.load_sprites_to_render();
+        if self.cycle == 257 {
+            if self.scanline == self.pre_render_scanline() {
+                // Nothing is evaluated for the pre-render line, every slot fetches tile $FF
+                self.sprites_to_render.clear();
+            } else {
+                self.load_sprites_to_render();
+            }
         }
 
-        if self.cycle == 340 {
-            for (i, e) in self.sprites_to_render.iter().enumerate() {
-                let pattern_addr_lo: u16;
-                let (_x, y, id, attr): (u16, u16, u16, u16) = e.to_u16_arr();
-                if !self.ctrl.sprite_size.get_as_bool() {
-                    // 8x8 mode
-                    let table: u16 = self.ctrl.pattern_sprite.get_as_value() as u16;
-
-                    if e.attribute & 0x80 == 0 {
-                        // Normal vertical orientation
-                        pattern_addr_lo = 
-                            (table << 12)
-                            | id << 4
-                            | ((self.scanline as u16) - y);
-
-                    } else {
-                        // flipped vertical
-                        pattern_addr_lo = 
-                            (table << 12)
-                            | id << 4
-                            | (7 - ((self.scanline as u16) - y));
-                    }
-                } else {
-                    // 8x16 mode
-                    if e.attribute & 0x80 == 0 {
-                        // Normal vertical orientation
-                        if (0..8).contains(&(self.scanline - (e.y as usize))) {
-                            pattern_addr_lo =
-                                (id & 0x0001) << 12
-                                | (id & 0x00FE) << 4
-                                | ((self.scanline as u16) - y);
-                        } else {
-                            pattern_addr_lo =
-                                (id & 0x0001) << 12
-                                | ((id & 0x00FE) + 1) << 4
-                                | ((self.scanline as u16) - y);
-                        }
-                    } else {
-                        // flipped vertical
-                        if (0..8).contains(&(self.scanline - (e.y as usize))) {
-                            pattern_addr_lo =
-                                (id & 0x0001) << 12
-                                | (id & 0x00FE) << 4
-                                | (7 - ((self.scanline as u16) - y));
-                        } else {
-                            pattern_addr_lo =
-                                (id & 0x0001) << 12
-                                | ((id & 0x00FE) + 1) << 4
-                                | ((self.scanline as u16) - y);
-                        }
-                    }
+        // Sprite pattern fetches, one slot every 8 dots between 257 and 320.
+        // Empty slots still do a dummy fetch so the cartridge sees the same
+        // A12 pattern the real ppu puts out (mmc3 counts scanlines with it).
+        let fetch_line = self.scanline < 240 || self.scanline == self.pre_render_scanline();
+        if fetch_line && self.can_render() && (257..=320).contains(&self.cycle) {
+            let slot = (self.cycle - 257) / 8;
+            let sprite = self.sprites_to_render.get(slot).copied();
+            let pattern_addr_lo = self.sprite_pattern_addr(sprite);
+            let flip_x = sprite.map_or(false, |e| e.attribute & 0x40 > 0);
+            match (self.cycle - 257) % 8 {
+                4 => {
+                    let data = self.fetch(pattern_addr_lo, PpuFetch::SpritePattern);
+                    self.sprite_shifter_pattern_lo[slot] = match (sprite, flip_x) {
+                        (None, _) => 0,
+                        (Some(_), true) => data.reverse_bits(),
+                        (Some(_), false) => data,
+                    };
                 }
-
-                if attr & 0x40 > 0 {
-                    self.sprite_shifter_pattern_lo[i] = self.ppu_read(pattern_addr_lo).reverse_bits();
-                    self.sprite_shifter_pattern_hi[i] = self.ppu_read(pattern_addr_lo + 8).reverse_bits();
-                } else {
-                    self.sprite_shifter_pattern_lo[i] = self.ppu_read(pattern_addr_lo); 
-                    self.sprite_shifter_pattern_hi[i] = self.ppu_read(pattern_addr_lo + 8);
+                6 => {
+                    let data = self.fetch(pattern_addr_lo + 8, PpuFetch::SpritePattern);
+                    self.sprite_shifter_pattern_hi[slot] = match (sprite, flip_x) {
+                        (None, _) => 0,
+                        (Some(_), true) => data.reverse_bits(),
+                        (Some(_), false) => data,
+                    };
                 }
-
+                _ => {}
             }
         }
 
@@ -335,7 +321,7 @@ impl PPU {
             self.cycle = 0;
 
             self.scanline += 1;
-            if self.scanline > 261 {
+            if self.scanline >= self.region.scanlines_per_frame() {
                 self.scanline = 0;
                 self.frame_complete = true;
                 self.frame_complete_count += 1;
@@ -454,6 +440,30 @@ impl PPU {
         }
     }
 
+    /// Which tile of the scanline the background fetches at this dot are for,
+    /// the first two are fetched at the end of the line before
+    fn fetch_tile(&self) -> u8 {
+        if self.cycle >= 321 {
+            ((self.cycle - 321) / 8) as u8
+        } else {
+            ((self.cycle - 1) / 8 + 2) as u8
+        }
+    }
+
+    /// Lets the cartridge follow along with rendering, mmc5 counts scanlines with this
+    fn scanline_event(&mut self) {
+        let event = if self.can_render() {
+            PpuEvent::ScanlineStart(self.scanline)
+        } else {
+            PpuEvent::RenderingStopped
+        };
+        self.cart.borrow_mut().ppu_event(event);
+    }
+
+    fn pre_render_scanline(&self) -> usize {
+        self.region.scanlines_per_frame() - 1
+    }
+
     fn can_render(&self) -> bool {
         if self.mask.render_background.get_as_value() > 0 || self.mask.render_sprites.get_as_value() > 0 {
             return true;
@@ -492,6 +502,67 @@ impl PPU {
         }
     }
 
+    /// Pattern address of the low plane for a sprite on this scanline,
+    /// empty slots point at tile $FF like the hardware does
+    fn sprite_pattern_addr(&self, sprite: Option<ObjectAttributeEntry>) -> u16 {
+        let e = match sprite {
+            Some(e) => e,
+            None if self.ctrl.sprite_size.get_as_bool() => return 0x1FE0,
+            None => return ((self.ctrl.pattern_sprite.get_as_value() as u16) << 12) | 0x0FF0,
+        };
+        let pattern_addr_lo: u16;
+        let (_x, y, id, _attr): (u16, u16, u16, u16) = e.to_u16_arr();
+        if !self.ctrl.sprite_size.get_as_bool() {
+            // 8x8 mode
+            let table: u16 = self.ctrl.pattern_sprite.get_as_value() as u16;
+
+            if e.attribute & 0x80 == 0 {
+                // Normal vertical orientation
+                pattern_addr_lo = 
+                    (table << 12)
+                    | id << 4
+                    | ((self.scanline as u16) - y);
+
+            } else {
+                // flipped vertical
+                pattern_addr_lo = 
+                    (table << 12)
+                    | id << 4
+                    | (7 - ((self.scanline as u16) - y));
+            }
+        } else {
+            // 8x16 mode
+            if e.attribute & 0x80 == 0 {
+                // Normal vertical orientation
+                if (0..8).contains(&(self.scanline - (e.y as usize))) {
+                    pattern_addr_lo =
+                        (id & 0x0001) << 12
+                        | (id & 0x00FE) << 4
+                        | ((self.scanline as u16) - y);
+                } else {
+                    pattern_addr_lo =
+                        (id & 0x0001) << 12
+                        | ((id & 0x00FE) + 1) << 4
+                        | ((self.scanline as u16) - y);
+                }
+            } else {
+                // flipped vertical
+                if (0..8).contains(&(self.scanline - (e.y as usize))) {
+                    pattern_addr_lo =
+                        (id & 0x0001) << 12
+                        | (id & 0x00FE) << 4
+                        | (7 - ((self.scanline as u16) - y));
+                } else {
+                    pattern_addr_lo =
+                        (id & 0x0001) << 12
+                        | ((id & 0x00FE) + 1) << 4
+                        | ((self.scanline as u16) - y);
+                }
+            }
+        }
+        pattern_addr_lo
+    }
+
     fn get_color_to_draw(&mut self) -> Pixel {
         let mut bg_pixel: u8 = 0x00;
         let mut bg_palette: u8 = 0x00;
@@ -563,17 +634,18 @@ impl PPU {
 enum LineState {
     Visible,    // 0..=239
     PostRender, // 240
-    VBlank,     // 241..=260
-    PreRender,  // 261
+    VBlank,     // 241..=260 (ntsc) 241..=310 (pal/dendy)
+    PreRender,  // 261 (ntsc) 311 (pal/dendy)
 }
 
 impl LineState {
-    fn from(line: usize) -> LineState {
+    fn from(line: usize, region: Region) -> LineState {
+        let pre_render = region.scanlines_per_frame() - 1;
         match line {
             0..=239 => LineState::Visible,
             240 => LineState::PostRender,
-            241..=260 => LineState::VBlank,
-            261 => LineState::PreRender,
+            x if x == pre_render => LineState::PreRender,
+            x if x > 240 && x < pre_render => LineState::VBlank,
             _ => panic!("Invalid line state"),
         }
     }
@@ -1,5 +1,5 @@
 use super::{helpers::get_oam_field, PPU};
-use crate::cartridge::MIRROR;
+use crate::mapper::{NametablePage, PpuFetch};
 
 impl PPU {
     // Plase ignore the cpu read/write sections, the 6502 and 2c02 can eat my shorts.
@@ -45,7 +45,9 @@ impl PPU {
                 let mut data = self.ppu_data_buffer;
 
                 // then update the buffer for next time
-                self.ppu_data_buffer = self.ppu_read(self.vram_addr.get_register());
+                let addr = self.vram_addr.get_register();
+                self.ppu_data_buffer = self.ppu_read(addr);
+                self.cart.borrow_mut().ppu_address(addr & 0x3FFF);
 
                 // However, if the address was in the palette range, the
                 // data is not delayed, so it returns immediately
@@ -74,11 +76,25 @@ impl PPU {
         }
     }
 
+    /// A rendering fetch, unlike `ppu_read` the cartridge gets to see the
+    /// address (mmc3 clocks its irq counter off A12) and can swap in its own data
+    pub(crate) fn fetch(&mut self, addr: u16, kind: PpuFetch) -> u8 {
+        let cart_data = self.cart.borrow_mut().ppu_fetch(addr & 0x3FFF, kind);
+        let data = match cart_data {
+            Some(x) => x,
+            None => self.ppu_read(addr),
+        };
+        // After the read, mmc2 switches banks once the latch tile has been fetched
+        self.cart.borrow_mut().ppu_address(addr & 0x3FFF);
+        data
+    }
+
     pub fn ppu_read(&self, addr: u16) -> u8 {
         let mut data: u8 = 0x00;
         let mut local_addr: u16 = addr & 0x3FFF;
 
-        if let Ok(x) = self.cart.borrow().ppu_read(local_addr) {
+        let cart_data = self.cart.borrow().ppu_read(local_addr);
+        if let Some(x) = cart_data {
             data = x;
         // Pattern table 1 and 2
         } else if (0x0000..=0x1FFF).contains(&local_addr) {
@@ -87,18 +103,13 @@ impl PPU {
 
         // Name tables 1-4
         } else if (0x2000..=0x3EFF).contains(&local_addr) {
-            local_addr &= 0x0FFF;
-            let mut bank: usize = 0;
-
-            match (local_addr, self.cart.borrow().mirror) {
-                (0x0000..=0x03FF, _) => {}
-                (0x0400..=0x07FF, MIRROR::VERTICAL) => bank = 1,
-                (0x0800..=0x0BFF, MIRROR::HORIZONTAL) => bank = 1,
-                (0x0C00..=0x0FFF, _) => bank = 1,
-                _ => {}
-            }
-
-            data = self.name_table[bank][(local_addr & 0x03FF) as usize];
+            local_addr = 0x2000 | (local_addr & 0x0FFF);
+            let page = self.cart.borrow().nametable_page(local_addr);
+            data = match page {
+                NametablePage::Ciram(bank) => self.name_table[bank][(local_addr & 0x03FF) as usize],
+                NametablePage::CartVram(page) => self.cart.borrow().vram_read(page, local_addr),
+                NametablePage::Mapper => self.cart.borrow().nametable_read(local_addr),
+            };
         } else if (0x3F00..=0x3FFF).contains(&local_addr) {
             local_addr &= 0x001F;
             // Blank cell mirroring for transparancy
@@ -4,6 +4,7 @@
 mod ppu_tests{
     use super::{PPU, Cartridge};
     use crate::cartridge::Rom;
+    use crate::nes::Region;
     use std::{rc::Rc, cell::RefCell};
     use tqdm::tqdm;
     
@@ -19,7 +20,7 @@ mod ppu_tests{
         let cart: Rc<RefCell<Cartridge>> = Rc::new(RefCell::new(Cartridge::from(Rom::CPUTest).unwrap()));
         
         for data in tqdm(u8::MIN..=u8::MAX) {
-            let mut ppu: PPU = PPU::new(cart.clone());
+            let mut ppu: PPU = PPU::new(cart.clone(), Region::Ntsc);
             ppu.cpu_write(0x2000, data);
     
             let data_nametable_x: u16 = ((data & CTRL_NAMETABLE_X) as u16) >> (CTRL_NAMETABLE_X.trailing_zeros());
@@ -40,7 +41,7 @@ mod ppu_tests{
         let cart: Rc<RefCell<Cartridge>> = Rc::new(RefCell::new(Cartridge::from(Rom::CPUTest).unwrap()));
         
         for data in tqdm(u8::MIN..=u8::MAX) {
-            let mut ppu: PPU = PPU::new(cart.clone());
+            let mut ppu: PPU = PPU::new(cart.clone(), Region::Ntsc);
             ppu.cpu_write(0x2001, data);
     
             assert_eq!(data, ppu.mask.get_register());
@@ -52,7 +53,7 @@ mod ppu_tests{
         let cart: Rc<RefCell<Cartridge>> = Rc::new(RefCell::new(Cartridge::from(Rom::CPUTest).unwrap()));
         
         for data in tqdm(u16::MIN..=u16::MAX) {
-            let mut ppu: PPU = PPU::new(cart.clone());
+            let mut ppu: PPU = PPU::new(cart.clone(), Region::Ntsc);
 
             ppu.cpu_write(0x2006, (data >> 8) as u8);
             ppu.cpu_write(0x2006, (data) as u8);
@@ -64,7 +65,7 @@ mod ppu_tests{
     fn test_ppu_write_register_7() {
         let cart: Rc<RefCell<Cartridge>> = Rc::new(RefCell::new(Cartridge::from(Rom::CPUTest).unwrap()));
         
-        let mut ppu: PPU = PPU::new(cart.clone());
+        let mut ppu: PPU = PPU::new(cart.clone(), Region::Ntsc);
         for i in (0..=1) {
             ppu.debug_set_ctrl_increment(i != 0);
             for data in tqdm(u8::MIN..=u8::MAX) {
@@ -1,5 +1,5 @@
 use super::{helpers::set_oam_field, PPU};
-use crate::cartridge::MIRROR;
+use crate::mapper::NametablePage;
 
 impl PPU {
     pub fn cpu_write(&mut self, addr: u16, data: u8) {
@@ -51,7 +51,9 @@ impl PPU {
                 }
             }
             0x0007 => {
-                self.ppu_write(self.vram_addr.get_register(), data);
+                let addr = self.vram_addr.get_register();
+                self.ppu_write(addr, data);
+                self.cart.borrow_mut().ppu_address(addr & 0x3FFF);
                 let mut v: u16 = 1;
                 if self.ctrl.increment_mode.get_as_value() > 0 {
                     v = 32;
@@ -65,29 +67,25 @@ impl PPU {
 
     pub fn ppu_write(&mut self, addr: u16, data: u8) {
         let mut local_addr: u16 = addr & 0x3FFF;
-        // let mut cart_steal: bool = false;
-        // if let Ok(_) = self.cart.borrow_mut().ppu_write(addr, data) {
-        //     cart_steal = true;
-        // }
-        /* else */
-        // if cart_steal {
-        // } else
-        if (0x0000..=0x1FFF).contains(&local_addr) {
+        let cart_steal = self.cart.borrow_mut().ppu_write(local_addr, data);
+        if cart_steal {
+            // Chr ram on the cartridge took it
+        } else if (0x0000..=0x1FFF).contains(&local_addr) {
             // normally a rom but adding because adding random bugs can be fun!
             self.pattern_table[((local_addr & 0x1000) >> 12) as usize]
                 [(local_addr & 0x0FFF) as usize] = data;
         } else if (0x2000..=0x3EFF).contains(&local_addr) {
-            local_addr &= 0x0FFF;
-            let mut bank: usize = 0;
-
-            match (local_addr, self.cart.borrow().mirror) {
-                (0x0000..=0x03FF, _) => {}
-                (0x0400..=0x07FF, MIRROR::VERTICAL) => bank = 1,
-                (0x0800..=0x0BFF, MIRROR::HORIZONTAL) => bank = 1,
-                (0x0C00..=0x0FFF, _) => bank = 1,
-                _ => {}
+            local_addr = 0x2000 | (local_addr & 0x0FFF);
+            let page = self.cart.borrow().nametable_page(local_addr);
+            match page {
+                NametablePage::Ciram(bank) => {
+                    self.name_table[bank][(local_addr & 0x03FF) as usize] = data
+                }
+                NametablePage::CartVram(page) => {
+                    self.cart.borrow_mut().vram_write(page, local_addr, data)
+                }
+                NametablePage::Mapper => self.cart.borrow_mut().nametable_write(local_addr, data),
             }
-            self.name_table[bank][(local_addr & 0x03FF) as usize] = data;
         } else if (0x3F00..=0x3FFF).contains(&local_addr) {
             local_addr &= 0x001F;
             // Blank cell mirroring for transparancy
@@ -119,6 +119,8 @@ impl System {
                 let mut run = true;
                 run_ui(&mut run, &mut nes, &mut state, ui); /* Run nes emulation */
                 if !run {
+                    nes.flush_save();
+                    nes.stop_trace();
                     *control_flow = ControlFlow::Exit;
                 }
 
@@ -135,7 +137,11 @@ impl System {
             Event::WindowEvent {
                 event: WindowEvent::CloseRequested,
                 ..
-            } => *control_flow = ControlFlow::Exit,
+            } => {
+                nes.flush_save();
+                nes.stop_trace();
+                *control_flow = ControlFlow::Exit
+            }
             event => {
                 let gl_window = display.gl_window();
                 platform.handle_event(imgui.io_mut(), gl_window.window(), &event);
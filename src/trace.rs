@@ -0,0 +1,159 @@
+use crate::bus::Bus;
+use crate::disassembler::decode_bytes_used;
+use cpu6502::instructions::instruction::{is_unofficial, AddressingMode::*, INSTRUCTION_LOOKUP};
+use cpu6502::Cpu6502;
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::ops::RangeInclusive;
+
+/// Which instructions make it into the trace file, a whole game's worth of
+/// trace is gigabytes so it usually pays to narrow it down
+#[derive(Debug, Clone, Default)]
+pub struct TraceFilter {
+    /// Only log instructions whose opcode sits in this range
+    pub pc_range: Option<RangeInclusive<u16>>,
+    /// Only log once the ppu has finished this many frames
+    pub start_frame: i32,
+}
+
+impl TraceFilter {
+    fn accepts(&self, pc: u16, frame: i32) -> bool {
+        frame >= self.start_frame
+            && self.pc_range.as_ref().is_none_or(|range| range.contains(&pc))
+    }
+}
+
+/// Writes a trace line for every instruction the filter lets through
+pub struct TraceLogger {
+    writer: BufWriter<File>,
+    filter: TraceFilter,
+}
+
+impl TraceLogger {
+    pub fn new(path: &str, filter: TraceFilter) -> io::Result<Self> {
+        Ok(Self {
+            writer: BufWriter::new(File::create(path)?),
+            filter,
+        })
+    }
+
+    /// Call it right before the cpu fetches its next opcode
+    pub fn log(&mut self, cpu: &mut Cpu6502<Bus>, frame: i32) -> io::Result<()> {
+        if !self.filter.accepts(cpu.pc, frame) {
+            return Ok(());
+        }
+        writeln!(self.writer, "{}", trace_line(cpu))
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// One line of a Nintendulator style trace (the format nestest.log is in) for
+/// the instruction the cpu is about to run, call it between instructions.
+///
+/// C000  4C F5 C5  JMP $C5F5                       A:00 X:00 Y:00 P:24 SP:FD PPU:  0, 21 CYC:7
+pub fn trace_line(cpu: &mut Cpu6502<Bus>) -> String {
+    let pc = cpu.pc;
+    let opcode = peek(cpu, pc);
+    let instruction = INSTRUCTION_LOOKUP[opcode as usize];
+    let bytes: Vec<u8> = (0..=decode_bytes_used(instruction.addr_mode) as u16)
+        .map(|i| peek(cpu, pc.wrapping_add(i)))
+        .collect();
+    let byte_column: Vec<String> = bytes.iter().map(|x| format!("{:02X}", x)).collect();
+
+    // Nintendulator calls ISC ISB and marks every unofficial opcode with a *
+    let name = match instruction.name {
+        "ISC" => "ISB",
+        x => x,
+    };
+    let marker = if is_unofficial(opcode) { '*' } else { ' ' };
+    let disassembly = format!("{} {}", name, operand(cpu, opcode, &bytes));
+
+    format!(
+        "{:04X}  {:<9}{}{:<32}A:{:02X} X:{:02X} Y:{:02X} P:{:02X} SP:{:02X} PPU:{:>3},{:>3} CYC:{}",
+        pc,
+        byte_column.join(" "),
+        marker,
+        disassembly.trim_end(),
+        cpu.acc,
+        cpu.x_reg,
+        cpu.y_reg,
+        cpu.status,
+        cpu.stack_pointer,
+        cpu.bus.ppu.debug_get_scanline(),
+        cpu.bus.ppu.debug_get_cycle(),
+        cpu.clock_count,
+    )
+}
+
+/// Reads without the side effects a real read would have on the ppu, apu
+/// or mapper registers, tracing must not change what the game sees
+fn peek(cpu: &mut Cpu6502<Bus>, addr: u16) -> u8 {
+    cpu.bus.cpu_read(addr, true)
+}
+
+fn peek_zero_page_word(cpu: &mut Cpu6502<Bus>, addr: u8) -> u16 {
+    let lo = peek(cpu, addr as u16) as u16;
+    let hi = peek(cpu, addr.wrapping_add(1) as u16) as u16;
+    (hi << 8) | lo
+}
+
+/// The operand along with the address it resolves to and the value there
+fn operand(cpu: &mut Cpu6502<Bus>, opcode: u8, bytes: &[u8]) -> String {
+    let byte = bytes.get(1).copied().unwrap_or(0);
+    let word = ((bytes.get(2).copied().unwrap_or(0) as u16) << 8) | byte as u16;
+    match INSTRUCTION_LOOKUP[opcode as usize].addr_mode {
+        IMP | YYY => match opcode {
+            0x0A | 0x2A | 0x4A | 0x6A => "A".to_string(),
+            _ => String::new(),
+        },
+        IMM => format!("#${:02X}", byte),
+        ZP0 => format!("${:02X} = {:02X}", byte, peek(cpu, byte as u16)),
+        ZPX | ZPY => {
+            let (index, name) = match INSTRUCTION_LOOKUP[opcode as usize].addr_mode {
+                ZPX => (cpu.x_reg, 'X'),
+                _ => (cpu.y_reg, 'Y'),
+            };
+            let addr = byte.wrapping_add(index);
+            let value = peek(cpu, addr as u16);
+            format!("${:02X},{} @ {:02X} = {:02X}", byte, name, addr, value)
+        }
+        REL => {
+            let target = cpu.pc.wrapping_add(2).wrapping_add(byte as i8 as u16);
+            format!("${:04X}", target)
+        }
+        // Jumps don't touch the memory at their target
+        ABS if opcode == 0x4C || opcode == 0x20 => format!("${:04X}", word),
+        ABS => format!("${:04X} = {:02X}", word, peek(cpu, word)),
+        ABX | ABY => {
+            let (index, name) = match INSTRUCTION_LOOKUP[opcode as usize].addr_mode {
+                ABX => (cpu.x_reg, 'X'),
+                _ => (cpu.y_reg, 'Y'),
+            };
+            let addr = word.wrapping_add(index as u16);
+            let value = peek(cpu, addr);
+            format!("${:04X},{} @ {:04X} = {:02X}", word, name, addr, value)
+        }
+        IND => {
+            // Same page wrap bug as the real thing
+            let hi_addr = (word & 0xFF00) | (word.wrapping_add(1) & 0x00FF);
+            let target = ((peek(cpu, hi_addr) as u16) << 8) | peek(cpu, word) as u16;
+            format!("(${:04X}) = {:04X}", word, target)
+        }
+        IZX => {
+            let pointer = byte.wrapping_add(cpu.x_reg);
+            let addr = peek_zero_page_word(cpu, pointer);
+            let value = peek(cpu, addr);
+            format!("(${:02X},X) @ {:02X} = {:04X} = {:02X}", byte, pointer, addr, value)
+        }
+        IZY => {
+            let base = peek_zero_page_word(cpu, byte);
+            let addr = base.wrapping_add(cpu.y_reg as u16);
+            let value = peek(cpu, addr);
+            format!("(${:02X}),Y = {:04X} @ {:04X} = {:02X}", byte, base, addr, value)
+        }
+    }
+}